@@ -0,0 +1,142 @@
+//! terminal debugger stepping through a bytecode.
+//!
+//! usage: evm-debug <code hex> [calldata hex]
+
+use ethereum_types::{Address, U256};
+use std::io::{self, BufRead, Write};
+
+use evmstar::emulator::EvmEmulator;
+use evmstar::interpreter::stack::Calldata;
+use evmstar::model::{
+    code::Code,
+    evmc::TxContext,
+    opcode::OpCode,
+    revision::Revision,
+};
+use evmstar::tracer::debugger::{Breakpoint, Command, Debugger, Paused};
+
+const HELP: &str = "\
+s, step                 execute the next instruction
+c, continue             run until a breakpoint
+b <pc>                  break at pc
+b <opcode>              break at opcode, such as SSTORE
+ws <key>                watch the storage slot of the current contract
+wm <offset> <size>      watch the memory range of the current scope
+l, list                 list breakpoints
+d <index>               delete a breakpoint
+stack                   print the stack, top first
+memory                  print the memory
+calldata                print the calldata
+bt, frames              print the call stack
+q, quit                 quit";
+
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    let usage = || -> ! {
+        eprintln!("usage: {} <code hex> [calldata hex]", args[0]);
+        std::process::exit(2);
+    };
+    let decode = |hex: &str| hex::decode(hex.trim_start_matches("0x")).unwrap_or_else(|_| usage());
+    if args.len() < 2 {
+        usage();
+    }
+    let code = Code::from(&decode(&args[1])[..]);
+    let calldata = args.get(2).map_or(Calldata::default(), |hex| Calldata::from(decode(hex)));
+    let to = Address::from_low_u64_be(0xffffeeee);
+
+    let mut debugger = Debugger::new(prompt);
+    let mut emu = EvmEmulator::new_stateful_with(TxContext::default());
+    let result = emu.with_to(to)
+        .with_code(code)
+        .mutate_scope(|scope| {
+            scope.code_address = to;
+            scope.calldata = calldata;
+        })
+        .with_default_gas()
+        .run_traced_as(Revision::Cancun, &mut debugger);
+
+    println!("status: {:?}", result.output.status_code);
+    println!("gas left: {}", result.output.gas_left);
+    println!("output: 0x{}", hex::encode(&result.output.data));
+}
+
+/// read commands until one resumes the execution.
+fn prompt(paused: &Paused, breakpoints: &mut Vec<Breakpoint>) -> Command {
+    let scope = paused.scope;
+    println!("[{:?}] depth {} pc {} {:?} gas {}", paused.reason, scope.depth, scope.pc, paused.opcode, scope.gas_left);
+
+    let stdin = io::stdin();
+    loop {
+        print!("> ");
+        let _ = io::stdout().flush();
+        let mut line = String::new();
+        if stdin.lock().read_line(&mut line).unwrap_or(0) == 0 {
+            std::process::exit(0);
+        }
+        let words: Vec<&str> = line.split_whitespace().collect();
+
+        match words.as_slice() {
+            [] => continue,
+            ["s"] | ["step"] => return Command::Step,
+            ["c"] | ["continue"] => return Command::Continue,
+            ["b", at] => match (parse_number(at), parse_opcode(at)) {
+                (Some(pc), _) => match pc.try_into() {
+                    Ok(pc) => breakpoints.push(Breakpoint::Pc(pc)),
+                    Err(_) => println!("invalid pc: {}", at),
+                },
+                (None, Some(opcode)) => breakpoints.push(Breakpoint::Opcode(opcode)),
+                (None, None) => println!("invalid pc or opcode: {}", at),
+            },
+            ["ws", key] => match parse_number(key) {
+                Some(key) => breakpoints.push(Breakpoint::Storage(scope.to, key)),
+                None => println!("invalid key: {}", key),
+            },
+            ["wm", offset, size] => match (parse_number(offset).map(usize::try_from), parse_number(size).map(usize::try_from)) {
+                (Some(Ok(offset)), Some(Ok(size))) => breakpoints.push(Breakpoint::Memory(offset, size)),
+                _ => println!("invalid range: {} {}", offset, size),
+            },
+            ["l"] | ["list"] => {
+                for (index, breakpoint) in breakpoints.iter().enumerate() {
+                    println!("{}: {:?}", index, breakpoint);
+                }
+            },
+            ["d", index] => match index.parse::<usize>() {
+                Ok(index) if index < breakpoints.len() => { breakpoints.remove(index); },
+                _ => println!("no breakpoint {}", index),
+            },
+            ["stack"] => {
+                for (index, value) in scope.stack.0.iter().rev().enumerate() {
+                    println!("{:>4}: 0x{:x}", index, value);
+                }
+            },
+            ["memory"] => {
+                for (index, word) in scope.memory.0.chunks(32).enumerate() {
+                    println!("0x{:04x}: {}", index * 32, hex::encode(word));
+                }
+            },
+            ["calldata"] => println!("0x{}", hex::encode(&scope.calldata.0)),
+            ["bt"] | ["frames"] => {
+                for frame in paused.frames.iter().rev() {
+                    println!("{:?} {:?} from {:?} pc {} depth {}", frame.kind, frame.code_address, frame.caller, frame.pc, frame.depth);
+                }
+            },
+            ["q"] | ["quit"] => std::process::exit(0),
+            ["h"] | ["help"] => println!("{}", HELP),
+            _ => println!("unknown command, h for help"),
+        }
+    }
+}
+
+fn parse_number(word: &str) -> Option<U256> {
+    match word.strip_prefix("0x") {
+        Some(hex) => U256::from_str_radix(hex, 16).ok(),
+        None => U256::from_dec_str(word).ok(),
+    }
+}
+
+fn parse_opcode(word: &str) -> Option<OpCode> {
+    let word = word.to_uppercase();
+    (0..=u8::MAX)
+        .filter_map(OpCode::from_u8)
+        .find(|opcode| format!("{:?}", opcode) == word)
+}
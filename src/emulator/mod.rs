@@ -1,13 +1,14 @@
 use std::{rc::Rc, cell::RefCell, ops::FnOnce};
 
 use bytes::Bytes;
-use ethereum_types::{Address, U256};
+use ethereum_types::{Address, H256, U256};
 use hex::decode;
 
 use crate::{
-    model::{code::Code, evmc::{Output, StatusCode, TxContext, AccessList}, revision::Revision},
+    model::{code::Code, evmc::{Output, StatusCode, TxContext, AccessList}, fee::DynamicFee, revert::{PanicCode, RevertReason}, revision::Revision, srcmap::{SourceLocation, Sources}, transaction::Transaction},
     executor::{callstack::CallScope, executor::Executor},
-    host::{stateful::StatefulHost, Host, transient::TransientHost}
+    host::{stateful::StatefulHost, Host, transient::TransientHost},
+    tracer::{FailureLocator, NoopTracer, Tracer},
 };
 
 pub struct EvmEmulator {
@@ -16,18 +17,24 @@ pub struct EvmEmulator {
 
     is_execution_cost_enabled: bool,
     access_list: AccessList,
+    dynamic_fee: Option<DynamicFee>,
+    sources: Sources,
 }
 
 pub struct EvmResult {
     host: Rc<RefCell<dyn Host>>,
     scope: CallScope,
     pub output: Output,
+    /// code hash and pc of the instruction the execution failed or reverted at.
+    pub failed_at: Option<(H256, usize)>,
+    /// where the execution failed or reverted in the source, if the source map of the code is known.
+    pub source_location: Option<SourceLocation>,
 }
 
 impl EvmResult {
     pub fn expect_output<'a>(&'a self, hex: &str) -> &'a Self {
         let data = decode(hex).unwrap();
-        assert_eq!(Bytes::from(data), self.output.data);
+        assert_eq!(Bytes::from(data), self.output.data, "{}", self.failure_message());
         self
     }
     pub fn expect_gas<'a>(&'a self, gas: i64) -> &'a Self {
@@ -39,7 +46,7 @@ impl EvmResult {
         self
     }
     pub fn expect_status<'a>(&'a self, status_code: StatusCode) -> &'a Self {
-        assert_eq!(status_code, self.output.status_code);
+        assert_eq!(status_code, self.output.status_code, "{}", self.failure_message());
         self
     }
     pub fn expect_storage<'a>(&'a self, address: Address, key: U256, expected_value: U256) -> &'a Self {
@@ -52,6 +59,31 @@ impl EvmResult {
         assert_eq!(value, balance);
         self
     }
+    pub fn expect_burnt<'a>(&'a self, amount: U256) -> &'a Self {
+        let value = (*self.host).borrow().get_burnt_amount();
+        assert_eq!(amount, value);
+        self
+    }
+    /// revert with `Error(string)` of `reason`.
+    pub fn expect_revert_reason<'a>(&'a self, reason: &str) -> &'a Self {
+        assert_eq!(Some(RevertReason::Error(reason.to_string())), self.output.revert_reason(), "{}", self.failure_message());
+        self
+    }
+    /// revert with `Panic(uint256)` of `code`.
+    pub fn expect_panic<'a>(&'a self, code: PanicCode) -> &'a Self {
+        let panic = self.output.revert_reason().and_then(|reason| reason.panic_code());
+        assert_eq!(Some(code), panic, "{}", self.failure_message());
+        self
+    }
+
+    /// where the execution failed, in the source if known.
+    fn failure_message(&self) -> String {
+        match (&self.source_location, self.failed_at) {
+            (Some(location), _) => format!("failed at {}", location),
+            (None, Some((code_hash, pc))) => format!("failed at pc {} of code {:?}", pc, code_hash),
+            (None, None) => String::new(),
+        }
+    }
 }
 
 impl EvmEmulator {
@@ -63,6 +95,8 @@ impl EvmEmulator {
             host,
             is_execution_cost_enabled: false,
             access_list: AccessList::default(),
+            dynamic_fee: None,
+            sources: Sources::default(),
         }
     }
 
@@ -74,6 +108,8 @@ impl EvmEmulator {
             host,
             is_execution_cost_enabled: false,
             access_list: AccessList::default(),
+            dynamic_fee: None,
+            sources: Sources::default(),
         }
     }
 
@@ -149,18 +185,39 @@ impl EvmEmulator {
         self
     }
 
+    /// charge transaction fee as a dynamic fee transaction.
+    pub fn with_dynamic_fee<'a>(&'a mut self, max_fee_per_gas: U256, max_priority_fee_per_gas: U256) -> &'a mut Self {
+        self.dynamic_fee = Some(DynamicFee::new(max_fee_per_gas, max_priority_fee_per_gas));
+        self
+    }
+
+    /// source files and source maps to locate failures in.
+    pub fn with_sources<'a>(&'a mut self, sources: Sources) -> &'a mut Self {
+        self.sources = sources;
+        self
+    }
+
+    /// hash BLOCKHASH returns for `number` while it is within the last 256 blocks.
+    pub fn with_block_hash<'a>(&'a mut self, number: u64, hash: H256) -> &'a mut Self {
+        (*self.host).borrow_mut().set_block_hash(number, hash);
+        self
+    }
+
     pub fn run(&mut self) -> EvmResult {
         self.run_as(Revision::Shanghai)
     }
 
     pub fn run_as(&mut self, revision: Revision) -> EvmResult {
-        let mut executor = 
-            if !self.is_execution_cost_enabled {
-                Executor::new_with_tracing(self.host.clone())
-            } else {
-                Executor::new_with_execution_cost(self.host.clone(), true, revision)
-            };
-        executor.set_revision(revision);
+        self.run_traced_as(revision, &mut NoopTracer)
+    }
+
+    /// run while `tracer` observes the execution.
+    pub fn run_traced_as<T: Tracer>(&mut self, revision: Revision, tracer: &mut T) -> EvmResult {
+        let mut locator = FailureLocator::default();
+        let mut executor = self.executor(revision).with_tracer((tracer, &mut locator));
+        if let Some(fee) = self.dynamic_fee {
+            executor.set_dynamic_fee(fee);
+        }
 
         let output = 
             if self.access_list.is_empty() {
@@ -168,13 +225,75 @@ impl EvmEmulator {
             } else {
                 executor.execute_with_access_list(self.scope.clone(), self.access_list.clone())
             };
+        self.result(self.scope.clone(), output, locator)
+    }
+
+    /// run a message call transaction sent by `sender`.
+    pub fn run_transaction(&mut self, tx: &Transaction, sender: Address) -> EvmResult {
+        self.run_transaction_as(tx, sender, Revision::Shanghai)
+    }
+
+    pub fn run_transaction_as(&mut self, tx: &Transaction, sender: Address, revision: Revision) -> EvmResult {
+        let mut locator = FailureLocator::default();
+        let output = self.executor(revision)
+            .with_tracer(&mut locator)
+            .execute_transaction(tx, sender);
+        // the next transaction starts with cold accounts and storage, as in a block.
+        (*self.host).borrow_mut().commit();
+
+        let scope = CallScope {
+            gas_limit: tx.gas_limit() as i64,
+            ..CallScope::default()
+        };
+        self.result(scope, output, locator)
+    }
+
+    /// run a signed transaction, recovering the sender from its signature.
+    pub fn run_signed_transaction(&mut self, tx: &Transaction) -> EvmResult {
+        self.run_signed_transaction_as(tx, Revision::Shanghai)
+    }
+
+    pub fn run_signed_transaction_as(&mut self, tx: &Transaction, revision: Revision) -> EvmResult {
+        let mut locator = FailureLocator::default();
+        let output = self.executor(revision)
+            .with_tracer(&mut locator)
+            .execute_signed_transaction(tx);
+        // the next transaction starts with cold accounts and storage, as in a block.
+        (*self.host).borrow_mut().commit();
+
+        let scope = CallScope {
+            gas_limit: tx.gas_limit() as i64,
+            ..CallScope::default()
+        };
+        self.result(scope, output, locator)
+    }
+
+    fn result(&self, scope: CallScope, output: Output, locator: FailureLocator) -> EvmResult {
+        let failed_at = match output.status_code {
+            StatusCode::Success => None,
+            StatusCode::Failure(_) => locator.location,
+        };
+        let source_location = failed_at.and_then(|(code_hash, pc)| self.sources.locate(&code_hash, pc));
         EvmResult{
             host: self.host.clone(),
-            scope: self.scope.clone(),
-            output
+            scope,
+            output,
+            failed_at,
+            source_location,
         }
     }
 
+    fn executor(&self, revision: Revision) -> Executor {
+        let mut executor = 
+            if !self.is_execution_cost_enabled {
+                Executor::new(self.host.clone())
+            } else {
+                Executor::new_with_execution_cost(self.host.clone(), revision)
+            };
+        executor.set_revision(revision);
+        executor
+    }
+
     pub fn run_code(&mut self, code: Code) -> EvmResult {
         self.scope.code = code;
         self.run()
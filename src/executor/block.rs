@@ -0,0 +1,274 @@
+use ethereum_types::{Address, Bloom, H256, U256};
+
+use std::rc::Rc;
+use std::cell::RefCell;
+
+use crate::host::Host;
+use crate::executor::callstack::CallScope;
+use crate::executor::executor::Executor;
+use crate::model::{
+    evmc::{FailureKind, Output, StatusCode},
+    fee::MAX_BLOB_GAS_PER_BLOCK,
+    receipt::{receipts_root, Receipt},
+    revision::Revision,
+    system::{BEACON_ROOTS_ADDRESS, HISTORY_STORAGE_ADDRESS, SYSTEM_ADDRESS, SYSTEM_CALL_GAS},
+    transaction::Transaction,
+    withdrawal::Withdrawal,
+};
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum BlockError {
+    /// the transaction at `index` does not fit in the gas left in the block.
+    GasLimitExceeded { index: usize },
+    /// the transaction at `index` does not fit in the blob gas left in the block.
+    BlobGasLimitExceeded { index: usize },
+    /// the transaction at `index` cannot be included, e.g. its signature is invalid or its sender cannot pay for it.
+    InvalidTransaction { index: usize, kind: FailureKind },
+    /// the operation is not enabled in the revision, e.g. withdrawals before Shanghai.
+    UnsupportedRevision,
+    /// the system call to a system contract failed.
+    SystemCallFailed(FailureKind),
+}
+
+/// uncle block header included in a pre-merge block.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Ommer {
+    pub coinbase: Address,
+    pub block_number: i64,
+}
+
+/// block reward paid to the coinbase in `revision`.
+///
+/// https://eips.ethereum.org/EIPS/eip-649
+/// https://eips.ethereum.org/EIPS/eip-1234
+pub fn default_block_reward(revision: Revision) -> U256 {
+    let ether = U256::exp10(18);
+    if revision >= Revision::Shanghai {
+        // no reward after the merge.
+        U256::zero()
+    }else if revision >= Revision::Constantinople {
+        ether * 2
+    }else if revision >= Revision::Byzantium {
+        ether * 3
+    }else{
+        ether * 5
+    }
+}
+
+/// receipts and totals of the transactions applied so far.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct BlockOutput {
+    pub receipts: Vec<Receipt>,
+    pub gas_used: u64,
+    pub blob_gas_used: u64,
+    /// ether burnt by the block, i.e. the base fee and blob fee portion of transaction fees.
+    pub burnt: U256,
+    pub logs_bloom: Bloom,
+    pub receipts_root: H256,
+    /// state root after the block.
+    pub state_root: H256,
+}
+
+/// applies transactions of a block in order against a single host.
+///
+/// the block gas limit is `TxContext::gas_limit` of the host.
+pub struct BlockExecutor {
+    host: Rc<RefCell<dyn Host>>,
+    revision: Revision,
+    output: BlockOutput,
+    burnt_before: U256,
+    block_reward: U256,
+}
+
+impl BlockExecutor {
+    pub fn new(host: Rc<RefCell<dyn Host>>, revision: Revision) -> Self {
+        let burnt_before = (*host).borrow().get_burnt_amount();
+        Self {
+            host,
+            revision,
+            output: BlockOutput::default(),
+            burnt_before,
+            block_reward: default_block_reward(revision),
+        }
+    }
+
+    /// override the block reward, which is `default_block_reward()` of the revision by default.
+    pub fn set_block_reward(&mut self, reward: U256) {
+        self.block_reward = reward;
+    }
+
+    /// apply a transaction sent by `sender`.
+    ///
+    /// on error, the transaction is not included and the state is left as it was before it.
+    pub fn apply_transaction(&mut self, tx: &Transaction, sender: Address) -> Result<&Receipt, BlockError> {
+        self.apply(tx, |executor| executor.execute_transaction(tx, sender))
+    }
+
+    /// apply a signed transaction, recovering the sender from its signature.
+    pub fn apply_signed_transaction(&mut self, tx: &Transaction) -> Result<&Receipt, BlockError> {
+        self.apply(tx, |executor| executor.execute_signed_transaction(tx))
+    }
+
+    /// credit withdrawals to their addresses. no gas is charged and no code is executed.
+    ///
+    /// https://eips.ethereum.org/EIPS/eip-4895
+    pub fn apply_withdrawals(&mut self, withdrawals: &[Withdrawal]) -> Result<(), BlockError> {
+        if self.revision < Revision::Shanghai {
+            return Err(BlockError::UnsupportedRevision);
+        }
+
+        let mut host = (*self.host).borrow_mut();
+        // a zero withdrawal does not create an empty account.
+        for withdrawal in withdrawals.iter().filter(|withdrawal| withdrawal.amount > 0) {
+            host.add_balance(withdrawal.address, withdrawal.amount_in_wei());
+        }
+        host.commit();
+        Ok(())
+    }
+
+    /// pay the block reward to the coinbase and the ommer rewards to the coinbases of `ommers`.
+    ///
+    /// the coinbase receives an extra 1/32 of the block reward per ommer,
+    /// and an ommer `n` blocks older than this block receives `(8 - n) / 8` of the block reward.
+    pub fn apply_block_reward(&mut self, ommers: &[Ommer]) {
+        let reward = self.block_reward;
+        let mut host = (*self.host).borrow_mut();
+        let context = host.get_tx_context();
+
+        // a zero reward, e.g. after the merge, does not create an empty account.
+        if reward.is_zero() {
+            return;
+        }
+
+        let coinbase_reward = reward + reward / 32 * U256::from(ommers.len());
+        host.add_balance(context.coinbase, coinbase_reward);
+        for ommer in ommers {
+            let distance = context.block_number - ommer.block_number;
+            if (1..8).contains(&distance) {
+                host.add_balance(ommer.coinbase, reward * U256::from(8 - distance) / 8);
+            }
+        }
+        host.commit();
+    }
+
+    /// store the parent beacon block root in the beacon roots contract before any transaction.
+    ///
+    /// nothing happens if the contract is not deployed.
+    /// https://eips.ethereum.org/EIPS/eip-4788
+    pub fn apply_beacon_root(&mut self, parent_beacon_block_root: H256) -> Result<(), BlockError> {
+        if self.revision < Revision::Cancun {
+            return Err(BlockError::UnsupportedRevision);
+        }
+        self.system_call(BEACON_ROOTS_ADDRESS, parent_beacon_block_root.as_bytes())
+    }
+
+    /// store the parent block hash in the history storage contract before any transaction.
+    ///
+    /// nothing happens if the contract is not deployed.
+    /// https://eips.ethereum.org/EIPS/eip-2935
+    pub fn apply_parent_hash(&mut self, parent_hash: H256) -> Result<(), BlockError> {
+        if self.revision < Revision::Prague {
+            return Err(BlockError::UnsupportedRevision);
+        }
+        self.system_call(HISTORY_STORAGE_ADDRESS, parent_hash.as_bytes())
+    }
+
+    pub fn receipts(&self) -> &[Receipt] {
+        &self.output.receipts
+    }
+
+    /// finish the block, returning receipts and block-level totals.
+    pub fn finish(mut self) -> BlockOutput {
+        let host = (*self.host).borrow();
+        self.output.burnt = host.get_burnt_amount() - self.burnt_before;
+        self.output.receipts_root = receipts_root(&self.output.receipts);
+        self.output.state_root = host.state_root();
+        drop(host);
+        self.output
+    }
+
+    /// call `address` from `SYSTEM_ADDRESS` without charging gas nor counting it in the block.
+    fn system_call(&mut self, address: Address, input: &[u8]) -> Result<(), BlockError> {
+        let code = {
+            let host = (*self.host).borrow();
+            let code_size = host.get_code_size(address);
+            host.get_code(address, 0, code_size.as_usize())
+        };
+        if code.is_empty() {
+            return Ok(());
+        }
+
+        let scope = CallScope {
+            origin: SYSTEM_ADDRESS,
+            caller: SYSTEM_ADDRESS,
+            to: address,
+            code_address: address,
+            code: code.into(),
+            calldata: input.to_vec().into(),
+            gas_limit: SYSTEM_CALL_GAS,
+            gas_left: SYSTEM_CALL_GAS,
+            ..CallScope::default()
+        };
+        let mut executor = Executor::new_with(self.host.clone(), self.revision);
+        let output = executor.execute_raw_with(scope);
+        {
+            let mut host = (*self.host).borrow_mut();
+            // the zero value transfer must not leave `SYSTEM_ADDRESS` behind as an empty account.
+            host.clear_empty_accounts();
+            host.commit();
+        }
+
+        match output.status_code {
+            StatusCode::Success => Ok(()),
+            StatusCode::Failure(kind) => Err(BlockError::SystemCallFailed(kind)),
+        }
+    }
+
+    fn apply<F>(&mut self, tx: &Transaction, execute: F) -> Result<&Receipt, BlockError>
+        where F: FnOnce(&mut Executor) -> Output
+    {
+        let index = self.output.receipts.len();
+
+        let block_gas_limit = (*self.host).borrow().get_tx_context().gas_limit.max(0) as u64;
+        match self.output.gas_used.checked_add(tx.gas_limit()) {
+            Some(gas) if gas <= block_gas_limit => (),
+            Some(_) => return Err(BlockError::GasLimitExceeded { index }),
+            None => return Err(BlockError::InvalidTransaction { index, kind: FailureKind::Rejected }),
+        }
+        let blob_gas = tx.blob_fee().map_or(0, |fee| fee.blob_gas());
+        if self.output.blob_gas_used + blob_gas > MAX_BLOB_GAS_PER_BLOCK {
+            return Err(BlockError::BlobGasLimitExceeded { index });
+        }
+
+        let mut executor = Executor::new_with_execution_cost(self.host.clone(), self.revision);
+        let output = execute(&mut executor);
+
+        let mut host = (*self.host).borrow_mut();
+        if let StatusCode::Failure(kind) = &output.status_code {
+            match kind {
+                FailureKind::Rejected | FailureKind::InsufficientBalance | FailureKind::InvalidNonce | FailureKind::Generic(_) => {
+                    host.commit();
+                    return Err(BlockError::InvalidTransaction { index, kind: kind.clone() });
+                },
+                _ => (),
+            }
+        }
+
+        let gas_left = output.gas_left.max(0) + output.effective_gas_refund;
+        let gas_used = tx.gas_limit() - gas_left as u64;
+        self.output.gas_used += gas_used;
+        self.output.blob_gas_used += blob_gas;
+
+        // a failed transaction emits no logs, as its execution is rolled back.
+        let logs = match output.status_code {
+            StatusCode::Success => host.get_logs(),
+            StatusCode::Failure(_) => vec![],
+        };
+        let receipt = Receipt::new(tx.tx_type(), output.status_code, gas_used, self.output.gas_used, logs);
+        host.commit();
+
+        self.output.logs_bloom.accrue_bloom(&receipt.logs_bloom);
+        self.output.receipts.push(receipt);
+        Ok(self.output.receipts.last().unwrap())
+    }
+}
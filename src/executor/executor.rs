@@ -1,4 +1,5 @@
 use bytes::Bytes;
+use ethereum_types::{Address, U256};
 use std::cmp::min;
 
 use std::rc::Rc;
@@ -8,6 +9,8 @@ use crate::host::Host;
 use crate::executor::callstack::{
     CallStack, CallScope, ExecutionContext
 };
+use crate::executor::precompiles;
+use crate::tracer::{NoopTracer, Tracer};
 use crate::interpreter::{CallParams, CallKind, ExitKind};
 use crate::interpreter::stack::{Calldata};
 use crate::interpreter::{
@@ -19,11 +22,14 @@ use crate::interpreter::{
 use crate::model::{
     evmc::*,
     code::Code,
+    fee::{BlobFee, DynamicFee, validate_versioned_hashes},
+    precompiles::{is_precompile, num_of_precompiles},
     revision::Revision,
+    transaction::Transaction,
 };
 
 #[allow(dead_code)]
-pub struct Executor {
+pub struct Executor<T: Tracer = NoopTracer> {
     host: Rc<RefCell<dyn Host>>,
     interpreter: Interpreter,
     callstack: Box<CallStack>,
@@ -31,7 +37,15 @@ pub struct Executor {
 
     /// if true, gas cost outside of EVM opcode, such as intrinsic cost, calldata cost and access list cost,
     /// will be charged.
-    is_execution_cost_on: bool
+    is_execution_cost_on: bool,
+
+    /// if set, transaction fee is charged to the caller as specified in EIP-1559.
+    dynamic_fee: Option<DynamicFee>,
+
+    /// if set together with `dynamic_fee`, blob gas is charged to the caller as specified in EIP-4844.
+    blob_fee: Option<BlobFee>,
+
+    tracer: T,
 }
 
 const MAX_CODE_SIZE: usize = 0x6000;
@@ -47,48 +61,75 @@ impl Executor {
             callstack: Box::new(CallStack::default()),
             revision: Revision::Shanghai,
             is_execution_cost_on: false,
+            dynamic_fee: None,
+            blob_fee: None,
+            tracer: NoopTracer,
         }
     }
-    pub fn new_with_tracing(host: Rc<RefCell<dyn Host>>) -> Self {
+    pub fn new_with(host: Rc<RefCell<dyn Host>>, revision: Revision) -> Self {
         Self {
             host: host,
-            interpreter: Interpreter::new_with_tracing(),
-            callstack: Box::new(CallStack::default()),
-            revision: Revision::Shanghai,
-            is_execution_cost_on: false,
-        }
-    }
-    pub fn new_with(host: Rc<RefCell<dyn Host>>, is_trace: bool, revision: Revision) -> Self {
-        Self {
-            host: host,
-            interpreter: match is_trace {
-                true => Interpreter::new_with_tracing(),
-                false => Interpreter::default()
-            },
+            interpreter: Interpreter::default(),
             callstack: Box::new(CallStack::default()),
             revision: revision,
             is_execution_cost_on: false,
+            dynamic_fee: None,
+            blob_fee: None,
+            tracer: NoopTracer,
         }
     }
 
     /// gas cost that is not related to EVM opcode, such as intrinsic cost, calldata cost and access list cost, will be charged.
-    pub fn new_with_execution_cost(host: Rc<RefCell<dyn Host>>, is_trace: bool, revision: Revision) -> Self {
+    pub fn new_with_execution_cost(host: Rc<RefCell<dyn Host>>, revision: Revision) -> Self {
         Self {
             host: host,
-            interpreter: match is_trace {
-                true => Interpreter::new_with_tracing(),
-                false => Interpreter::default()
-            },
+            interpreter: Interpreter::default(),
             callstack: Box::new(CallStack::default()),
             revision: revision,
             is_execution_cost_on: true,
+            dynamic_fee: None,
+            blob_fee: None,
+            tracer: NoopTracer,
         }
     }
+}
+
+impl<T: Tracer> Executor<T> {
+    /// trace the execution with `tracer`.
+    /// pass `&mut tracer` to keep hold of the tracer.
+    pub fn with_tracer<U: Tracer>(self, tracer: U) -> Executor<U> {
+        Executor {
+            host: self.host,
+            interpreter: self.interpreter,
+            callstack: self.callstack,
+            revision: self.revision,
+            is_execution_cost_on: self.is_execution_cost_on,
+            dynamic_fee: self.dynamic_fee,
+            blob_fee: self.blob_fee,
+            tracer,
+        }
+    }
+
+    pub fn tracer(&self) -> &T {
+        &self.tracer
+    }
+
+    pub fn into_tracer(self) -> T {
+        self.tracer
+    }
 
     pub fn set_revision(&mut self, revision: Revision) {
         self.revision = revision;
     }
 
+    pub fn set_dynamic_fee(&mut self, fee: DynamicFee) {
+        self.dynamic_fee = Some(fee);
+    }
+
+    pub fn set_blob_fee(&mut self, fee: BlobFee) {
+        self.blob_fee = Some(fee);
+    }
+
     pub fn call_message(&mut self, msg: &Message) -> Output {
         (*self.host).borrow_mut().call(msg)
     }
@@ -127,7 +168,221 @@ impl Executor {
         self.execute_raw_with(scope)
     }
 
-    pub fn execute_raw_with(&mut self, mut scope: CallScope) -> Output {
+    /// execute a signed transaction, recovering the sender from its signature.
+    /// 
+    /// transaction with invalid signature, or signed for a chain other than `TxContext::chain_id`, is rejected.
+    /// EIP-155 replay protected transaction is rejected before Spurious Dragon.
+    pub fn execute_signed_transaction(&mut self, tx: &Transaction) -> Output {
+        if let Transaction::Legacy(legacy) = tx {
+            if legacy.chain_id().is_some() && self.revision < Revision::Spurious {
+                return Output::new_failure(FailureKind::Rejected, 0);
+            }
+        }
+
+        let chain_id = (*self.host).borrow().get_tx_context().chain_id;
+        match tx.recover_sender(chain_id) {
+            Ok(sender) => self.execute_transaction(tx, sender),
+            Err(_) => Output::new_failure(FailureKind::Rejected, 0),
+        }
+    }
+
+    /// execute a message call transaction sent by `sender`.
+    /// 
+    /// fee is charged as specified in EIP-1559 from London onward, and at the gas price before.
+    /// blob transaction is accepted from Cancun onward.
+    /// the nonce of `sender` is incremented, and touched empty accounts are deleted from Spurious Dragon onward.
+    pub fn execute_transaction(&mut self, tx: &Transaction, sender: Address) -> Output {
+        let to = match tx.to() {
+            Some(to) => to,
+            None => return Output::new_failure(FailureKind::Generic("contract creation is not supported".to_string()), 0),
+        };
+
+        let mut scope = CallScope {
+            origin: sender,
+            caller: sender,
+            to,
+            code_address: to,
+            value: tx.value(),
+            calldata: tx.data().to_vec().into(),
+            gas_limit: tx.gas_limit() as i64,
+            gas_left: tx.gas_limit() as i64,
+            ..CallScope::default()
+        };
+        {
+            let mut host = (*self.host).borrow_mut();
+            let code_size = host.get_code_size(to);
+            scope.code = host.get_code(to, 0, code_size.as_usize()).into();
+
+            let mut tx_context = host.get_tx_context();
+            tx_context.origin = sender;
+            if let Some(gas_price) = tx.gas_price() {
+                tx_context.gas_price = gas_price;
+            }
+            tx_context.blob_hashes = tx.blob_versioned_hashes().to_vec();
+            host.set_tx_context(tx_context);
+        }
+
+        // the fees of a transaction executed before do not carry over.
+        self.dynamic_fee = Some(tx.dynamic_fee());
+        // only a legacy gas price is known before London.
+        if self.revision < Revision::London && tx.gas_price().is_none() {
+            return Output::new_failure(FailureKind::Rejected, 0);
+        }
+        self.blob_fee = tx.blob_fee();
+        if self.blob_fee.is_some() {
+            if self.revision < Revision::Cancun {
+                return Output::new_failure(FailureKind::Rejected, 0);
+            }
+            if let Err(kind) = validate_versioned_hashes(tx.blob_versioned_hashes()) {
+                return Output::new_failure(kind, 0);
+            }
+        }
+
+        // a transaction which cannot even pay its intrinsic gas is invalid, rather than failed.
+        if self.is_execution_cost_on && (tx.gas_limit() as i64) < intrinsic_gas(tx, &scope.calldata, self.revision) {
+            return Output::new_failure(FailureKind::Rejected, 0);
+        }
+
+        // https://eips.ethereum.org/EIPS/eip-2681
+        let nonce = (*self.host).borrow().get_nonce(sender);
+        if nonce == u64::MAX || tx.nonce() == u64::MAX {
+            return Output::new_failure(FailureKind::Rejected, 0);
+        }
+        if tx.nonce() != nonce {
+            return Output::new_failure(FailureKind::InvalidNonce, 0);
+        }
+        let snapshot = {
+            let mut host = (*self.host).borrow_mut();
+            let snapshot = host.take_snapshot();
+            host.set_nonce(sender, nonce + 1);
+            snapshot
+        };
+
+        let access_list = tx.access_list();
+        let output = if access_list.is_empty() {
+            self.execute_raw_with(scope)
+        }else{
+            self.execute_with_access_list(scope, access_list)
+        };
+
+        let mut host = (*self.host).borrow_mut();
+        match output.status_code {
+            // invalid transaction leaves no trace, not even the nonce.
+            StatusCode::Failure(FailureKind::Rejected) | StatusCode::Failure(FailureKind::InsufficientBalance) => {
+                host.rollback(&snapshot);
+            },
+            _ => {
+                if self.revision >= Revision::Spurious {
+                    host.clear_empty_accounts();
+                }
+            },
+        }
+        output
+    }
+
+    pub fn execute_raw_with(&mut self, scope: CallScope) -> Output {
+        let output = match self.dynamic_fee {
+            Some(fee) => self.execute_with_dynamic_fee(scope, fee),
+            None => self.execute_scope(scope),
+        };
+        // the fee payment after the execution reads state as well.
+        match (*self.host).borrow().failure() {
+            Some(kind) => Output::new_failure(kind, 0),
+            None => output,
+        }
+    }
+
+    /// execute as a dynamic fee transaction.
+    /// 
+    /// gas is bought upfront at the effective gas price, unused gas is refunded to the caller,
+    /// the priority fee is paid to the coinbase and the base fee portion is burnt.
+    /// blob gas, if any, is bought at the blob base fee and burnt as a whole.
+    /// 
+    /// before London, there is no base fee, and only a gas price, i.e. equal fee caps, is accepted.
+    /// the whole fee is then paid to the coinbase.
+    /// 
+    /// https://eips.ethereum.org/EIPS/eip-1559
+    /// https://eips.ethereum.org/EIPS/eip-4844
+    fn execute_with_dynamic_fee(&mut self, scope: CallScope, fee: DynamicFee) -> Output {
+        let mut tx_context = (*self.host).borrow().get_tx_context();
+        let base_fee = if self.revision >= Revision::London {
+            tx_context.base_fee
+        }else{
+            if fee.max_fee_per_gas != fee.max_priority_fee_per_gas {
+                return Output::new_failure(FailureKind::Rejected, 0);
+            }
+            U256::zero()
+        };
+        if let Err(kind) = fee.validate(base_fee) {
+            return Output::new_failure(kind, 0);
+        }
+
+        let blob_base_fee = tx_context.blob_base_fee();
+        let (blob_gas, max_fee_per_blob_gas) = match self.blob_fee {
+            Some(blob_fee) => {
+                if self.revision < Revision::Cancun {
+                    return Output::new_failure(FailureKind::Rejected, 0);
+                }
+                if let Err(kind) = blob_fee.validate(blob_base_fee) {
+                    return Output::new_failure(kind, 0);
+                }
+                (U256::from(blob_fee.blob_gas()), blob_fee.max_fee_per_blob_gas)
+            },
+            None => (U256::zero(), U256::zero()),
+        };
+
+        let gas_limit = U256::from(scope.gas_limit);
+        let max_blob_cost = blob_gas.checked_mul(max_fee_per_blob_gas);
+        let max_cost = gas_limit.checked_mul(fee.max_fee_per_gas)
+            .and_then(|cost| cost.checked_add(scope.value))
+            .and_then(|cost| max_blob_cost.and_then(|blob_cost| cost.checked_add(blob_cost)));
+        let max_cost = match max_cost {
+            Some(cost) => cost,
+            None => return Output::new_failure(FailureKind::Rejected, 0),
+        };
+        if (*self.host).borrow().get_balance(scope.caller) < max_cost {
+            return Output::new_failure(FailureKind::InsufficientBalance, 0);
+        }
+
+        let gas_price = fee.effective_gas_price(base_fee);
+        let priority_fee = fee.priority_fee_per_gas(base_fee);
+        {
+            let mut host = (*self.host).borrow_mut();
+
+            // GASPRICE returns the effective gas price.
+            tx_context.gas_price = gas_price;
+            host.set_tx_context(tx_context.clone());
+
+            // buy gas. this must survive the revert of the execution itself.
+            host.subtract_balance(scope.caller, gas_limit * gas_price);
+
+            // blob gas is never refunded.
+            let blob_cost = blob_gas * blob_base_fee;
+            host.subtract_balance(scope.caller, blob_cost);
+            host.burn(blob_cost);
+        }
+
+        let caller = scope.caller;
+        let scope_gas_limit = scope.gas_limit;
+        let output = self.execute_scope(scope);
+
+        let gas_left = output.gas_left.max(0) + output.effective_gas_refund;
+        let gas_used = U256::from(scope_gas_limit - gas_left);
+        {
+            let mut host = (*self.host).borrow_mut();
+            host.add_balance(caller, U256::from(gas_left) * gas_price);
+            // a zero fee does not touch the coinbase.
+            let fee = gas_used * priority_fee;
+            if !fee.is_zero() {
+                host.add_balance(tx_context.coinbase, fee);
+            }
+            host.burn(gas_used * base_fee);
+        }
+
+        output
+    }
+
+    fn execute_scope(&mut self, mut scope: CallScope) -> Output {
         let mut exec_context = ExecutionContext {
             refund_counter: 0,
             revision: self.revision,
@@ -149,8 +404,18 @@ impl Executor {
             // accessed_addresses is initialized to include
             // the tx.sender, tx.to (or the address being created if it is a contract creation transaction)
             // and the set of all precompiles.
-            (*self.host).borrow_mut().access_account(scope.to);
-            (*self.host).borrow_mut().access_account(scope.caller);
+            let mut host = (*self.host).borrow_mut();
+            host.access_account(scope.to);
+            host.access_account(scope.caller);
+            for i in 1..=num_of_precompiles(self.revision) {
+                host.access_account(Address::from_low_u64_be(i as u64));
+            }
+
+            // https://eips.ethereum.org/EIPS/eip-3651
+            if self.revision >= Revision::Shanghai {
+                let coinbase = host.get_tx_context().coinbase;
+                host.access_account(coinbase);
+            }
         }
 
         if self.is_execution_cost_on {
@@ -166,18 +431,33 @@ impl Executor {
             }
         }
 
-        (*self.host).borrow_mut().subtract_balance(scope.caller, scope.value);
-        (*self.host).borrow_mut().add_balance(scope.to, scope.value);
+        {
+            let mut host = (*self.host).borrow_mut();
+            // the transfer and the execution are undone together if the execution fails.
+            scope.snapshot = host.take_snapshot();
+            host.subtract_balance(scope.caller, scope.value);
+            host.add_balance(scope.to, scope.value);
+        }
 
+        self.tracer.call_enter(CallKind::Call, &scope);
         self.callstack.push(scope.clone()).unwrap();
 
         let mut resume = Resume::Init;
         loop {
             let interrupt = {
                 let mut current_scope = self.callstack.peek().borrow_mut(); // current scope is top of the callstack.
-                let interrupt = self.interpreter.resume_interpret(resume, &mut current_scope, &mut exec_context, self.host.clone());
+                let interrupt = self.interpreter.resume_interpret(resume, &mut current_scope, &mut exec_context, self.host.clone(), &mut self.tracer);
                 interrupt
             };
+
+            // state read by the scope is not to be trusted, so neither is anything after it.
+            let failure = (*self.host).borrow().failure();
+            if let Some(failure_kind) = failure {
+                while let Some(child) = self.callstack.pop() {
+                    self.tracer.call_exit(&child.borrow(), &StatusCode::Failure(failure_kind.clone()), &Bytes::default());
+                }
+                return Output::new_failure(failure_kind, 0);
+            }
             
             match interrupt {
                 Err(failure_kind) => {
@@ -185,15 +465,15 @@ impl Executor {
                         None => panic!("pop from empty callstack is not allowed."),
                         Some(scope) => scope,
                     };
+                    self.tracer.call_exit(&child.borrow(), &StatusCode::Failure(failure_kind.clone()), &Bytes::default());
+                    (*self.host).borrow_mut().rollback(&child.borrow().snapshot);
                     if self.callstack.is_empty() {
                         match failure_kind {
                             FailureKind::Revert => return Output::new_failure(failure_kind, scope.gas_left),
                             _ => return Output::new_failure(failure_kind, 0),
                         }
                     }
-    
-                    (*self.host).borrow_mut().rollback(&child.borrow().snapshot);
-    
+
                     resume = Resume::Returned(FAILED);
                     continue;
                 },
@@ -212,6 +492,12 @@ impl Executor {
                             }
                         },
                         Interrupt::Call(params) => {
+                            if is_precompile(params.address, self.revision) {
+                                if let Some(r) = self.call_precompile(&mut exec_context, &params) {
+                                    resume = r;
+                                    continue;
+                                }
+                            }
                             match self.push_child_scope(&params) {
                                 Err(kind) => {
                                     return Output::new_failure(kind, 0);
@@ -239,6 +525,12 @@ impl Executor {
         };
         let mut child = child.borrow_mut();
 
+        let status = match exit_kind {
+            ExitKind::Revert => StatusCode::Failure(FailureKind::Revert),
+            _ => StatusCode::Success,
+        };
+        self.tracer.call_exit(&child, &status, data);
+
         if exit_kind == ExitKind::Revert {
             child.refund_counter = 0;
             (*self.host).borrow_mut().rollback(&child.snapshot); // revert the state to previous snapshot
@@ -263,6 +555,53 @@ impl Executor {
         Some(Resume::Returned(SUCCESS))
     }
 
+    /// run precompiled contract natively instead of pushing a child scope.
+    /// 
+    /// returns `None` if the precompiled contract is not implemented.
+    fn call_precompile(&mut self, exec_context: &mut ExecutionContext, params: &CallParams) -> Option<Resume> {
+        let parent = self.callstack.peek();
+        let mut parent = parent.borrow_mut();
+
+        // scope seen by the tracer, as if the precompiled contract was called like any other.
+        let mut scope = CallScope {
+            calldata: parent.memory.get_range(params.args_offset, params.args_size).into(),
+            code_address: params.address,
+            caller: parent.code_address,
+            to: params.address,
+            origin: parent.origin,
+            value: params.value,
+            is_staticcall: parent.is_staticcall,
+            gas_limit: params.gas,
+            gas_left: params.gas,
+            ret_offset: params.ret_offset,
+            ret_size: params.ret_size,
+            snapshot: params.snapshot,
+            depth: parent.depth + 1,
+            ..CallScope::default()
+        };
+        let result = precompiles::execute(params.address, &scope.calldata.0, params.gas)?;
+        self.tracer.call_enter(params.kind, &scope);
+
+        match result {
+            Ok((gas_left, output)) => {
+                let size = min(params.ret_size, output.len());
+                parent.memory.set_range(params.ret_offset, &output[..size]);
+                parent.gas_left = parent.gas_left.saturating_add(gas_left);  // refund unused gas
+                scope.gas_left = gas_left;
+                self.tracer.call_exit(&scope, &StatusCode::Success, &output);
+                exec_context.return_data_buffer = output;
+                Some(Resume::Returned(SUCCESS))
+            },
+            Err(failure_kind) => {
+                (*self.host).borrow_mut().rollback(&params.snapshot);
+                scope.gas_left = 0;
+                self.tracer.call_exit(&scope, &StatusCode::Failure(failure_kind), &Bytes::default());
+                exec_context.return_data_buffer = Bytes::default();
+                Some(Resume::Returned(FAILED))
+            },
+        }
+    }
+
     pub fn execute_raw(&mut self, code: &Code) -> Output {
         let mut scope = CallScope::default();
         scope.code = code.clone();
@@ -289,6 +628,7 @@ impl Executor {
         };
         
         self.callstack.push(child)?;
+        self.tracer.call_enter(params.kind, &self.callstack.peek().borrow());
 
         Ok(())
     }
@@ -363,6 +703,14 @@ fn cost_of_calldata(calldata: &Calldata, revision: Revision) -> i64 {
     cost
 }
 
+/// intrinsic gas of `tx`, which is charged before the execution.
+fn intrinsic_gas(tx: &Transaction, calldata: &Calldata, revision: Revision) -> i64 {
+    let access_list_cost: i64 = tx.access_list_items().iter()
+        .map(|item| 2400 + 1900 * item.storage_keys.len() as i64)
+        .sum();
+    21000 + cost_of_calldata(calldata, revision) + access_list_cost
+}
+
 fn calc_effective_refund(
     gas_limit: i64,
     gas_left: i64,
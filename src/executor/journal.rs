@@ -1,61 +1,83 @@
 use ethereum_types::{
     Address, U256
 };
+use bytes::Bytes;
 
+use crate::host::stateful::StorageValue;
+
+/// state change which can be undone when a frame reverts.
+#[derive(Clone, Debug, PartialEq)]
+pub enum JournalEntry {
+    /// account did not exist before.
+    AccountCreated { address: Address },
+    /// `previous` holds the dirty flag as well, which the storage status of the next write depends on.
+    StorageChanged { address: Address, key: U256, previous: StorageValue },
+    BalanceChanged { address: Address, previous: U256 },
+    NonceChanged { address: Address, previous: u64 },
+    CodeChanged { address: Address, previous: Bytes },
+    /// log appended to the logs of the transaction.
+    LogEmitted,
+    /// selfdestruct appended to the selfdestructs of the transaction.
+    SelfDestructed,
+    /// account touched for the first time in the transaction, as defined in EIP-161.
+    AccountTouched { address: Address },
+    /// address added to the accessed addresses of EIP-2929.
+    AccountWarmed { address: Address },
+    /// storage key added to the accessed storage keys of EIP-2929.
+    StorageWarmed { address: Address, key: U256 },
+    /// ether removed from circulation, `previous` being the total burnt before.
+    Burnt { previous: U256 },
+}
+
+/// state changes of the current transaction in the order they happened.
 #[derive(Clone, Debug, Default)]
 pub struct Journal {
-    pub storage_log: Vec<StorageDelta>,
-    pub balance_log: Vec<BalanceDelta>,
+    pub entries: Vec<JournalEntry>,
 }
 
+/// position in the journal to revert to.
 #[derive(Copy, Clone, PartialEq, Debug, Default)]
 pub struct Snapshot {
-    pub storage_snapshot: usize,
-    pub balance_snapshot: usize,
+    pub journal_snapshot: usize,
 }
 
 impl Journal {
-    pub fn record_storage(&mut self, address: Address, key: U256, value: U256) {
-        let delta = StorageDelta {
-            address,
-            key,
-            previous: value,
-        };
-        self.storage_log.push(delta);
+    pub fn record(&mut self, entry: JournalEntry) {
+        self.entries.push(entry);
     }
 
-    pub fn record_balance_delta(&mut self, address: Address, sign: Sign, amount: U256) {
-        let delta = BalanceDelta {
-            address,
-            sign,
-            amount,
-        };
-        self.balance_log.push(delta);
+    pub fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            journal_snapshot: self.entries.len(),
+        }
     }
-}
 
-#[derive(Clone, Debug, Default)]
-pub struct StorageDelta {
-    pub address: Address,
-    pub key: U256,
-    pub previous: U256,
+    /// remove the entries recorded after `snapshot`, returning them newest first so that they can be undone in order.
+    pub fn revert(&mut self, snapshot: &Snapshot) -> Vec<JournalEntry> {
+        let start = snapshot.journal_snapshot.min(self.entries.len());
+        let mut reverted = self.entries.split_off(start);
+        reverted.reverse();
+        reverted
+    }
 }
 
-#[derive(Clone, Debug)]
-pub enum Sign {
-    Plus,
-    Minus,
-    Zero,
-}
-impl Default for Sign {
-    fn default() -> Self {
-        Self::Plus
+#[cfg(test)]
+mod test {
+    use crate::executor::journal::*;
+
+    #[test]
+    pub fn test_revert() {
+        let mut journal = Journal::default();
+        journal.record(JournalEntry::LogEmitted);
+        let snapshot = journal.snapshot();
+        journal.record(JournalEntry::AccountCreated { address: Address::from_low_u64_be(1) });
+        journal.record(JournalEntry::SelfDestructed);
+
+        assert_eq!(
+            vec![JournalEntry::SelfDestructed, JournalEntry::AccountCreated { address: Address::from_low_u64_be(1) }],
+            journal.revert(&snapshot)
+        );
+        assert_eq!(vec![JournalEntry::LogEmitted], journal.entries);
+        assert!(journal.revert(&snapshot).is_empty());
     }
 }
-
-#[derive(Clone, Debug, Default)]
-pub struct BalanceDelta {
-    pub address: Address,
-    pub sign: Sign,
-    pub amount: U256,
-}
\ No newline at end of file
@@ -1,3 +1,5 @@
+pub mod block;
 pub mod callstack;
 pub mod executor;
-pub mod journal;
\ No newline at end of file
+pub mod journal;
+pub mod precompiles;
\ No newline at end of file
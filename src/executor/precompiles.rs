@@ -0,0 +1,54 @@
+use std::cmp::min;
+
+use bytes::Bytes;
+use ethereum_types::{Address, H256, U256};
+
+use crate::model::{
+    evmc::FailureKind,
+    precompiles::ECRECOVER_ADDRESS,
+};
+use crate::utils::secp256k1::recover_address;
+
+const ECRECOVER_GAS: i64 = 3000;
+
+/// execute the precompiled contract at `address`, returning gas left and output.
+///
+/// returns `None` if the precompiled contract is not implemented.
+pub fn execute(address: Address, input: &[u8], gas: i64) -> Option<Result<(i64, Bytes), FailureKind>> {
+    match address.to_low_u64_be() {
+        ECRECOVER_ADDRESS => Some(ecrecover(input, gas)),
+        _ => None,
+    }
+}
+
+/// input is `hash || v || r || s`, each padded to 32 bytes.
+/// output is the signer address padded to 32 bytes, or empty if the signature is invalid.
+fn ecrecover(input: &[u8], gas: i64) -> Result<(i64, Bytes), FailureKind> {
+    if gas < ECRECOVER_GAS {
+        return Err(FailureKind::OutOfGas);
+    }
+    let gas_left = gas - ECRECOVER_GAS;
+
+    let mut data = [0u8; 128];
+    let size = min(input.len(), data.len());
+    data[..size].copy_from_slice(&input[..size]);
+
+    let hash = H256::from_slice(&data[0..32]);
+    let v = U256::from_big_endian(&data[32..64]);
+    let r = U256::from_big_endian(&data[64..96]);
+    let s = U256::from_big_endian(&data[96..128]);
+
+    if v != U256::from(27) && v != U256::from(28) {
+        return Ok((gas_left, Bytes::default()));
+    }
+    let y_parity = (v.low_u32() - 27) as u8;
+
+    match recover_address(&hash, y_parity, r, s) {
+        Some(address) => {
+            let mut output = [0u8; 32];
+            output[12..].copy_from_slice(&address.0);
+            Ok((gas_left, Bytes::from(output.to_vec())))
+        },
+        None => Ok((gas_left, Bytes::default())),
+    }
+}
@@ -1,14 +1,15 @@
 pub mod transient;
 pub mod stateful;
+pub mod witness;
 
-use ethereum_types::{Address, U256};
+use ethereum_types::{Address, H256, U256};
 use bytes::Bytes;
 
 use crate::{model::{evmc::{
-    Message, Output, TxContext, AccessStatus, StorageStatus
+    Message, Output, TxContext, AccessStatus, StorageStatus, FailureKind,
 }, code::Code}, executor::journal::Snapshot};
 
-use self::stateful::Account;
+use self::stateful::{Account, LogRecord};
 
 /// EVMC Host interface
 /// https://evmc.ethereum.org/structevmc__host__interface.html
@@ -38,10 +39,39 @@ pub trait Host {
     fn debug_deploy_contract(&mut self, address_hex: &str, code: Code, balance: U256);
     fn debug_deploy_contract2(&mut self, address: Address, code: Code, balance: U256);
     fn get_blockhash(&self, height: usize) -> U256;
+    fn set_block_hash(&mut self, number: u64, hash: H256);
     fn get_code(&self, address: Address, offset: usize, size: usize) -> Bytes;
     fn add_balance(&mut self, address: Address, amount: U256);
     fn subtract_balance(&mut self, address: Address, amount: U256);
+    fn get_nonce(&self, address: Address) -> u64;
+    fn set_nonce(&mut self, address: Address, nonce: u64);
+    fn set_code(&mut self, address: Address, code: Bytes);
+
+    // deletes the touched accounts which are empty, i.e. have no code, zero nonce and zero balance.
+    // https://eips.ethereum.org/EIPS/eip-161
+    fn clear_empty_accounts(&mut self);
     fn take_snapshot(&self) -> Snapshot;
     fn rollback(&mut self, snapshot: &Snapshot);
     fn force_update_storage(&mut self, address: Address, key: U256, value: U256);
+    fn set_tx_context(&mut self, context: TxContext);
+
+    // records ether removed from circulation. the amount must already be deducted from the payer.
+    fn burn(&mut self, amount: U256);
+    fn get_burnt_amount(&self) -> U256;
+
+    // logs emitted since the last commit, excluding those of reverted frames.
+    fn get_logs(&self) -> Vec<LogRecord>;
+
+    // ends a transaction: current storage becomes original, warm accounts and storage become cold,
+    // and logs, selfdestructs and the journal are discarded.
+    fn commit(&mut self);
+
+    // root of the state trie, committing to every account and its storage.
+    fn state_root(&self) -> H256;
+
+    // failure which makes the state read so far untrustworthy, e.g. state missing from a witness.
+    // the executor stops the execution as soon as it notices one.
+    fn failure(&self) -> Option<FailureKind> {
+        None
+    }
 }
\ No newline at end of file
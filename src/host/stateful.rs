@@ -1,17 +1,20 @@
-use ethereum_types::{Address, U256};
+use ethereum_types::{Address, H256, U256};
 use bytes::Bytes;
 use std::{
-    collections::HashMap,
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
     sync::Mutex,
 };
 
-use crate::executor::journal::{Journal, Snapshot, Sign};
+use crate::executor::journal::{Journal, JournalEntry, Snapshot};
 use crate::host::Host;
 use crate::model::code::Code;
 use crate::model::evmc::{
     Message, Output, TxContext, AccessStatus, StatusCode, StorageStatus, StorageStatusKind,
 };
-use hex_literal::hex;
+use crate::model::proof::{encode_account, storage_key, AccountProof, StateWitness, StorageProof};
+use crate::utils::{keccak256, KECCAK_EMPTY};
+use crate::utils::rlp::encode_u256;
+use crate::utils::trie::Trie;
 use hex::decode;
 
 /// LOG record.
@@ -36,21 +39,20 @@ pub struct SelfdestructRecord {
     pub beneficiary: Address,
 }
 
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug, Default, PartialEq)]
 pub struct StorageValue {
     pub original_value: U256,
     pub current_value: U256,
-    pub access_status: AccessStatus,
     pub dirty: bool,
 }
 
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug)]
 pub struct Account {
     /// The account nonce.
     pub nonce: u64,
     /// The account code.
     pub code: Bytes,
-    /// The code hash. Can be a value not related to the actual code.
+    /// The code hash, keccak256 of the code.
     pub code_hash: U256,
     /// The account balance.
     pub balance: U256,
@@ -58,7 +60,35 @@ pub struct Account {
     pub storage: HashMap<U256, StorageValue>,
 }
 
+impl Default for Account {
+    fn default() -> Self {
+        Self {
+            nonce: 0,
+            code: Bytes::default(),
+            code_hash: U256::from_big_endian(KECCAK_EMPTY.as_bytes()),
+            balance: U256::zero(),
+            storage: Default::default(),
+        }
+    }
+}
+
+impl Account {
+    /// replace the code and its hash, returning the previous code.
+    pub fn set_code(&mut self, code: Bytes) -> Bytes {
+        self.code_hash = U256::from_big_endian(keccak256(&code).as_bytes());
+        std::mem::replace(&mut self.code, code)
+    }
+
+    /// no code, zero nonce and zero balance, as defined in EIP-161.
+    /// https://eips.ethereum.org/EIPS/eip-161
+    pub fn is_empty(&self) -> bool {
+        self.nonce == 0 && self.balance.is_zero() && self.code.is_empty()
+    }
+}
+
 const MAX_RECORDED_ACCOUNT_ACCESSES: usize = 200;
+/// BLOCKHASH serves the hashes of the last 256 blocks.
+const BLOCK_HASH_WINDOW: u64 = 256;
 // const MAX_RECORDED_CALLS: usize = 100;
 
 #[derive(Clone, Debug, Default)]
@@ -71,12 +101,26 @@ pub struct Records {
     pub calls: Vec<Message>,
     pub logs: Vec<LogRecord>,
     pub selfdestructs: Vec<SelfdestructRecord>,
+
+    /// accounts and storage slots touched since `StatefulHost::record_witness()`, if recording.
+    pub touched: Option<BTreeMap<Address, BTreeSet<U256>>>,
 }
 impl Records {
     fn record_account_access(&mut self, address: Address) {
         if self.account_accesses.len() < MAX_RECORDED_ACCOUNT_ACCESSES {
             self.account_accesses.push(address)
         }
+        self.record_touch(address);
+    }
+    fn record_touch(&mut self, address: Address) {
+        if let Some(touched) = &mut self.touched {
+            touched.entry(address).or_default();
+        }
+    }
+    fn record_storage_touch(&mut self, address: Address, key: U256) {
+        if let Some(touched) = &mut self.touched {
+            touched.entry(address).or_default().insert(key);
+        }
     }
 }
 
@@ -89,6 +133,20 @@ pub struct StatefulHost {
     recorded: Mutex<Records>,
     is_always_warm: bool,
     journal: Journal,
+    /// accessed addresses of the current transaction.
+    /// https://eips.ethereum.org/EIPS/eip-2929
+    accessed_addresses: HashSet<Address>,
+    /// accessed storage keys of the current transaction.
+    accessed_storage_keys: HashSet<(Address, U256)>,
+    /// accounts touched by the current transaction.
+    /// https://eips.ethereum.org/EIPS/eip-161
+    touched_accounts: HashSet<Address>,
+    /// total amount of ether burnt, e.g. the base fee portion of transaction fees.
+    burnt: U256,
+    /// hashes of past blocks by block number.
+    block_hashes: HashMap<u64, H256>,
+    /// accounts as of `record_witness()`.
+    witness_base: Option<HashMap<Address, Account>>,
 }
 
 impl StatefulHost {
@@ -96,11 +154,13 @@ impl StatefulHost {
         Self {
             context: TxContext {
                 base_fee: U256::zero(),
+                blob_hashes: Vec::new(),
                 block_number: 0,
                 block_timestamp: 0,
                 chain_id: U256::one(),
                 coinbase: Address::zero(),
                 difficulty: U256::zero(),
+                excess_blob_gas: 0,
                 gas_limit: 0,
                 gas_price: U256::zero(),
                 origin: Address::zero(),
@@ -109,6 +169,12 @@ impl StatefulHost {
             recorded: Mutex::default(),
             is_always_warm: false,
             journal: Journal::default(),
+            accessed_addresses: HashSet::new(),
+            accessed_storage_keys: HashSet::new(),
+            touched_accounts: HashSet::new(),
+            burnt: U256::zero(),
+            block_hashes: HashMap::new(),
+            witness_base: None,
         }
     }
 
@@ -119,12 +185,21 @@ impl StatefulHost {
             recorded: Mutex::default(),
             is_always_warm: false,
             journal: Journal::default(),
+            accessed_addresses: HashSet::new(),
+            accessed_storage_keys: HashSet::new(),
+            touched_accounts: HashSet::new(),
+            burnt: U256::zero(),
+            block_hashes: HashMap::new(),
+            witness_base: None,
         }
     }
 }
 
 impl StatefulHost {
-    pub fn add_account(&mut self, address: Address, account: Account) {
+    /// add `account`, whose code hash is derived from its code.
+    pub fn add_account(&mut self, address: Address, mut account: Account) {
+        let code = std::mem::take(&mut account.code);
+        account.set_code(code);
         self.accounts.insert(address, account);
     }
     pub fn debug_get_storage(&self, address: Address, key: U256) -> U256 {
@@ -155,26 +230,127 @@ impl StatefulHost {
             dst[hex.len() - 1 - i] = hex[hex.len() - 1 - i];
         }
 
-        let account = Account {
+        let mut account = Account {
             balance,
-            code: code.0.into(),
-            code_hash: U256::from(0x123456),
-            nonce: 0,
-            storage: Default::default(),
+            ..Default::default()
         };
+        account.set_code(code.0.into());
         let address = Address::from_slice(&dst);
         self.accounts.insert(address, account);
     }
     pub fn debug_deploy_contract2(&mut self, address: Address, code: Code, balance: U256) {
-        let account = Account {
+        let mut account = Account {
             balance,
-            code: code.0.into(),
-            code_hash: U256::from(0x123456),
-            nonce: 0,
-            storage: Default::default(),
+            ..Default::default()
         };
+        account.set_code(code.0.into());
         self.accounts.insert(address, account);
     }
+
+    /// the account at `address`, created empty if it does not exist. the account is touched.
+    fn account_mut(&mut self, address: Address) -> &mut Account {
+        if self.touched_accounts.insert(address) {
+            self.journal.record(JournalEntry::AccountTouched { address });
+        }
+        if !self.accounts.contains_key(&address) {
+            self.journal.record(JournalEntry::AccountCreated { address });
+        }
+        self.accounts.entry(address).or_default()
+    }
+
+    /// root of the storage trie of `address`. slots holding zero are not in the trie.
+    pub fn storage_root(&self, address: Address) -> H256 {
+        storage_trie(self.accounts.get(&address)).root_hash()
+    }
+
+    /// proof of the account at `address` and its storage at `keys` against `Host::state_root()`.
+    ///
+    /// absent account and slots are proven absent.
+    pub fn get_proof(&self, address: Address, keys: &[U256]) -> AccountProof {
+        let account = self.accounts.get(&address);
+        let storage_trie = storage_trie(account);
+
+        let storage_proof = keys.iter()
+            .map(|key| StorageProof {
+                key: *key,
+                value: self.debug_get_storage(address, *key),
+                proof: to_bytes(storage_trie.prove(storage_key(*key).as_bytes())),
+            })
+            .collect();
+
+        AccountProof {
+            address,
+            nonce: account.map_or(0, |account| account.nonce),
+            balance: account.map_or(U256::zero(), |account| account.balance),
+            storage_hash: storage_trie.root_hash(),
+            code_hash: keccak256(account.map_or(&[][..], |account| &account.code[..])),
+            account_proof: to_bytes(state_trie(&self.accounts).prove(keccak256(address.as_bytes()).as_bytes())),
+            storage_proof,
+        }
+    }
+}
+
+impl StatefulHost {
+    /// start recording the accounts and storage slots touched, e.g. by executing a transaction.
+    pub fn record_witness(&mut self) {
+        self.witness_base = Some(self.accounts.clone());
+        self.recorded.lock().unwrap().touched = Some(BTreeMap::new());
+    }
+
+    /// stop recording and return the witness proving everything touched since `record_witness()`
+    /// against the state as it was then. `None` if not recording.
+    pub fn take_witness(&mut self) -> Option<StateWitness> {
+        let accounts = self.witness_base.take()?;
+        let touched = self.recorded.lock().unwrap().touched.take()?;
+
+        let state_trie = state_trie(&accounts);
+        let mut nodes = BTreeSet::new();
+        let mut codes = BTreeSet::new();
+        for (address, keys) in touched {
+            nodes.extend(state_trie.prove(keccak256(address.as_bytes()).as_bytes()));
+
+            let account = accounts.get(&address);
+            if let Some(account) = account.filter(|account| !account.code.is_empty()) {
+                codes.insert(account.code.clone());
+            }
+            let storage_trie = storage_trie(account);
+            for key in keys {
+                nodes.extend(storage_trie.prove(storage_key(key).as_bytes()));
+            }
+        }
+
+        Some(StateWitness {
+            state_root: state_trie.root_hash(),
+            nodes: nodes.into_iter().map(Bytes::from).collect(),
+            codes: codes.into_iter().collect(),
+        })
+    }
+}
+
+fn storage_trie(account: Option<&Account>) -> Trie {
+    let mut trie = Trie::new();
+    for (key, value) in account.iter().flat_map(|account| &account.storage) {
+        if !value.current_value.is_zero() {
+            trie.insert(storage_key(*key).as_bytes(), encode_u256(value.current_value));
+        }
+    }
+    trie
+}
+
+/// accounts keyed by the hash of their address.
+/// the code hash is computed from the code rather than `Account::code_hash`.
+fn state_trie(accounts: &HashMap<Address, Account>) -> Trie {
+    let mut trie = Trie::new();
+    for (address, account) in accounts {
+        let storage_root = storage_trie(Some(account)).root_hash();
+        let value = encode_account(account.nonce, account.balance, &storage_root, &keccak256(&account.code));
+        trie.insert(keccak256(address.as_bytes()).as_bytes(), value);
+    }
+    trie
+}
+
+fn to_bytes(proof: Vec<Vec<u8>>) -> Vec<Bytes> {
+    proof.into_iter().map(Bytes::from).collect()
 }
 
 #[allow(unused_variables)]
@@ -189,6 +365,7 @@ impl Host for StatefulHost {
     fn get_storage(&self, address: Address, key: U256) -> U256 {
         let mut record = self.recorded.lock().unwrap();
         record.record_account_access(address);
+        record.record_storage_touch(address, key);
 
         self.accounts
             .get(&address)
@@ -197,22 +374,22 @@ impl Host for StatefulHost {
     }
 
     fn set_storage(&mut self, address: Address, key: U256, new_value: U256) -> StorageStatus {
-        let mut record = self.recorded.lock().unwrap();
-        record.record_account_access(address);
+        {
+            let mut record = self.recorded.lock().unwrap();
+            record.record_account_access(address);
+            record.record_storage_touch(address, key);
+        }
 
         // Get the reference to the old value.
         // This will create the account in case it was not present.
         // This is convenient for unit testing and standalone EVM execution to preserve the
         // storage values after the execution terminates.
         let value = self
-            .accounts
-            .entry(address)
-            .or_default()
+            .account_mut(address)
             .storage
             .entry(key)
             .or_default();
-        
-        self.journal.record_storage(address, key, value.current_value);
+        let previous = value.clone();
 
         // Follow https://eips.ethereum.org/EIPS/eip-1283 specification.
         if value.current_value == new_value {
@@ -239,11 +416,13 @@ impl Host for StatefulHost {
         let current_value_before_set = value.current_value;
         value.current_value = new_value;
 
-        return StorageStatus {
+        let status = StorageStatus {
             original: value.original_value,
             current: current_value_before_set,
             kind: kind
-        }
+        };
+        self.journal.record(JournalEntry::StorageChanged { address, key, previous });
+        status
     }
     
     fn get_balance(&self, address: Address) -> U256 {
@@ -270,8 +449,11 @@ impl Host for StatefulHost {
         let mut record = self.recorded.lock().unwrap();
         record.record_account_access(address);
 
+        // EXTCODEHASH of a non-existent or empty account is zero.
+        // https://eips.ethereum.org/EIPS/eip-1052
         self.accounts
             .get(&address)
+            .filter(|account| !account.is_empty())
             .map(|account| account.code_hash)
             .unwrap_or_else(U256::zero)
     }
@@ -289,6 +471,7 @@ impl Host for StatefulHost {
             selfdestructed: address,
             beneficiary
         });
+        self.journal.record(JournalEntry::SelfDestructed);
     }
 
     fn call(&mut self, msg: &Message) -> Output {
@@ -314,49 +497,39 @@ impl Host for StatefulHost {
             data: data.to_vec().into(),
             topics: topics.to_vec()
         });
+        self.journal.record(JournalEntry::LogEmitted);
     }
 
     fn access_account(&mut self, address: Address) -> AccessStatus {
         if self.is_always_warm {
             return AccessStatus::Warm;
         }
+        self.recorded.lock().unwrap().record_account_access(address);
 
-        let mut record = self.recorded.lock().unwrap();
-        let already_accessed = record.account_accesses.iter().any(|&a| a == address);
-
-        record.record_account_access(address);
-
-        if address.0 >= hex!("0000000000000000000000000000000000000001") && address.0 <= hex!("0000000000000000000000000000000000000009")
-        {
-            return AccessStatus::Warm;
-        }
-
-        if already_accessed {
-            AccessStatus::Warm
-        } else {
+        if self.accessed_addresses.insert(address) {
+            self.journal.record(JournalEntry::AccountWarmed { address });
             AccessStatus::Cold
+        } else {
+            AccessStatus::Warm
         }
     }
 
     fn access_storage(&mut self, address: Address, key: U256) -> AccessStatus {
+        self.recorded.lock().unwrap().record_storage_touch(address, key);
         if self.is_always_warm {
             return AccessStatus::Warm;
         }
-        
-        let value = self
-            .accounts
-            .entry(address)
-            .or_default()
-            .storage
-            .entry(key)
-            .or_default();
-        let access_status = value.access_status;
-        value.access_status = AccessStatus::Warm;
-        access_status
+
+        if self.accessed_storage_keys.insert((address, key)) {
+            self.journal.record(JournalEntry::StorageWarmed { address, key });
+            AccessStatus::Cold
+        } else {
+            AccessStatus::Warm
+        }
     }
 
     fn add_account(&mut self, address: Address, account: Account) {
-        self.accounts.insert(address, account);
+        StatefulHost::add_account(self, address, account);
     }
     fn debug_get_storage(&self, address: Address, key: U256) -> U256 {
         self.accounts
@@ -386,29 +559,43 @@ impl Host for StatefulHost {
             dst[hex.len() - 1 - i] = hex[hex.len() - 1 - i];
         }
 
-        let account = Account {
+        let mut account = Account {
             balance,
-            code: code.0.into(),
-            code_hash: U256::from(0x123456),
-            nonce: 0,
-            storage: Default::default(),
+            ..Default::default()
         };
+        account.set_code(code.0.into());
         let address = Address::from_slice(&dst);
         self.accounts.insert(address, account);
     }
     fn debug_deploy_contract2(&mut self, address: Address, code: Code, balance: U256) {
-        let account = Account {
+        let mut account = Account {
             balance,
-            code: code.0.into(),
-            code_hash: U256::from(0x123456),
-            nonce: 0,
-            storage: Default::default(),
+            ..Default::default()
         };
+        account.set_code(code.0.into());
         self.accounts.insert(address, account);
     }
 
     fn get_blockhash(&self, height: usize) -> U256 {
-        U256::from(height)
+        let mut record = self.recorded.lock().unwrap();
+        record.blockhashes.push(height as u64);
+
+        // only the last 256 blocks, excluding the current one, are available.
+        let height = height as u64;
+        let current = self.context.block_number.max(0) as u64;
+        if height >= current || current - height > BLOCK_HASH_WINDOW {
+            return U256::zero();
+        }
+        self.block_hashes
+            .get(&height)
+            .map(|hash| U256::from_big_endian(hash.as_bytes()))
+            .unwrap_or_else(U256::zero)
+    }
+    fn set_block_hash(&mut self, number: u64, hash: H256) {
+        self.block_hashes.insert(number, hash);
+    }
+    fn state_root(&self) -> H256 {
+        state_trie(&self.accounts).root_hash()
     }
     fn get_code(&self, address: Address, offset: usize, size: usize) -> Bytes {
         let mut record = self.recorded.lock().unwrap();
@@ -420,53 +607,96 @@ impl Host for StatefulHost {
             .unwrap_or_else(Bytes::default)
     }
     fn add_balance(&mut self, address: Address, amount: U256){
-        let account = self
-            .accounts
-            .entry(address)
-            .or_default();
-        
+        self.recorded.lock().unwrap().record_touch(address);
+        let account = self.account_mut(address);
+        let previous = account.balance;
         account.balance += amount;
-        self.journal.record_balance_delta(address, Sign::Plus, amount);
+        self.journal.record(JournalEntry::BalanceChanged { address, previous });
     }
     fn subtract_balance(&mut self, address: Address, amount: U256){
-        let account = self
-            .accounts
-            .entry(address)
-            .or_default();
-        
+        self.recorded.lock().unwrap().record_touch(address);
+        let account = self.account_mut(address);
+        let previous = account.balance;
         account.balance -= amount;
-        self.journal.record_balance_delta(address, Sign::Minus, amount);
+        self.journal.record(JournalEntry::BalanceChanged { address, previous });
     }
-    fn take_snapshot(&self) -> Snapshot {
-        Snapshot {
-            storage_snapshot: self.journal.storage_log.len(),
-            balance_snapshot: self.journal.balance_log.len(),
+    fn get_nonce(&self, address: Address) -> u64 {
+        self.recorded.lock().unwrap().record_touch(address);
+        self.accounts
+            .get(&address)
+            .map_or(0, |account| account.nonce)
+    }
+    fn set_nonce(&mut self, address: Address, nonce: u64) {
+        self.recorded.lock().unwrap().record_touch(address);
+        let account = self.account_mut(address);
+        let previous = account.nonce;
+        account.nonce = nonce;
+        self.journal.record(JournalEntry::NonceChanged { address, previous });
+    }
+    fn set_code(&mut self, address: Address, code: Bytes) {
+        self.recorded.lock().unwrap().record_touch(address);
+        let account = self.account_mut(address);
+        let previous = account.set_code(code);
+        self.journal.record(JournalEntry::CodeChanged { address, previous });
+    }
+    fn clear_empty_accounts(&mut self) {
+        for address in std::mem::take(&mut self.touched_accounts) {
+            let is_empty = self.accounts
+                .get(&address)
+                .is_some_and(Account::is_empty);
+            if is_empty {
+                self.accounts.remove(&address);
+            }
         }
     }
+    fn take_snapshot(&self) -> Snapshot {
+        self.journal.snapshot()
+    }
     fn rollback(&mut self, snapshot: &Snapshot) {
-        // rollback storage delta
-        let length = self.journal.storage_log.len();
-        for _ in 0..length - snapshot.storage_snapshot {
-            if let Some(delta) = self.journal.storage_log.pop() {
-                self.force_update_storage(delta.address, delta.key, delta.previous);
-            }
-        }
-
-        // rollback balance delta
-        let length = self.journal.balance_log.len();
-        for _ in 0..length - snapshot.balance_snapshot {
-            if let Some(delta) = self.journal.balance_log.pop() {
-                let account = self
-                    .accounts
-                    .entry(delta.address)
-                    .or_default();
-                
-                // undo balance delta
-                match delta.sign {
-                    Sign::Plus => account.balance -= delta.amount,
-                    Sign::Minus => account.balance += delta.amount,
-                    _ => ()
-                }
+        let mut record = self.recorded.lock().unwrap();
+        for entry in self.journal.revert(snapshot) {
+            match entry {
+                JournalEntry::AccountCreated { address } => {
+                    self.accounts.remove(&address);
+                },
+                JournalEntry::StorageChanged { address, key, previous } => {
+                    if let Some(account) = self.accounts.get_mut(&address) {
+                        account.storage.insert(key, previous);
+                    }
+                },
+                JournalEntry::BalanceChanged { address, previous } => {
+                    if let Some(account) = self.accounts.get_mut(&address) {
+                        account.balance = previous;
+                    }
+                },
+                JournalEntry::NonceChanged { address, previous } => {
+                    if let Some(account) = self.accounts.get_mut(&address) {
+                        account.nonce = previous;
+                    }
+                },
+                JournalEntry::CodeChanged { address, previous } => {
+                    if let Some(account) = self.accounts.get_mut(&address) {
+                        account.set_code(previous);
+                    }
+                },
+                JournalEntry::LogEmitted => {
+                    record.logs.pop();
+                },
+                JournalEntry::SelfDestructed => {
+                    record.selfdestructs.pop();
+                },
+                JournalEntry::AccountTouched { address } => {
+                    self.touched_accounts.remove(&address);
+                },
+                JournalEntry::AccountWarmed { address } => {
+                    self.accessed_addresses.remove(&address);
+                },
+                JournalEntry::StorageWarmed { address, key } => {
+                    self.accessed_storage_keys.remove(&(address, key));
+                },
+                JournalEntry::Burnt { previous } => {
+                    self.burnt = previous;
+                },
             }
         }
     }
@@ -480,4 +710,35 @@ impl Host for StatefulHost {
             .or_default();
         value.current_value = new_value;
     }
+    fn set_tx_context(&mut self, context: TxContext) {
+        self.context = context;
+    }
+    fn burn(&mut self, amount: U256) {
+        self.journal.record(JournalEntry::Burnt { previous: self.burnt });
+        self.burnt += amount;
+    }
+    fn get_burnt_amount(&self) -> U256 {
+        self.burnt
+    }
+    fn get_logs(&self) -> Vec<LogRecord> {
+        self.recorded.lock().unwrap().logs.clone()
+    }
+    fn commit(&mut self) {
+        for account in self.accounts.values_mut() {
+            for value in account.storage.values_mut() {
+                value.original_value = value.current_value;
+                value.dirty = false;
+            }
+        }
+        self.accessed_addresses.clear();
+        self.accessed_storage_keys.clear();
+        self.touched_accounts.clear();
+
+        let mut record = self.recorded.lock().unwrap();
+        record.account_accesses.clear();
+        record.logs.clear();
+        record.selfdestructs.clear();
+
+        self.journal = Journal::default();
+    }
 }
@@ -1,4 +1,4 @@
-use ethereum_types::{Address, U256};
+use ethereum_types::{Address, H256, U256};
 use bytes::Bytes;
 
 use crate::executor::journal::Snapshot;
@@ -8,7 +8,9 @@ use crate::model::evmc::{
     Message, Output, TxContext, AccessStatus, StatusCode, StorageStatus
 };
 
-use super::stateful::Account;
+use crate::utils::trie::EMPTY_ROOT;
+
+use super::stateful::{Account, LogRecord};
 
 /// host without no persistent storage
 pub struct TransientHost {
@@ -20,11 +22,13 @@ impl TransientHost {
         TransientHost{
             context: TxContext {
                 base_fee: U256::zero(),
+                blob_hashes: Vec::new(),
                 block_number: 0,
                 block_timestamp: 0,
                 chain_id: U256::one(),
                 coinbase: Address::zero(),
                 difficulty: U256::zero(),
+                excess_blob_gas: 0,
                 gas_limit: 0,
                 gas_price: U256::zero(),
                 origin: Address::zero(),
@@ -107,6 +111,9 @@ impl Host for TransientHost {
 
     fn get_blockhash(&self, height: usize) -> U256 {
         U256::from(0x0101)
+    }
+    fn set_block_hash(&mut self, number: u64, hash: H256) {
+
     }
     fn get_code(&self, address: Address, offset: usize, size: usize) -> Bytes {
         Bytes::default()
@@ -116,6 +123,18 @@ impl Host for TransientHost {
     }
     fn subtract_balance(&mut self, address: Address, amount: U256){
 
+    }
+    fn get_nonce(&self, address: Address) -> u64 {
+        0
+    }
+    fn set_nonce(&mut self, address: Address, nonce: u64) {
+        
+    }
+    fn set_code(&mut self, address: Address, code: Bytes) {
+        
+    }
+    fn clear_empty_accounts(&mut self) {
+        
     }
     fn take_snapshot(&self) -> Snapshot {
         Snapshot::default()
@@ -126,4 +145,22 @@ impl Host for TransientHost {
     fn force_update_storage(&mut self, address: Address, key: U256, value: U256){
 
     }
+    fn set_tx_context(&mut self, context: TxContext) {
+        self.context = context;
+    }
+    fn burn(&mut self, amount: U256) {
+
+    }
+    fn get_burnt_amount(&self) -> U256 {
+        U256::zero()
+    }
+    fn get_logs(&self) -> Vec<LogRecord> {
+        Vec::new()
+    }
+    fn commit(&mut self) {
+
+    }
+    fn state_root(&self) -> H256 {
+        EMPTY_ROOT
+    }
 }
\ No newline at end of file
@@ -0,0 +1,305 @@
+use ethereum_types::{Address, H256, U256};
+use bytes::Bytes;
+use hex::decode;
+use std::{
+    cell::RefCell,
+    collections::{HashMap, HashSet},
+};
+
+use crate::executor::journal::Snapshot;
+use crate::host::Host;
+use crate::model::code::Code;
+use crate::model::evmc::{
+    Message, Output, TxContext, AccessStatus, StorageStatus, FailureKind,
+};
+use crate::model::proof::{decode_account, storage_key, StateWitness};
+use crate::utils::{keccak256, KECCAK_EMPTY};
+use crate::utils::rlp;
+use crate::utils::trie::{get_from_nodes, ProofError, EMPTY_ROOT};
+
+use super::stateful::{Account, LogRecord, StatefulHost};
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum WitnessError {
+    /// the witness does not prove whether the account exists.
+    MissingAccount(Address),
+    /// the witness does not prove the value of the storage slot.
+    MissingStorage { address: Address, key: U256 },
+    /// the code of the account is not in the witness.
+    MissingCode(Address),
+    /// a node of the witness cannot be decoded.
+    InvalidWitness(ProofError),
+}
+
+/// host backed by a state witness instead of the whole state.
+///
+/// accounts and storage slots are read from the witness the first time they are touched,
+/// and kept in a `StatefulHost` afterwards.
+/// touching state the witness does not prove reads as empty, and fails the execution
+/// with `FailureKind::InternalError`. `check()` tells which state is missing.
+pub struct WitnessHost {
+    inner: RefCell<StatefulHost>,
+    state_root: H256,
+    nodes: HashMap<H256, Vec<u8>>,
+    codes: HashMap<H256, Bytes>,
+    /// storage root of the loaded accounts, or `None` for accounts absent from the state.
+    loaded_accounts: RefCell<HashMap<Address, Option<H256>>>,
+    loaded_slots: RefCell<HashSet<(Address, U256)>>,
+    error: RefCell<Option<WitnessError>>,
+}
+
+impl WitnessHost {
+    pub fn new(context: TxContext, witness: &StateWitness) -> Self {
+        Self {
+            inner: RefCell::new(StatefulHost::new_with(context)),
+            state_root: witness.state_root,
+            nodes: witness.nodes.iter().map(|node| (keccak256(node), node.to_vec())).collect(),
+            codes: witness.codes.iter().map(|code| (keccak256(code), code.clone())).collect(),
+            loaded_accounts: RefCell::default(),
+            loaded_slots: RefCell::default(),
+            error: RefCell::default(),
+        }
+    }
+
+    /// the first state touched that the witness does not prove, if any.
+    pub fn check(&self) -> Result<(), WitnessError> {
+        match self.error.borrow().clone() {
+            Some(err) => Err(err),
+            None => Ok(()),
+        }
+    }
+
+    fn fail(&self, err: WitnessError) {
+        self.error.borrow_mut().get_or_insert(err);
+    }
+
+    fn load_account(&self, address: Address) -> Option<H256> {
+        if let Some(storage_root) = self.loaded_accounts.borrow().get(&address) {
+            return *storage_root;
+        }
+
+        let storage_root = match get_from_nodes(self.state_root, keccak256(address.as_bytes()).as_bytes(), &self.nodes) {
+            Ok(Some(encoded)) => self.decode_account(address, &encoded),
+            Ok(None) => None,
+            Err(ProofError::MissingNode) => {
+                self.fail(WitnessError::MissingAccount(address));
+                None
+            },
+            Err(err) => {
+                self.fail(WitnessError::InvalidWitness(err));
+                None
+            },
+        };
+        self.loaded_accounts.borrow_mut().insert(address, storage_root);
+        storage_root
+    }
+
+    /// add the account to the inner host, returning its storage root.
+    fn decode_account(&self, address: Address, encoded: &[u8]) -> Option<H256> {
+        let (nonce, balance, storage_root, code_hash) = match decode_account(encoded) {
+            Ok(account) => account,
+            Err(err) => {
+                self.fail(WitnessError::InvalidWitness(err.into()));
+                return None;
+            },
+        };
+
+        let code = if code_hash == KECCAK_EMPTY {
+            Bytes::default()
+        }else{
+            self.codes.get(&code_hash).cloned().unwrap_or_else(|| {
+                self.fail(WitnessError::MissingCode(address));
+                Bytes::default()
+            })
+        };
+
+        self.inner.borrow_mut().add_account(address, Account {
+            nonce,
+            code,
+            code_hash: U256::from_big_endian(code_hash.as_bytes()),
+            balance,
+            storage: Default::default(),
+        });
+        Some(storage_root)
+    }
+
+    fn load_storage(&self, address: Address, key: U256) {
+        let storage_root = self.load_account(address);
+        if !self.loaded_slots.borrow_mut().insert((address, key)) {
+            return;
+        }
+        // absent account has no storage.
+        let Some(storage_root) = storage_root else {
+            return;
+        };
+
+        let value = match get_from_nodes(storage_root, storage_key(key).as_bytes(), &self.nodes) {
+            Ok(Some(encoded)) => match rlp::decode(&encoded).and_then(|item| item.as_u256()) {
+                Ok(value) => value,
+                Err(err) => {
+                    self.fail(WitnessError::InvalidWitness(err.into()));
+                    return;
+                },
+            },
+            Ok(None) => return,
+            Err(ProofError::MissingNode) => {
+                self.fail(WitnessError::MissingStorage { address, key });
+                return;
+            },
+            Err(err) => {
+                self.fail(WitnessError::InvalidWitness(err));
+                return;
+            },
+        };
+        self.inner.borrow_mut().debug_set_storage(address, key, value);
+    }
+
+    /// mark the account as loaded so that the witness does not override it.
+    fn overwrite_account(&self, address: Address) {
+        self.loaded_accounts.borrow_mut().entry(address).or_insert(Some(EMPTY_ROOT));
+    }
+}
+
+impl Host for WitnessHost {
+    fn account_exists(&self, address: Address) -> bool {
+        self.load_account(address);
+        self.inner.borrow().account_exists(address)
+    }
+    fn get_storage(&self, address: Address, key: U256) -> U256 {
+        self.load_storage(address, key);
+        self.inner.borrow().get_storage(address, key)
+    }
+    fn set_storage(&mut self, address: Address, key: U256, value: U256) -> StorageStatus {
+        self.load_storage(address, key);
+        self.inner.get_mut().set_storage(address, key, value)
+    }
+    fn get_balance(&self, address: Address) -> U256 {
+        self.load_account(address);
+        self.inner.borrow().get_balance(address)
+    }
+    fn get_code_size(&self, address: Address) -> U256 {
+        self.load_account(address);
+        self.inner.borrow().get_code_size(address)
+    }
+    fn get_code_hash(&self, address: Address) -> U256 {
+        self.load_account(address);
+        self.inner.borrow().get_code_hash(address)
+    }
+    fn copy_code(&self, address: Address, code_offset: usize, memory_offset: usize, size: usize) {
+        self.load_account(address);
+        self.inner.borrow().copy_code(address, code_offset, memory_offset, size)
+    }
+    fn self_destruct(&mut self, address: Address, beneficiary: Address) {
+        self.load_account(address);
+        self.load_account(beneficiary);
+        self.inner.get_mut().self_destruct(address, beneficiary)
+    }
+    fn call(&mut self, msg: &Message) -> Output {
+        self.inner.get_mut().call(msg)
+    }
+    fn get_tx_context(&self) -> TxContext {
+        self.inner.borrow().get_tx_context()
+    }
+    fn emit_log(&mut self, address: Address, data: &[u8], topics: &[U256]) {
+        self.inner.get_mut().emit_log(address, data, topics)
+    }
+    fn access_account(&mut self, address: Address) -> AccessStatus {
+        self.inner.get_mut().access_account(address)
+    }
+    fn access_storage(&mut self, address: Address, key: U256) -> AccessStatus {
+        self.load_storage(address, key);
+        self.inner.get_mut().access_storage(address, key)
+    }
+
+    fn add_account(&mut self, address: Address, account: Account) {
+        self.overwrite_account(address);
+        self.inner.get_mut().add_account(address, account)
+    }
+    fn debug_get_storage(&self, address: Address, key: U256) -> U256 {
+        self.load_storage(address, key);
+        self.inner.borrow().debug_get_storage(address, key)
+    }
+    fn debug_set_storage(&mut self, address: Address, key: U256, new_value: U256) {
+        self.load_storage(address, key);
+        self.inner.get_mut().debug_set_storage(address, key, new_value)
+    }
+    fn debug_set_storage_as_warm(&mut self) {
+        self.inner.get_mut().debug_set_storage_as_warm()
+    }
+    fn debug_deploy_contract(&mut self, address_hex: &str, code: Code, balance: U256) {
+        let hex = decode(address_hex).unwrap();
+        let mut dst = [0u8; 20];
+        dst[..hex.len()].copy_from_slice(&hex);
+        self.debug_deploy_contract2(Address::from(dst), code, balance)
+    }
+    fn debug_deploy_contract2(&mut self, address: Address, code: Code, balance: U256) {
+        self.overwrite_account(address);
+        self.inner.get_mut().debug_deploy_contract2(address, code, balance)
+    }
+    fn get_blockhash(&self, height: usize) -> U256 {
+        self.inner.borrow().get_blockhash(height)
+    }
+    fn set_block_hash(&mut self, number: u64, hash: H256) {
+        self.inner.get_mut().set_block_hash(number, hash)
+    }
+    /// root of the state the witness proves, not the state after execution,
+    /// since the witness does not hold the whole trie.
+    fn state_root(&self) -> H256 {
+        self.state_root
+    }
+    fn get_code(&self, address: Address, offset: usize, size: usize) -> Bytes {
+        self.load_account(address);
+        self.inner.borrow().get_code(address, offset, size)
+    }
+    fn add_balance(&mut self, address: Address, amount: U256) {
+        self.load_account(address);
+        self.inner.get_mut().add_balance(address, amount)
+    }
+    fn subtract_balance(&mut self, address: Address, amount: U256) {
+        self.load_account(address);
+        self.inner.get_mut().subtract_balance(address, amount)
+    }
+    fn get_nonce(&self, address: Address) -> u64 {
+        self.load_account(address);
+        self.inner.borrow().get_nonce(address)
+    }
+    fn set_nonce(&mut self, address: Address, nonce: u64) {
+        self.load_account(address);
+        self.inner.get_mut().set_nonce(address, nonce)
+    }
+    fn set_code(&mut self, address: Address, code: Bytes) {
+        self.load_account(address);
+        self.inner.get_mut().set_code(address, code)
+    }
+    fn clear_empty_accounts(&mut self) {
+        self.inner.get_mut().clear_empty_accounts()
+    }
+    fn take_snapshot(&self) -> Snapshot {
+        self.inner.borrow().take_snapshot()
+    }
+    fn rollback(&mut self, snapshot: &Snapshot) {
+        self.inner.get_mut().rollback(snapshot)
+    }
+    fn force_update_storage(&mut self, address: Address, key: U256, value: U256) {
+        self.load_storage(address, key);
+        self.inner.get_mut().force_update_storage(address, key, value)
+    }
+    fn set_tx_context(&mut self, context: TxContext) {
+        self.inner.get_mut().set_tx_context(context)
+    }
+    fn burn(&mut self, amount: U256) {
+        self.inner.get_mut().burn(amount)
+    }
+    fn get_burnt_amount(&self) -> U256 {
+        self.inner.borrow().get_burnt_amount()
+    }
+    fn get_logs(&self) -> Vec<LogRecord> {
+        self.inner.borrow().get_logs()
+    }
+    fn commit(&mut self) {
+        self.inner.get_mut().commit()
+    }
+    fn failure(&self) -> Option<FailureKind> {
+        self.error.borrow().as_ref().map(|err| FailureKind::InternalError(format!("witness error: {:?}", err)))
+    }
+}
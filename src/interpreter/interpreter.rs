@@ -1,6 +1,6 @@
 use bytes::Bytes;
 use ethereum_types::{
-    U256, U512
+    Address, U256, U512
 };
 use std::{cmp::min, cell::{RefCell}, rc::Rc};
 
@@ -29,6 +29,7 @@ use crate::interpreter::{
         gasometer::{calc_sstore_gas_cost, calc_sstore_gas_refund},
     },
 };
+use crate::tracer::Tracer;
 use crate::utils::{
     i256::{I256, Sign},
     address_to_u256, u256_to_address,
@@ -36,84 +37,85 @@ use crate::utils::{
 
 use super::{CallParams, CallKind, ExitKind};
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Default)]
 pub struct Interpreter {
     pub pc: usize,
     pub stack: Stack,
-    pub trace: bool,
-}
-
-impl Default for Interpreter {
-    fn default() -> Self {
-        Self {
-            pc: 0,
-            stack: Stack::default(),
-            trace: false,
-        }
-    }
 }
 
 impl Interpreter {
-    pub fn new_with_tracing() -> Self {
-        Self {
-            pc: 0,
-            stack: Stack::default(),
-            trace: true,
-        }
-    }
-
-    pub fn resume_interpret(
+    pub fn resume_interpret<T: Tracer>(
         &self,
         resume: Resume,
         scope: &mut CallScope,
         exec_context: &mut ExecutionContext,
-        host: Rc<RefCell<dyn Host>>
+        host: Rc<RefCell<dyn Host>>,
+        tracer: &mut T,
     ) -> Result<Interrupt, FailureKind> {
-        let mut old_gas_left = scope.gas_left;
-        
-        self.apply_resume(resume, scope, exec_context)?;
-        
-        loop {
-            if self.trace {
-                let gas_consumed_by_current = old_gas_left - scope.gas_left;
-                let gas_consumed_sofar = scope.gas_limit - scope.gas_left;
-                println!("{}, {}", gas_consumed_by_current, gas_consumed_sofar);
-            }
-            old_gas_left = scope.gas_left;
+        if let Err(kind) = self.apply_resume(resume, scope, exec_context) {
+            tracer.failure(&kind, scope);
+            return Err(kind);
+        }
 
+        loop {
             let op_byte = match scope.code.0.get(scope.pc) {
                 Some(num) => *num,
                 None => return Ok(Interrupt::Exit(scope.gas_left, Bytes::default(), ExitKind::Stop))
             };
 
-            if let Some(opcode) = OpCode::from_u8(op_byte) {
-                if self.trace {
-                    print!("[{}]: {:?} ", scope.pc, opcode);
+            let opcode = match OpCode::from_u8(op_byte) {
+                Some(opcode) => opcode,
+                None => {
+                    scope.pc += 1;
+                    continue;
                 }
+            };
 
-                // handle PUSH instruction
-                if let Some(push_num) = opcode.is_push() {
-                    Self::consume_constant_gas(&mut scope.gas_left, 3)?;
-                    let value = U256::from_big_endian(scope.code.slice(scope.pc+1,push_num));
-                    scope.stack.push(value)?;
-                    scope.pc += 1 + push_num;
-                    continue;
+            tracer.step(opcode, scope, exec_context);
+            let old_gas_left = scope.gas_left;
+
+            let interrupt = match self.execute_instruction(&opcode, scope, exec_context, host.clone(), tracer) {
+                Ok(interrupt) => interrupt,
+                Err(kind) => {
+                    tracer.failure(&kind, scope);
+                    return Err(kind);
                 }
-                
-                match self.next_instruction(&opcode, scope, exec_context, host.clone())? {
-                    None => (),
-                    Some(i) => {
-                        if i == Interrupt::Jump {
-                            continue;   // jump doesn't need incrementing pc.
-                        }else{
-                            scope.pc += 1;
-                            return Ok(i)
-                        }
-                    }
-                };
+            };
+            tracer.step_end(opcode, old_gas_left - scope.gas_left, scope, exec_context);
+
+            if let Some(interrupt) = interrupt {
+                return Ok(interrupt);
+            }
+        }
+    }
+
+    /// execute a single instruction and advance pc.
+    ///
+    /// returns the interrupt to hand over to the executor, if any.
+    fn execute_instruction<T: Tracer>(
+        &self,
+        opcode: &OpCode,
+        scope: &mut CallScope,
+        exec_context: &mut ExecutionContext,
+        host: Rc<RefCell<dyn Host>>,
+        tracer: &mut T,
+    ) -> Result<Option<Interrupt>, FailureKind> {
+        // handle PUSH instruction
+        if let Some(push_num) = opcode.is_push() {
+            Self::consume_constant_gas(&mut scope.gas_left, 3)?;
+            let value = U256::from_big_endian(scope.code.slice(scope.pc+1,push_num));
+            scope.stack.push(value)?;
+            scope.pc += 1 + push_num;
+            return Ok(None);
+        }
+
+        match self.next_instruction(opcode, scope, exec_context, host, tracer)? {
+            // jump doesn't need incrementing pc.
+            Some(Interrupt::Jump) => Ok(None),
+            interrupt => {
+                scope.pc += 1;
+                Ok(interrupt)
             }
-            
-            scope.pc += 1;
         }
     }
 
@@ -144,12 +146,13 @@ impl Interpreter {
     }
 
     /// interpret next instruction, returning interrupt if needed.
-    fn next_instruction(
+    fn next_instruction<T: Tracer>(
         &self,
         opcode: &OpCode,
         scope: &mut CallScope,
         exec_context: &mut ExecutionContext,
         host: Rc<RefCell<dyn Host>>,
+        tracer: &mut T,
     ) -> Result<Option<Interrupt>, FailureKind> {
         let mut host = host.borrow_mut();
         let stack = &mut scope.stack;
@@ -687,7 +690,11 @@ impl Interpreter {
             OpCode::BLOCKHASH => {
                 Self::consume_constant_gas(&mut scope.gas_left, 20)?;
                 let height = stack.pop()?;
-                let hash = host.get_blockhash(height.as_usize());
+                let hash = if height > U256::from(u64::MAX) {
+                    U256::zero()
+                }else{
+                    host.get_blockhash(height.as_usize())
+                };
                 stack.push_unchecked(hash);
                 Ok(None)
             },
@@ -751,6 +758,32 @@ impl Interpreter {
                 self.handle_context(ContextKind::BaseFee, tx_context, stack)?;
                 Ok(None)
             }
+            OpCode::BLOBHASH => {
+                // EIP-4844: https://eips.ethereum.org/EIPS/eip-4844
+                if exec_context.revision < Revision::Cancun {
+                    return Err(FailureKind::InvalidInstruction);
+                }
+                Self::consume_constant_gas(&mut scope.gas_left, 3)?;
+                let index = stack.pop()?;
+                let blob_hashes = host.get_tx_context().blob_hashes;
+                let hash = if index < U256::from(blob_hashes.len()) {
+                    U256::from_big_endian(&blob_hashes[index.as_usize()].0)
+                }else{
+                    U256::zero()
+                };
+                stack.push_unchecked(hash);
+                Ok(None)
+            },
+            OpCode::BLOBBASEFEE => {
+                // EIP-7516: https://eips.ethereum.org/EIPS/eip-7516
+                if exec_context.revision < Revision::Cancun {
+                    return Err(FailureKind::InvalidInstruction);
+                }
+                Self::consume_constant_gas(&mut scope.gas_left, 2)?;
+                let tx_context = host.get_tx_context();
+                self.handle_context(ContextKind::BlobBaseFee, tx_context, stack)?;
+                Ok(None)
+            },
             OpCode::POP => {
                 Self::consume_constant_gas(&mut scope.gas_left, 2)?;
                 stack.pop()?;
@@ -789,6 +822,7 @@ impl Interpreter {
                 };
                 let value = host.get_storage(scope.to, key);
                 stack.push_unchecked(value);
+                tracer.storage_read(scope.to, key, value);
 
                 // calculate dynamic gas
                 let gas =
@@ -835,6 +869,7 @@ impl Interpreter {
                 };
 
                 let storage_status = host.set_storage(scope.to, key, new_value);
+                tracer.storage_write(scope.to, key, new_value);
 
                 scope.refund_counter += calc_sstore_gas_refund(new_value, exec_context.revision, storage_status);
                 let gas = calc_sstore_gas_cost(new_value, exec_context.revision, access_status, storage_status);
@@ -890,6 +925,15 @@ impl Interpreter {
                 Self::consume_constant_gas(&mut scope.gas_left, 1)?;
                 Ok(None)
             }
+            OpCode::PUSH0 => {
+                // EIP-3855: https://eips.ethereum.org/EIPS/eip-3855
+                if exec_context.revision < Revision::Shanghai {
+                    return Err(FailureKind::InvalidInstruction);
+                }
+                Self::consume_constant_gas(&mut scope.gas_left, 2)?;
+                stack.push(U256::zero())?;
+                Ok(None)
+            },
             
             // PUSH instruction is already handled in `resume_interpret()`
 
@@ -938,21 +982,30 @@ impl Interpreter {
                 Ok(None)
             },
 
-            // OpCode::LOG0 => {
-            //     Ok(None)
-            // },
-            // OpCode::LOG1 => {
-            //     Ok(None)
-            // },
-            // OpCode::LOG2 => {
-            //     Ok(None)
-            // },
-            // OpCode::LOG3 => {
-            //     Ok(None)
-            // },
-            // OpCode::LOG4 => {
-            //     Ok(None)
-            // },
+            OpCode::LOG0
+            | OpCode::LOG1
+            | OpCode::LOG2
+            | OpCode::LOG3
+            | OpCode::LOG4 => {
+                // https://eips.ethereum.org/EIPS/eip-214
+                if scope.is_staticcall {
+                    return Err(FailureKind::StaticModeViolation);
+                }
+                let num_of_topics = opcode.to_usize() - OpCode::LOG0.to_usize();
+                let offset = stack.pop()?;
+                let size = stack.pop()?;
+                let mut topics = Vec::with_capacity(num_of_topics);
+                for _ in 0..num_of_topics {
+                    topics.push(stack.pop()?);
+                }
+
+                let (memory_cost, data) = ret(offset, size, memory, scope.gas_left)?;
+                let gas = 375 + 375 * num_of_topics as i64 + 8 * data.len() as i64 + memory_cost;
+                Self::consume_constant_gas(&mut scope.gas_left, gas)?;
+                host.emit_log(scope.to, &data, &topics);
+                tracer.log(scope.to, &data, &topics);
+                Ok(None)
+            },
 
             // OpCode::CREATE => {
             //     Ok(None)
@@ -1010,11 +1063,11 @@ impl Interpreter {
                         0
                     };
                 
-                let value_to_empty_cost = 
-                    if host.account_exists(address){
-                        0
+                let value_to_empty_cost =
+                    if Self::creates_account(&*host, address, value, exec_context.revision) {
+                        25000
                     }else{
-                        if !value.is_zero() { 25000 } else { 0 }
+                        0
                     };
 
                 let caller_balance = host.get_balance(scope.to);
@@ -1168,6 +1221,21 @@ impl Interpreter {
         }
     }
 
+    /// whether calling `address` with `value` brings a new account into the state.
+    ///
+    /// from Spurious Dragon, sending value to an empty account counts as creating one.
+    /// https://eips.ethereum.org/EIPS/eip-161
+    fn creates_account(host: &dyn Host, address: Address, value: U256, revision: Revision) -> bool {
+        if revision >= Revision::Spurious {
+            !value.is_zero()
+                && host.get_balance(address).is_zero()
+                && host.get_nonce(address) == 0
+                && host.get_code_size(address).is_zero()
+        }else{
+            !host.account_exists(address)
+        }
+    }
+
     fn consume_constant_gas(gas_left: &mut i64, gas: i64) -> Result<(), FailureKind> {
         if *gas_left - gas < 0 {
             return Err(FailureKind::OutOfGas);
@@ -1206,7 +1274,10 @@ impl Interpreter {
             },
             ContextKind::BaseFee => {
                 stack.push(context.base_fee)?;
-            }
+            },
+            ContextKind::BlobBaseFee => {
+                stack.push(context.blob_base_fee())?;
+            },
         };
 
         Ok(())
@@ -48,6 +48,7 @@ pub enum ContextKind {
     GasLimit,
     ChainId,
     BaseFee,
+    BlobBaseFee,
 }
 
 #[derive(Copy, Clone, Debug, PartialEq, Default)]
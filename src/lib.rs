@@ -3,4 +3,5 @@ pub mod interpreter;
 pub mod executor;
 pub mod utils;
 pub mod host;
-pub mod emulator;
\ No newline at end of file
+pub mod emulator;
+pub mod tracer;
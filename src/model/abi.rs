@@ -0,0 +1,336 @@
+use ethereum_types::{Address, U256};
+use serde_json::Value;
+use std::fmt;
+
+use crate::utils::{
+    i256::{I256, Sign},
+    keccak256,
+};
+
+/// solidity ABI type.
+#[derive(Clone, Debug, PartialEq)]
+pub enum AbiType {
+    Uint(usize),
+    Int(usize),
+    Address,
+    Bool,
+    FixedBytes(usize),
+    Bytes,
+    String,
+    Array(Box<AbiType>),
+    FixedArray(Box<AbiType>, usize),
+    Tuple(Vec<AbiType>),
+}
+
+/// value decoded from its ABI encoding.
+#[derive(Clone, Debug, PartialEq)]
+pub enum AbiValue {
+    Uint(U256),
+    Int(I256),
+    Address(Address),
+    Bool(bool),
+    FixedBytes(Vec<u8>),
+    Bytes(Vec<u8>),
+    String(String),
+    /// elements of a dynamic or fixed size array.
+    Array(Vec<AbiValue>),
+    Tuple(Vec<AbiValue>),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum AbiParseError {
+    /// the ABI is not JSON, or an entry lacks a field.
+    InvalidJson(String),
+    UnknownType(String),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Param {
+    pub name: String,
+    pub kind: AbiType,
+}
+
+/// custom error declared as `error Name(...)`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ErrorDefinition {
+    pub name: String,
+    pub inputs: Vec<Param>,
+}
+
+impl ErrorDefinition {
+    /// canonical signature, such as `InsufficientBalance(uint256,uint256)`.
+    pub fn signature(&self) -> String {
+        let inputs: Vec<String> = self.inputs.iter().map(|input| input.kind.to_string()).collect();
+        format!("{}({})", self.name, inputs.join(","))
+    }
+
+    pub fn selector(&self) -> [u8; 4] {
+        let hash = keccak256(self.signature().as_bytes());
+        [hash[0], hash[1], hash[2], hash[3]]
+    }
+
+    /// decode the arguments of the error, following its selector.
+    pub fn decode(&self, data: &[u8]) -> Option<Vec<AbiValue>> {
+        if data.get(..4)? != self.selector() {
+            return None;
+        }
+        let kinds: Vec<AbiType> = self.inputs.iter().map(|input| input.kind.clone()).collect();
+        decode(&kinds, &data[4..])
+    }
+}
+
+/// custom errors of a contract ABI.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Abi {
+    pub errors: Vec<ErrorDefinition>,
+}
+
+impl Abi {
+    /// parse the JSON ABI output by solc, keeping the errors.
+    pub fn from_json(json: &str) -> Result<Self, AbiParseError> {
+        let entries: Vec<Value> = serde_json::from_str(json)
+            .map_err(|err| AbiParseError::InvalidJson(err.to_string()))?;
+
+        let mut errors = Vec::new();
+        for entry in entries.iter().filter(|entry| entry["type"] == "error") {
+            let name = entry["name"].as_str()
+                .ok_or_else(|| AbiParseError::InvalidJson("error without name".to_string()))?;
+            let inputs = match entry["inputs"].as_array() {
+                Some(inputs) => inputs.iter().map(parse_param).collect::<Result<Vec<_>, _>>()?,
+                None => Vec::new(),
+            };
+            errors.push(ErrorDefinition {
+                name: name.to_string(),
+                inputs,
+            });
+        }
+        Ok(Self { errors })
+    }
+
+    /// error whose selector starts `data`.
+    pub fn error(&self, data: &[u8]) -> Option<&ErrorDefinition> {
+        let selector = data.get(..4)?;
+        self.errors.iter().find(|error| error.selector() == selector)
+    }
+}
+
+impl AbiType {
+    /// parse a type such as `uint256`, `bytes32[2]` or `(address,bool)[]`.
+    pub fn parse(kind: &str) -> Result<Self, AbiParseError> {
+        let unknown = || AbiParseError::UnknownType(kind.to_string());
+
+        // base type ends where the array dimensions start.
+        let base_end = match kind.strip_prefix('(') {
+            Some(_) => {
+                let mut depth = 0;
+                let close = kind.char_indices().find(|(_, c)| {
+                    match c {
+                        '(' => depth += 1,
+                        ')' => depth -= 1,
+                        _ => (),
+                    }
+                    depth == 0
+                });
+                close.ok_or_else(unknown)?.0 + 1
+            },
+            None => kind.find('[').unwrap_or(kind.len()),
+        };
+        let (base, dimensions) = kind.split_at(base_end);
+
+        let base = match base {
+            "address" => AbiType::Address,
+            "bool" => AbiType::Bool,
+            "string" => AbiType::String,
+            "bytes" => AbiType::Bytes,
+            "uint" => AbiType::Uint(256),
+            "int" => AbiType::Int(256),
+            _ if base.starts_with('(') => {
+                let inner = &base[1..base.len() - 1];
+                AbiType::Tuple(split_top_level(inner).into_iter().map(AbiType::parse).collect::<Result<_, _>>()?)
+            },
+            _ => {
+                let (prefix, bits) = base.split_at(base.find(|c: char| c.is_ascii_digit()).ok_or_else(unknown)?);
+                let bits: usize = bits.parse().map_err(|_| unknown())?;
+                match prefix {
+                    "uint" if bits.is_multiple_of(8) && (8..=256).contains(&bits) => AbiType::Uint(bits),
+                    "int" if bits.is_multiple_of(8) && (8..=256).contains(&bits) => AbiType::Int(bits),
+                    "bytes" if (1..=32).contains(&bits) => AbiType::FixedBytes(bits),
+                    _ => return Err(unknown()),
+                }
+            },
+        };
+        with_dimensions(base, dimensions).ok_or_else(unknown)
+    }
+
+    fn is_dynamic(&self) -> bool {
+        match self {
+            AbiType::Bytes | AbiType::String | AbiType::Array(_) => true,
+            AbiType::FixedArray(kind, _) => kind.is_dynamic(),
+            AbiType::Tuple(kinds) => kinds.iter().any(AbiType::is_dynamic),
+            _ => false,
+        }
+    }
+
+    /// size of the encoding in the head of the enclosing tuple.
+    fn head_size(&self) -> usize {
+        if self.is_dynamic() {
+            return 32;
+        }
+        match self {
+            AbiType::FixedArray(kind, len) => kind.head_size() * len,
+            AbiType::Tuple(kinds) => kinds.iter().map(AbiType::head_size).sum(),
+            _ => 32,
+        }
+    }
+
+    /// decode the value encoded at the start of `data`.
+    fn decode(&self, data: &[u8]) -> Option<AbiValue> {
+        let value = match self {
+            AbiType::Uint(_) => AbiValue::Uint(word(data, 0)?),
+            AbiType::Int(_) => AbiValue::Int(I256::from(word(data, 0)?)),
+            AbiType::Address => AbiValue::Address(Address::from_slice(data.get(12..32)?)),
+            AbiType::Bool => AbiValue::Bool(!word(data, 0)?.is_zero()),
+            AbiType::FixedBytes(len) => AbiValue::FixedBytes(data.get(..*len)?.to_vec()),
+            AbiType::Bytes => AbiValue::Bytes(bytes(data)?.to_vec()),
+            AbiType::String => AbiValue::String(String::from_utf8(bytes(data)?.to_vec()).ok()?),
+            AbiType::Array(kind) => {
+                let len = offset(data, 0)?;
+                // each element takes at least a word.
+                if len > data.len() / 32 {
+                    return None;
+                }
+                AbiValue::Array(decode(&vec![(**kind).clone(); len], &data[32..])?)
+            },
+            AbiType::FixedArray(kind, len) => AbiValue::Array(decode(&vec![(**kind).clone(); *len], data)?),
+            AbiType::Tuple(kinds) => AbiValue::Tuple(decode(kinds, data)?),
+        };
+        Some(value)
+    }
+}
+
+/// decode the ABI encoding of a tuple of `kinds`, as arguments are encoded.
+pub fn decode(kinds: &[AbiType], data: &[u8]) -> Option<Vec<AbiValue>> {
+    let mut head = 0;
+    let mut values = Vec::with_capacity(kinds.len());
+    for kind in kinds {
+        let value = if kind.is_dynamic() {
+            kind.decode(data.get(offset(data, head)?..)?)?
+        }else{
+            kind.decode(data.get(head..)?)?
+        };
+        values.push(value);
+        head += kind.head_size();
+    }
+    Some(values)
+}
+
+fn word(data: &[u8], offset: usize) -> Option<U256> {
+    data.get(offset..offset + 32).map(U256::from_big_endian)
+}
+
+/// word read as an offset or a length, which must be within the data.
+fn offset(data: &[u8], at: usize) -> Option<usize> {
+    let value = word(data, at)?;
+    (value <= U256::from(data.len())).then(|| value.as_usize())
+}
+
+/// content of `bytes` or `string`, following its length.
+fn bytes(data: &[u8]) -> Option<&[u8]> {
+    let len = offset(data, 0)?;
+    data.get(32..32 + len)
+}
+
+/// apply array dimensions such as `[2][]` to `kind`.
+fn with_dimensions(mut kind: AbiType, mut dimensions: &str) -> Option<AbiType> {
+    while !dimensions.is_empty() {
+        let close = dimensions.find(']')?;
+        let dimension = dimensions.strip_prefix('[')?.get(..close - 1)?;
+        kind = match dimension {
+            "" => AbiType::Array(Box::new(kind)),
+            len => AbiType::FixedArray(Box::new(kind), len.parse().ok()?),
+        };
+        dimensions = &dimensions[close + 1..];
+    }
+    Some(kind)
+}
+
+/// split the types of a tuple at the commas outside of nested tuples.
+fn split_top_level(types: &str) -> Vec<&str> {
+    if types.is_empty() {
+        return Vec::new();
+    }
+    let mut parts = Vec::new();
+    let (mut depth, mut start) = (0, 0);
+    for (index, c) in types.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            ',' if depth == 0 => {
+                parts.push(&types[start..index]);
+                start = index + 1;
+            },
+            _ => (),
+        }
+    }
+    parts.push(&types[start..]);
+    parts
+}
+
+/// parameter of an ABI entry, whose tuple type is given by its components.
+fn parse_param(param: &Value) -> Result<Param, AbiParseError> {
+    let kind = param["type"].as_str()
+        .ok_or_else(|| AbiParseError::InvalidJson("parameter without type".to_string()))?;
+    let kind = match kind.strip_prefix("tuple") {
+        Some(dimensions) => {
+            let components = param["components"].as_array()
+                .ok_or_else(|| AbiParseError::InvalidJson("tuple without components".to_string()))?;
+            let components = components.iter()
+                .map(|component| parse_param(component).map(|param| param.kind))
+                .collect::<Result<Vec<_>, _>>()?;
+            with_dimensions(AbiType::Tuple(components), dimensions)
+                .ok_or_else(|| AbiParseError::UnknownType(kind.to_string()))?
+        },
+        None => AbiType::parse(kind)?,
+    };
+    Ok(Param {
+        name: param["name"].as_str().unwrap_or_default().to_string(),
+        kind,
+    })
+}
+
+impl fmt::Display for AbiType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AbiType::Uint(bits) => write!(f, "uint{}", bits),
+            AbiType::Int(bits) => write!(f, "int{}", bits),
+            AbiType::Address => write!(f, "address"),
+            AbiType::Bool => write!(f, "bool"),
+            AbiType::FixedBytes(len) => write!(f, "bytes{}", len),
+            AbiType::Bytes => write!(f, "bytes"),
+            AbiType::String => write!(f, "string"),
+            AbiType::Array(kind) => write!(f, "{}[]", kind),
+            AbiType::FixedArray(kind, len) => write!(f, "{}[{}]", kind, len),
+            AbiType::Tuple(kinds) => {
+                let kinds: Vec<String> = kinds.iter().map(|kind| kind.to_string()).collect();
+                write!(f, "({})", kinds.join(","))
+            },
+        }
+    }
+}
+
+impl fmt::Display for AbiValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let join = |values: &[AbiValue]| values.iter().map(|value| value.to_string()).collect::<Vec<_>>().join(", ");
+        match self {
+            AbiValue::Uint(value) => write!(f, "{}", value),
+            AbiValue::Int(I256(Sign::Minus, value)) => write!(f, "-{}", value),
+            AbiValue::Int(I256(_, value)) => write!(f, "{}", value),
+            AbiValue::Address(address) => write!(f, "{:?}", address),
+            AbiValue::Bool(value) => write!(f, "{}", value),
+            AbiValue::FixedBytes(bytes) | AbiValue::Bytes(bytes) => write!(f, "0x{}", hex::encode(bytes)),
+            AbiValue::String(value) => write!(f, "{:?}", value),
+            AbiValue::Array(values) => write!(f, "[{}]", join(values)),
+            AbiValue::Tuple(values) => write!(f, "({})", join(values)),
+        }
+    }
+}
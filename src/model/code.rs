@@ -29,6 +29,18 @@ impl Code {
         self
     }
 
+    /// pc of each instruction, skipping the data of PUSH instructions.
+    pub fn instruction_pcs(&self) -> Vec<usize> {
+        let mut pcs = Vec::new();
+        let mut pc = 0;
+        while pc < self.0.len() {
+            pcs.push(pc);
+            let push_size = OpCode::from_u8(self.0[pc]).and_then(|op| op.is_push()).unwrap_or(0);
+            pc += 1 + push_size;
+        }
+        pcs
+    }
+
     pub fn get_range(&self, offset: usize, size: usize) -> Vec<u8> {
         if offset + size < self.0.len() {
             let data = &self.0[offset..offset+size];
@@ -1,7 +1,11 @@
-use ethereum_types::{Address, U256};
+use ethereum_types::{Address, H256, U256};
 use bytes::Bytes;
 use std::collections::HashMap;
 
+use super::abi::Abi;
+use super::fee::calc_blob_base_fee;
+use super::revert::RevertReason;
+
 /// https://evmc.ethereum.org/structevmc__message.html
 #[derive(Clone, Debug, PartialEq)]
 pub struct Message {
@@ -122,6 +126,22 @@ impl Output {
         }
     }
 
+    /// reason of a revert, encoded as `Error(string)` or `Panic(uint256)`.
+    pub fn revert_reason(&self) -> Option<RevertReason> {
+        match self.status_code {
+            StatusCode::Failure(FailureKind::Revert) => RevertReason::decode(&self.data),
+            _ => None,
+        }
+    }
+
+    /// reason of a revert, also decoding the custom errors of `abi`.
+    pub fn revert_reason_with(&self, abi: &Abi) -> Option<RevertReason> {
+        match self.status_code {
+            StatusCode::Failure(FailureKind::Revert) => RevertReason::decode_with(&self.data, abi),
+            _ => None,
+        }
+    }
+
     pub fn new_revert(gas_left: i64, data: Bytes) -> Self {
         let size = data.len();
         Output {
@@ -165,6 +185,8 @@ pub enum FailureKind {
     InternalError(String),
     Rejected,
     OutOfMemory,
+    /// the nonce of the transaction is not the nonce of its sender.
+    InvalidNonce,
 }
 
 #[derive(Clone, Copy, Debug, PartialEq)]
@@ -200,6 +222,17 @@ pub struct TxContext {
     pub difficulty: U256,
     pub chain_id: U256,
     pub base_fee: U256,
+    /// https://eips.ethereum.org/EIPS/eip-4844
+    pub excess_blob_gas: u64,
+    /// versioned hashes of the blobs carried by the transaction, which BLOBHASH returns.
+    pub blob_hashes: Vec<H256>,
+}
+
+impl TxContext {
+    /// blob base fee of the block, which BLOBBASEFEE returns.
+    pub fn blob_base_fee(&self) -> U256 {
+        calc_blob_base_fee(self.excess_blob_gas)
+    }
 }
 
 impl Default for TxContext {
@@ -214,6 +247,8 @@ impl Default for TxContext {
             difficulty: U256::default(),
             chain_id: U256::from(0),
             base_fee: U256::from(0),
+            excess_blob_gas: 0,
+            blob_hashes: Vec::new(),
         }
     }
 }
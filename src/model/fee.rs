@@ -0,0 +1,185 @@
+use std::cmp::min;
+
+use ethereum_types::{H256, U256};
+
+use super::evmc::FailureKind;
+
+/// fee parameters of a dynamic fee transaction.
+///
+/// https://eips.ethereum.org/EIPS/eip-1559
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct DynamicFee {
+    /// the maximum total fee per gas the sender is willing to pay, base fee included.
+    pub max_fee_per_gas: U256,
+    /// the maximum fee per gas paid to the coinbase on top of the base fee.
+    pub max_priority_fee_per_gas: U256,
+}
+
+impl DynamicFee {
+    pub fn new(max_fee_per_gas: U256, max_priority_fee_per_gas: U256) -> Self {
+        Self {
+            max_fee_per_gas,
+            max_priority_fee_per_gas,
+        }
+    }
+
+    /// check the fee caps against the base fee of the block.
+    pub fn validate(&self, base_fee: U256) -> Result<(), FailureKind> {
+        if self.max_priority_fee_per_gas > self.max_fee_per_gas {
+            return Err(FailureKind::Rejected);
+        }
+        if self.max_fee_per_gas < base_fee {
+            return Err(FailureKind::Rejected);
+        }
+        Ok(())
+    }
+
+    /// fee per gas paid to the coinbase.
+    ///
+    /// `validate()` must have succeeded for `base_fee`.
+    pub fn priority_fee_per_gas(&self, base_fee: U256) -> U256 {
+        min(self.max_priority_fee_per_gas, self.max_fee_per_gas - base_fee)
+    }
+
+    /// fee per gas actually paid by the sender, which is what GASPRICE returns.
+    ///
+    /// `validate()` must have succeeded for `base_fee`.
+    pub fn effective_gas_price(&self, base_fee: U256) -> U256 {
+        base_fee + self.priority_fee_per_gas(base_fee)
+    }
+}
+
+/// blob gas consumed by each blob.
+pub const GAS_PER_BLOB: u64 = 1 << 17;
+pub const TARGET_BLOB_GAS_PER_BLOCK: u64 = 3 * GAS_PER_BLOB;
+pub const MAX_BLOB_GAS_PER_BLOCK: u64 = 6 * GAS_PER_BLOB;
+pub const MIN_BASE_FEE_PER_BLOB_GAS: u64 = 1;
+pub const BLOB_BASE_FEE_UPDATE_FRACTION: u64 = 3338477;
+/// the first byte of a versioned hash of a KZG commitment.
+pub const VERSIONED_HASH_VERSION_KZG: u8 = 0x01;
+
+/// fee parameters of a blob transaction.
+///
+/// https://eips.ethereum.org/EIPS/eip-4844
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct BlobFee {
+    /// the maximum fee per blob gas the sender is willing to pay.
+    pub max_fee_per_blob_gas: U256,
+    pub num_of_blobs: usize,
+}
+
+impl BlobFee {
+    pub fn new(max_fee_per_blob_gas: U256, num_of_blobs: usize) -> Self {
+        Self {
+            max_fee_per_blob_gas,
+            num_of_blobs,
+        }
+    }
+
+    pub fn blob_gas(&self) -> u64 {
+        GAS_PER_BLOB * self.num_of_blobs as u64
+    }
+
+    /// check the number of blobs and the fee cap against the blob base fee of the block.
+    pub fn validate(&self, blob_base_fee: U256) -> Result<(), FailureKind> {
+        if self.num_of_blobs == 0 || self.blob_gas() > MAX_BLOB_GAS_PER_BLOCK {
+            return Err(FailureKind::Rejected);
+        }
+        if self.max_fee_per_blob_gas < blob_base_fee {
+            return Err(FailureKind::Rejected);
+        }
+        Ok(())
+    }
+}
+
+/// every versioned hash must start with the KZG version byte.
+pub fn validate_versioned_hashes(hashes: &[H256]) -> Result<(), FailureKind> {
+    if hashes.iter().all(|hash| hash.0[0] == VERSIONED_HASH_VERSION_KZG) {
+        Ok(())
+    }else{
+        Err(FailureKind::Rejected)
+    }
+}
+
+/// blob base fee of a block with `excess_blob_gas`.
+pub fn calc_blob_base_fee(excess_blob_gas: u64) -> U256 {
+    fake_exponential(
+        U256::from(MIN_BASE_FEE_PER_BLOB_GAS),
+        U256::from(excess_blob_gas),
+        U256::from(BLOB_BASE_FEE_UPDATE_FRACTION),
+    )
+}
+
+/// excess blob gas of a block given its parent.
+pub fn calc_excess_blob_gas(parent_excess_blob_gas: u64, parent_blob_gas_used: u64) -> u64 {
+    (parent_excess_blob_gas + parent_blob_gas_used).saturating_sub(TARGET_BLOB_GAS_PER_BLOCK)
+}
+
+/// approximates `factor * e ** (numerator / denominator)` using Taylor expansion.
+fn fake_exponential(factor: U256, numerator: U256, denominator: U256) -> U256 {
+    let mut i = U256::one();
+    let mut output = U256::zero();
+    let mut numerator_accum = factor * denominator;
+    while !numerator_accum.is_zero() {
+        output = output.saturating_add(numerator_accum);
+        numerator_accum = match numerator_accum.checked_mul(numerator) {
+            Some(accum) => accum / (denominator * i),
+            None => return U256::max_value(),
+        };
+        i += U256::one();
+    }
+    output / denominator
+}
+
+#[cfg(test)]
+mod test {
+    use crate::model::fee::*;
+
+    #[test]
+    pub fn test_dynamic_fee_validation() {
+        let fee = DynamicFee::new(U256::from(100), U256::from(2));
+        assert_eq!(Ok(()), fee.validate(U256::from(100)));
+        assert_eq!(Err(FailureKind::Rejected), fee.validate(U256::from(101)));
+
+        let fee = DynamicFee::new(U256::from(1), U256::from(2));
+        assert_eq!(Err(FailureKind::Rejected), fee.validate(U256::zero()));
+    }
+
+    #[test]
+    pub fn test_effective_gas_price() {
+        let fee = DynamicFee::new(U256::from(100), U256::from(2));
+        assert_eq!(U256::from(2), fee.priority_fee_per_gas(U256::from(10)));
+        assert_eq!(U256::from(12), fee.effective_gas_price(U256::from(10)));
+
+        // priority fee is capped by what is left above the base fee.
+        assert_eq!(U256::from(1), fee.priority_fee_per_gas(U256::from(99)));
+        assert_eq!(U256::from(100), fee.effective_gas_price(U256::from(99)));
+    }
+
+    #[test]
+    pub fn test_blob_base_fee() {
+        assert_eq!(U256::from(1), calc_blob_base_fee(0));
+        assert_eq!(U256::from(2), calc_blob_base_fee(BLOB_BASE_FEE_UPDATE_FRACTION));
+        assert_eq!(U256::from(22026), calc_blob_base_fee(10 * BLOB_BASE_FEE_UPDATE_FRACTION));
+        // does not overflow.
+        assert_eq!(U256::max_value(), calc_blob_base_fee(u64::MAX));
+
+        assert_eq!(0, calc_excess_blob_gas(0, TARGET_BLOB_GAS_PER_BLOCK));
+        assert_eq!(GAS_PER_BLOB, calc_excess_blob_gas(GAS_PER_BLOB, TARGET_BLOB_GAS_PER_BLOCK));
+        assert_eq!(0, calc_excess_blob_gas(GAS_PER_BLOB, GAS_PER_BLOB));
+    }
+
+    #[test]
+    pub fn test_blob_fee_validation() {
+        let fee = BlobFee::new(U256::from(7), 2);
+        assert_eq!(2 * GAS_PER_BLOB, fee.blob_gas());
+        assert_eq!(Ok(()), fee.validate(U256::from(7)));
+        assert_eq!(Err(FailureKind::Rejected), fee.validate(U256::from(8)));
+
+        assert_eq!(Err(FailureKind::Rejected), BlobFee::new(U256::from(7), 0).validate(U256::one()));
+        assert_eq!(Err(FailureKind::Rejected), BlobFee::new(U256::from(7), 7).validate(U256::one()));
+
+        assert_eq!(Ok(()), validate_versioned_hashes(&[H256::from_slice(&[VERSIONED_HASH_VERSION_KZG; 32])]));
+        assert_eq!(Err(FailureKind::Rejected), validate_versioned_hashes(&[H256::zero()]));
+    }
+}
@@ -2,4 +2,13 @@ pub mod opcode;
 pub mod code;
 pub mod revision;
 pub mod evmc;
-pub mod precompiles;
\ No newline at end of file
+pub mod precompiles;
+pub mod fee;
+pub mod transaction;
+pub mod receipt;
+pub mod withdrawal;
+pub mod system;
+pub mod proof;
+pub mod srcmap;
+pub mod abi;
+pub mod revert;
@@ -58,6 +58,8 @@ pub enum OpCode {
     CHAINID = 0x46,
     SELFBALANCE = 0x47,
     BASEFEE = 0x48,
+    BLOBHASH = 0x49,
+    BLOBBASEFEE = 0x4a,
 
     POP = 0x50,
     MLOAD = 0x51,
@@ -72,6 +74,8 @@ pub enum OpCode {
     GAS = 0x5a,
     JUMPDEST = 0x5b,
 
+    PUSH0 = 0x5f,
+
     PUSH1 = 0x60,
     PUSH2 = 0x61,
     PUSH3 = 0x62,
@@ -179,8 +183,9 @@ impl OpCode {
         if (n <= 0x0b)
             || (0x10 <= n && n <= 0x1d)
             || n == 0x20
-            || (0x30 <= n && n <= 0x48)
+            || (0x30 <= n && n <= 0x4a)
             || (0x50 <= n && n <= 0x5b)
+            || n == 0x5f
             || (0x60 <= n && n <= 0xa4)
             || (0xf0 <= n && n <= 0xf5)
             || (0xfd <= n)
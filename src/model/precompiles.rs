@@ -1,8 +1,12 @@
+use ethereum_types::Address;
+
 use super::revision::Revision;
 
 pub const NUM_OF_FRONTIER_CONTRACTS: usize = 4;
 pub const NUM_OF_BYZANTIUM_CONTRACTS: usize = 8;
 pub const NUM_OF_ISTANBUL_CONTRACTS: usize = 9;
+pub const NUM_OF_CANCUN_CONTRACTS: usize = 10;
+pub const NUM_OF_PRAGUE_CONTRACTS: usize = 17;
 
 pub fn num_of_precompiles(revision: Revision) -> u8 {
     match revision {
@@ -15,5 +19,20 @@ pub fn num_of_precompiles(revision: Revision) -> u8 {
         Revision::Istanbul | Revision::Berlin | Revision::London | Revision::Shanghai => {
             NUM_OF_ISTANBUL_CONTRACTS as u8
         }
+        Revision::Cancun => {
+            NUM_OF_CANCUN_CONTRACTS as u8
+        }
+        Revision::Prague => {
+            NUM_OF_PRAGUE_CONTRACTS as u8
+        }
     }
-}
\ No newline at end of file
+}
+
+pub const ECRECOVER_ADDRESS: u64 = 0x01;
+
+/// true if `address` is a precompiled contract in `revision`.
+pub fn is_precompile(address: Address, revision: Revision) -> bool {
+    address.0[..12].iter().all(|b| *b == 0)
+        && address.to_low_u64_be() >= 1
+        && address.to_low_u64_be() <= num_of_precompiles(revision) as u64
+}
@@ -0,0 +1,114 @@
+use ethereum_types::{Address, H256, U256};
+use bytes::Bytes;
+
+use crate::utils::{keccak256, KECCAK_EMPTY};
+use crate::utils::rlp::{self, encode_h256, encode_list, encode_u256, encode_u64, RlpError};
+use crate::utils::trie::{verify_proof, ProofError, EMPTY_ROOT};
+
+/// Merkle proof of a storage slot against the storage root of its account.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct StorageProof {
+    pub key: U256,
+    pub value: U256,
+    /// RLP encoded trie nodes from the storage root to the slot.
+    pub proof: Vec<Bytes>,
+}
+
+/// Merkle proof of an account and some of its storage slots, as returned by `eth_getProof`.
+///
+/// https://eips.ethereum.org/EIPS/eip-1186
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct AccountProof {
+    pub address: Address,
+    pub nonce: u64,
+    pub balance: U256,
+    pub storage_hash: H256,
+    pub code_hash: H256,
+    /// RLP encoded trie nodes from the state root to the account.
+    pub account_proof: Vec<Bytes>,
+    pub storage_proof: Vec<StorageProof>,
+}
+
+/// key of the storage slot `key` in the storage trie.
+pub fn storage_key(key: U256) -> H256 {
+    let mut slot = [0u8; 32];
+    key.to_big_endian(&mut slot);
+    keccak256(&slot)
+}
+
+/// RLP encoding of an account in the state trie.
+pub fn encode_account(nonce: u64, balance: U256, storage_hash: &H256, code_hash: &H256) -> Vec<u8> {
+    encode_list(&[
+        encode_u64(nonce),
+        encode_u256(balance),
+        encode_h256(storage_hash),
+        encode_h256(code_hash),
+    ])
+}
+
+/// inverse of `encode_account()`, returning (nonce, balance, storage hash, code hash).
+pub fn decode_account(data: &[u8]) -> Result<(u64, U256, H256, H256), RlpError> {
+    let item = rlp::decode(data)?;
+    match item.as_list()? {
+        [nonce, balance, storage_hash, code_hash] => Ok((
+            nonce.as_u64()?,
+            balance.as_u256()?,
+            storage_hash.as_h256()?,
+            code_hash.as_h256()?,
+        )),
+        _ => Err(RlpError::InvalidItemCount),
+    }
+}
+
+/// trie nodes and code proving the accounts and storage slots a transaction touches,
+/// which is enough to execute it without the rest of the state.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct StateWitness {
+    pub state_root: H256,
+    /// RLP encoded nodes of the state trie and storage tries.
+    pub nodes: Vec<Bytes>,
+    /// code of the touched accounts.
+    pub codes: Vec<Bytes>,
+}
+
+impl AccountProof {
+    /// whether the fields are those of an account absent from the state.
+    pub fn is_empty(&self) -> bool {
+        self.nonce == 0
+            && self.balance.is_zero()
+            && self.storage_hash == EMPTY_ROOT
+            && self.code_hash == KECCAK_EMPTY
+    }
+
+    /// check the account against `state_root` and every storage slot against `storage_hash`.
+    pub fn verify(&self, state_root: H256) -> Result<(), ProofError> {
+        let proven = verify_proof(state_root, keccak256(self.address.as_bytes()).as_bytes(), &to_vecs(&self.account_proof))?;
+        let expected = encode_account(self.nonce, self.balance, &self.storage_hash, &self.code_hash);
+        match proven {
+            Some(account) if account == expected => (),
+            None if self.is_empty() => (),
+            _ => return Err(ProofError::ValueMismatch),
+        }
+
+        for storage in &self.storage_proof {
+            storage.verify(self.storage_hash)?;
+        }
+        Ok(())
+    }
+}
+
+impl StorageProof {
+    /// check the slot against `storage_root`. slot absent from the trie holds zero.
+    pub fn verify(&self, storage_root: H256) -> Result<(), ProofError> {
+        let proven = verify_proof(storage_root, storage_key(self.key).as_bytes(), &to_vecs(&self.proof))?;
+        let expected = if self.value.is_zero() { None } else { Some(encode_u256(self.value)) };
+        if proven != expected {
+            return Err(ProofError::ValueMismatch);
+        }
+        Ok(())
+    }
+}
+
+fn to_vecs(proof: &[Bytes]) -> Vec<Vec<u8>> {
+    proof.iter().map(|node| node.to_vec()).collect()
+}
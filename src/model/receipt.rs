@@ -0,0 +1,118 @@
+use ethereum_types::{Bloom, BloomInput, H256};
+
+use crate::host::stateful::LogRecord;
+use crate::utils::rlp::{encode_address, encode_bytes, encode_list, encode_u64};
+use crate::utils::trie::ordered_trie_root;
+use super::evmc::StatusCode;
+use super::transaction::LEGACY_TX_TYPE;
+
+/// receipt of a transaction included in a block.
+///
+/// https://eips.ethereum.org/EIPS/eip-658
+#[derive(Clone, Debug, PartialEq)]
+pub struct Receipt {
+    pub tx_type: u8,
+    pub status_code: StatusCode,
+    /// gas used by this transaction after refund.
+    pub gas_used: u64,
+    /// gas used in the block up to and including this transaction.
+    pub cumulative_gas_used: u64,
+    pub logs: Vec<LogRecord>,
+    pub logs_bloom: Bloom,
+}
+
+impl Receipt {
+    pub fn new(tx_type: u8, status_code: StatusCode, gas_used: u64, cumulative_gas_used: u64, logs: Vec<LogRecord>) -> Self {
+        let logs_bloom = logs_bloom(&logs);
+        Self {
+            tx_type,
+            status_code,
+            gas_used,
+            cumulative_gas_used,
+            logs,
+            logs_bloom,
+        }
+    }
+
+    pub fn is_success(&self) -> bool {
+        self.status_code == StatusCode::Success
+    }
+
+    /// `rlp([status, cumulative_gas_used, logs_bloom, logs])`, prefixed by the transaction type if typed.
+    ///
+    /// https://eips.ethereum.org/EIPS/eip-2718#receipts
+    pub fn encode(&self) -> Vec<u8> {
+        let logs: Vec<Vec<u8>> = self.logs.iter().map(encode_log).collect();
+        let list = encode_list(&[
+            encode_u64(self.is_success() as u64),
+            encode_u64(self.cumulative_gas_used),
+            encode_bytes(self.logs_bloom.as_bytes()),
+            encode_list(&logs),
+        ]);
+
+        if self.tx_type == LEGACY_TX_TYPE {
+            return list;
+        }
+        let mut out = vec![self.tx_type];
+        out.extend(list);
+        out
+    }
+}
+
+/// root of the receipts trie of a block.
+pub fn receipts_root(receipts: &[Receipt]) -> H256 {
+    ordered_trie_root(receipts.iter().map(Receipt::encode))
+}
+
+fn encode_log(log: &LogRecord) -> Vec<u8> {
+    let topics: Vec<Vec<u8>> = log.topics.iter()
+        .map(|topic| {
+            let mut bytes = [0u8; 32];
+            topic.to_big_endian(&mut bytes);
+            encode_bytes(&bytes)
+        })
+        .collect();
+    encode_list(&[
+        encode_address(&log.creator),
+        encode_list(&topics),
+        encode_bytes(&log.data),
+    ])
+}
+
+/// 2048-bit bloom filter of the addresses and topics of `logs`.
+pub fn logs_bloom(logs: &[LogRecord]) -> Bloom {
+    let mut bloom = Bloom::default();
+    for log in logs {
+        bloom.accrue(BloomInput::Raw(log.creator.as_bytes()));
+        for topic in &log.topics {
+            let mut bytes = [0u8; 32];
+            topic.to_big_endian(&mut bytes);
+            bloom.accrue(BloomInput::Raw(&bytes));
+        }
+    }
+    bloom
+}
+
+#[cfg(test)]
+mod test {
+    use ethereum_types::{Address, H256, U256};
+    use crate::model::receipt::*;
+
+    #[test]
+    pub fn test_logs_bloom() {
+        let log = LogRecord {
+            creator: Address::from_low_u64_be(0xaa),
+            data: Default::default(),
+            topics: vec![U256::from(1)],
+        };
+        let bloom = logs_bloom(&[log]);
+
+        // each input sets 3 bits.
+        let bits: u32 = bloom.as_bytes().iter().map(|b| b.count_ones()).sum();
+        assert!(bits > 0 && bits <= 6);
+        assert!(bloom.contains_input(BloomInput::Raw(Address::from_low_u64_be(0xaa).as_bytes())));
+        assert!(bloom.contains_input(BloomInput::Raw(H256::from_low_u64_be(1).as_bytes())));
+        assert!(!bloom.contains_input(BloomInput::Raw(H256::from_low_u64_be(2).as_bytes())));
+        assert_eq!(Bloom::default(), logs_bloom(&[]));
+    }
+}
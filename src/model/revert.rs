@@ -0,0 +1,147 @@
+use ethereum_types::U256;
+use std::fmt;
+
+use super::abi::{Abi, AbiType, AbiValue, decode};
+
+/// selector of `Error(string)`, which `require` and `revert("...")` encode the reason with.
+const ERROR_SELECTOR: [u8; 4] = [0x08, 0xc3, 0x79, 0xa0];
+/// selector of `Panic(uint256)`, which solidity raises on failed assertions and runtime errors.
+const PANIC_SELECTOR: [u8; 4] = [0x4e, 0x48, 0x7b, 0x71];
+
+/// solidity panic codes.
+///
+/// https://docs.soliditylang.org/en/latest/control-structures.html#panic-via-assert-and-error-via-require
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PanicCode {
+    /// generic compiler inserted panic.
+    Generic = 0x00,
+    /// `assert` failed.
+    Assert = 0x01,
+    /// arithmetic overflowed or underflowed outside of an `unchecked` block.
+    ArithmeticOverflow = 0x11,
+    DivisionByZero = 0x12,
+    /// value too big or negative converted to an enum.
+    InvalidEnum = 0x21,
+    /// storage byte array incorrectly encoded.
+    InvalidStorageByteArray = 0x22,
+    /// `.pop()` on an empty array.
+    EmptyArrayPop = 0x31,
+    /// array, `bytesN` or slice index out of bounds.
+    IndexOutOfBounds = 0x32,
+    /// too much memory allocated, or array too large.
+    OutOfMemory = 0x41,
+    /// zero-initialized variable of internal function type called.
+    ZeroFunction = 0x51,
+}
+
+impl PanicCode {
+    pub fn from_code(code: U256) -> Option<Self> {
+        let code = match code > U256::from(u8::MAX) {
+            true => return None,
+            false => code.as_u32() as u8,
+        };
+        let panic = match code {
+            0x00 => PanicCode::Generic,
+            0x01 => PanicCode::Assert,
+            0x11 => PanicCode::ArithmeticOverflow,
+            0x12 => PanicCode::DivisionByZero,
+            0x21 => PanicCode::InvalidEnum,
+            0x22 => PanicCode::InvalidStorageByteArray,
+            0x31 => PanicCode::EmptyArrayPop,
+            0x32 => PanicCode::IndexOutOfBounds,
+            0x41 => PanicCode::OutOfMemory,
+            0x51 => PanicCode::ZeroFunction,
+            _ => return None,
+        };
+        Some(panic)
+    }
+
+    pub fn code(self) -> u8 {
+        self as u8
+    }
+
+    /// meaning of the code, as geth reports it.
+    pub fn description(self) -> &'static str {
+        match self {
+            PanicCode::Generic => "generic panic",
+            PanicCode::Assert => "assert(false)",
+            PanicCode::ArithmeticOverflow => "arithmetic underflow or overflow",
+            PanicCode::DivisionByZero => "division or modulo by zero",
+            PanicCode::InvalidEnum => "enum overflow",
+            PanicCode::InvalidStorageByteArray => "invalid encoded storage byte array accessed",
+            PanicCode::EmptyArrayPop => "out-of-bounds array access; popping on an empty array",
+            PanicCode::IndexOutOfBounds => "out-of-bounds access of an array or bytesN",
+            PanicCode::OutOfMemory => "out of memory",
+            PanicCode::ZeroFunction => "uninitialized function",
+        }
+    }
+}
+
+/// reason decoded from revert data.
+#[derive(Clone, Debug, PartialEq)]
+pub enum RevertReason {
+    /// `Error(string)`.
+    Error(String),
+    /// `Panic(uint256)`, with the code which may be unknown to `PanicCode`.
+    Panic(U256),
+    /// custom error of the ABI, with its arguments.
+    Custom {
+        name: String,
+        args: Vec<AbiValue>,
+    },
+}
+
+impl RevertReason {
+    /// decode `Error(string)` or `Panic(uint256)`.
+    pub fn decode(data: &[u8]) -> Option<Self> {
+        let (selector, args) = (data.get(..4)?, &data[4..]);
+        if selector == ERROR_SELECTOR {
+            match decode(&[AbiType::String], args)?.pop()? {
+                AbiValue::String(reason) => Some(RevertReason::Error(reason)),
+                _ => None,
+            }
+        }else if selector == PANIC_SELECTOR {
+            match decode(&[AbiType::Uint(256)], args)?.pop()? {
+                AbiValue::Uint(code) => Some(RevertReason::Panic(code)),
+                _ => None,
+            }
+        }else{
+            None
+        }
+    }
+
+    /// decode `Error(string)`, `Panic(uint256)` or one of the custom errors of `abi`.
+    pub fn decode_with(data: &[u8], abi: &Abi) -> Option<Self> {
+        if let Some(reason) = Self::decode(data) {
+            return Some(reason);
+        }
+        let error = abi.error(data)?;
+        Some(RevertReason::Custom {
+            name: error.name.clone(),
+            args: error.decode(data)?,
+        })
+    }
+
+    pub fn panic_code(&self) -> Option<PanicCode> {
+        match self {
+            RevertReason::Panic(code) => PanicCode::from_code(*code),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for RevertReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RevertReason::Error(reason) => write!(f, "{}", reason),
+            RevertReason::Panic(code) => match PanicCode::from_code(*code) {
+                Some(panic) => write!(f, "{}", panic.description()),
+                None => write!(f, "unknown panic code: {:#x}", code),
+            },
+            RevertReason::Custom { name, args } => {
+                let args: Vec<String> = args.iter().map(|arg| arg.to_string()).collect();
+                write!(f, "{}({})", name, args.join(", "))
+            },
+        }
+    }
+}
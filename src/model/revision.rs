@@ -34,6 +34,12 @@ pub enum Revision {
 
     /// The Shanghai revision.
     Shanghai = 10,
+
+    /// [The Cancun revision.](https://github.com/ethereum/execution-specs/blob/master/network-upgrades/mainnet-upgrades/cancun.md)
+    Cancun = 11,
+
+    /// [The Prague revision.](https://eips.ethereum.org/EIPS/eip-7600)
+    Prague = 12,
 }
 
 impl Revision {
@@ -50,11 +56,13 @@ impl Revision {
             Self::Berlin,
             Self::London,
             Self::Shanghai,
+            Self::Cancun,
+            Self::Prague,
         ]
     }
 
     pub const fn latest() -> Self {
-        Self::Shanghai
+        Self::Prague
     }
 
     pub const fn len() -> usize {
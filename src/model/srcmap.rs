@@ -0,0 +1,173 @@
+use ethereum_types::H256;
+use std::collections::HashMap;
+use std::fmt;
+
+use super::code::Code;
+use crate::utils::keccak256;
+
+/// how the instruction enters or leaves a function.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Jump {
+    /// jump into a function.
+    Into,
+    /// return from a function.
+    Out,
+    Regular,
+}
+
+/// range of the source the instruction was generated from.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SourceRange {
+    /// byte offset in the source file.
+    pub offset: usize,
+    pub length: usize,
+    /// index of the source file, `None` for compiler generated code.
+    pub file: Option<usize>,
+    pub jump: Jump,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum SourceMapError {
+    /// the entry of the instruction at this index is malformed.
+    InvalidEntry(usize),
+    /// the first entry leaves a field to be inherited.
+    MissingField,
+}
+
+/// solc source map, mapping each instruction of a bytecode to its source range.
+///
+/// https://docs.soliditylang.org/en/latest/internals/source_mappings.html
+#[derive(Clone, Debug, Default)]
+pub struct SourceMap {
+    ranges: Vec<SourceRange>,
+    /// index of the instruction at each pc.
+    instructions: HashMap<usize, usize>,
+}
+
+impl SourceMap {
+    /// parse `srcmap` (or `srcmap-runtime`) of the contract compiled to `code`.
+    pub fn new(srcmap: &str, code: &Code) -> Result<Self, SourceMapError> {
+        let mut ranges = Vec::new();
+        let mut last: Option<SourceRange> = None;
+
+        for (index, entry) in srcmap.split(';').enumerate() {
+            if srcmap.is_empty() {
+                break;
+            }
+            let mut fields = entry.split(':');
+            let mut field = |previous: Option<usize>| -> Result<Option<usize>, SourceMapError> {
+                match fields.next() {
+                    None | Some("") => Ok(previous),
+                    Some("-1") => Ok(None),
+                    Some(value) => value.parse().map(Some).map_err(|_| SourceMapError::InvalidEntry(index)),
+                }
+            };
+
+            let offset = field(last.map(|range| range.offset))?.ok_or(SourceMapError::MissingField)?;
+            let length = field(last.map(|range| range.length))?.ok_or(SourceMapError::MissingField)?;
+            let file = match last {
+                Some(range) => field(range.file)?,
+                None => field(None)?,
+            };
+            let jump = match fields.next() {
+                None | Some("") => last.map_or(Jump::Regular, |range| range.jump),
+                Some("i") => Jump::Into,
+                Some("o") => Jump::Out,
+                Some("-") => Jump::Regular,
+                Some(_) => return Err(SourceMapError::InvalidEntry(index)),
+            };
+
+            let range = SourceRange { offset, length, file, jump };
+            ranges.push(range);
+            last = Some(range);
+        }
+
+        let instructions = code.instruction_pcs().into_iter()
+            .enumerate()
+            .map(|(index, pc)| (pc, index))
+            .collect();
+        Ok(Self { ranges, instructions })
+    }
+
+    /// source range of the instruction at `pc`.
+    pub fn get(&self, pc: usize) -> Option<&SourceRange> {
+        self.instructions.get(&pc).and_then(|index| self.ranges.get(*index))
+    }
+}
+
+/// source file as indexed by the source map.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SourceFile {
+    pub name: String,
+    pub content: String,
+}
+
+impl SourceFile {
+    pub fn new(name: &str, content: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            content: content.to_string(),
+        }
+    }
+
+    /// 1-based line and column of the byte `offset`.
+    pub fn line_column(&self, offset: usize) -> (usize, usize) {
+        let offset = offset.min(self.content.len());
+        let before = &self.content.as_bytes()[..offset];
+        let line = before.iter().filter(|byte| **byte == b'\n').count() + 1;
+        let line_start = before.iter().rposition(|byte| *byte == b'\n').map_or(0, |pos| pos + 1);
+        (line, offset - line_start + 1)
+    }
+}
+
+/// position in a source file.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SourceLocation {
+    pub file: String,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl fmt::Display for SourceLocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}:{}", self.file, self.line, self.column)
+    }
+}
+
+/// source files and the source maps of the bytecodes compiled from them, keyed by code hash.
+#[derive(Clone, Debug, Default)]
+pub struct Sources {
+    pub files: Vec<SourceFile>,
+    maps: HashMap<H256, SourceMap>,
+}
+
+impl Sources {
+    pub fn new(files: Vec<SourceFile>) -> Self {
+        Self {
+            files,
+            maps: HashMap::new(),
+        }
+    }
+
+    /// add the source map of `code`, which indexes `files`.
+    pub fn add(&mut self, code: &Code, srcmap: &str) -> Result<(), SourceMapError> {
+        self.maps.insert(keccak256(&code.0), SourceMap::new(srcmap, code)?);
+        Ok(())
+    }
+
+    pub fn source_map(&self, code_hash: &H256) -> Option<&SourceMap> {
+        self.maps.get(code_hash)
+    }
+
+    /// location in the source of the instruction at `pc` of the bytecode `code_hash`.
+    pub fn locate(&self, code_hash: &H256, pc: usize) -> Option<SourceLocation> {
+        let range = self.maps.get(code_hash)?.get(pc)?;
+        let file = self.files.get(range.file?)?;
+        let (line, column) = file.line_column(range.offset);
+        Some(SourceLocation {
+            file: file.name.clone(),
+            line,
+            column,
+        })
+    }
+}
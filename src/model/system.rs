@@ -0,0 +1,20 @@
+use ethereum_types::{Address, H160};
+use hex_literal::hex;
+
+/// caller of system calls, which is not an account anyone controls.
+pub const SYSTEM_ADDRESS: Address = H160(hex!("fffffffffffffffffffffffffffffffffffffffe"));
+
+/// gas available to a system call, which is not charged to anyone nor counted in the block.
+pub const SYSTEM_CALL_GAS: i64 = 30_000_000;
+
+/// https://eips.ethereum.org/EIPS/eip-4788
+pub const BEACON_ROOTS_ADDRESS: Address = H160(hex!("000f3df6d732807ef1319fb7b8bb8522d0beac02"));
+pub const BEACON_ROOTS_CODE: &str = "3373fffffffffffffffffffffffffffffffffffffffe14604d57602036146024575f5ffd5b5f35801560495762001fff810690815414603c575f5ffd5b62001fff01545f5260205ff35b5f5ffd5b62001fff42064281555f359062001fff015500";
+/// length of the ring buffers of timestamps and roots.
+pub const HISTORY_BUFFER_LENGTH: u64 = 8191;
+
+/// https://eips.ethereum.org/EIPS/eip-2935
+pub const HISTORY_STORAGE_ADDRESS: Address = H160(hex!("0000f90827f1c53a10cb7a02335b175320002935"));
+pub const HISTORY_STORAGE_CODE: &str = "3373fffffffffffffffffffffffffffffffffffffffe14604657602036036042575f35600143038111604257611fff81430311604257611fff9006545f5260205ff35b5f5ffd5b5f35611fff60014303065500";
+/// number of block hashes the history contract serves.
+pub const HISTORY_SERVE_WINDOW: u64 = 8191;
@@ -0,0 +1,708 @@
+use bytes::Bytes;
+use ethereum_types::{Address, H256, U256};
+
+use crate::utils::{keccak256, secp256k1::{self, SECP256K1N}, trie::ordered_trie_root};
+use crate::utils::rlp::{
+    self, RlpError, RlpItem,
+    encode_bytes, encode_list, encode_u256, encode_u64, encode_address, encode_optional_address, encode_h256,
+};
+use super::evmc::AccessList;
+use super::fee::{BlobFee, DynamicFee};
+
+pub const LEGACY_TX_TYPE: u8 = 0x00;
+pub const ACCESS_LIST_TX_TYPE: u8 = 0x01;
+pub const DYNAMIC_FEE_TX_TYPE: u8 = 0x02;
+pub const BLOB_TX_TYPE: u8 = 0x03;
+pub const SET_CODE_TX_TYPE: u8 = 0x04;
+
+/// magic prefix of the EIP-7702 authorization signing payload.
+pub const SET_CODE_AUTHORIZATION_MAGIC: u8 = 0x05;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum TransactionError {
+    Rlp(RlpError),
+    InvalidHex,
+    /// transaction type byte outside of the known EIP-2718 types.
+    UnsupportedType(u8),
+    /// signature does not recover to an address, or its `s` is in the upper half of the curve order.
+    InvalidSignature,
+    /// transaction is signed for another chain.
+    ChainIdMismatch,
+}
+
+impl From<RlpError> for TransactionError {
+    fn from(err: RlpError) -> Self {
+        TransactionError::Rlp(err)
+    }
+}
+
+/// ECDSA signature attached to a transaction.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Signature {
+    /// `27`/`28` or `chain_id * 2 + 35`/`36` for legacy transactions,
+    /// y parity (`0`/`1`) for typed transactions.
+    pub v: u64,
+    pub r: U256,
+    pub s: U256,
+}
+
+impl Signature {
+    fn encode(&self) -> [Vec<u8>; 3] {
+        [encode_u64(self.v), encode_u256(self.r), encode_u256(self.s)]
+    }
+
+    fn decode(items: &[RlpItem]) -> Result<Self, RlpError> {
+        Ok(Signature {
+            v: items[0].as_u64()?,
+            r: items[1].as_u256()?,
+            s: items[2].as_u256()?,
+        })
+    }
+}
+
+/// https://eips.ethereum.org/EIPS/eip-2930
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct AccessListItem {
+    pub address: Address,
+    pub storage_keys: Vec<H256>,
+}
+
+/// https://eips.ethereum.org/EIPS/eip-7702
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Authorization {
+    pub chain_id: U256,
+    pub address: Address,
+    pub nonce: u64,
+    pub signature: Signature,
+}
+
+impl Authorization {
+    /// recover the address of the authority which signed this authorization.
+    pub fn recover_authority(&self) -> Option<Address> {
+        if self.signature.v > 1 || self.signature.s > SECP256K1N / 2 {
+            return None;
+        }
+        secp256k1::recover_address(&self.signing_hash(), self.signature.v as u8, self.signature.r, self.signature.s)
+    }
+
+    /// keccak256(MAGIC || rlp([chain_id, address, nonce]))
+    pub fn signing_hash(&self) -> H256 {
+        let mut payload = vec![SET_CODE_AUTHORIZATION_MAGIC];
+        payload.extend(encode_list(&[
+            encode_u256(self.chain_id),
+            encode_address(&self.address),
+            encode_u64(self.nonce),
+        ]));
+        keccak256(&payload)
+    }
+
+    fn encode(&self) -> Vec<u8> {
+        let [v, r, s] = self.signature.encode();
+        encode_list(&[
+            encode_u256(self.chain_id),
+            encode_address(&self.address),
+            encode_u64(self.nonce),
+            v, r, s,
+        ])
+    }
+
+    fn decode(item: &RlpItem) -> Result<Self, RlpError> {
+        let items = expect_items(item, 6)?;
+        Ok(Authorization {
+            chain_id: items[0].as_u256()?,
+            address: items[1].as_address()?,
+            nonce: items[2].as_u64()?,
+            signature: Signature::decode(&items[3..])?,
+        })
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct LegacyTransaction {
+    pub nonce: u64,
+    pub gas_price: U256,
+    pub gas_limit: u64,
+    /// `None` for contract creation.
+    pub to: Option<Address>,
+    pub value: U256,
+    pub data: Bytes,
+    pub signature: Signature,
+}
+
+impl LegacyTransaction {
+    /// chain id encoded in `v` as specified in EIP-155, `None` for pre EIP-155 transactions.
+    ///
+    /// https://eips.ethereum.org/EIPS/eip-155
+    pub fn chain_id(&self) -> Option<U256> {
+        if self.signature.v >= 35 {
+            Some(U256::from((self.signature.v - 35) / 2))
+        }else{
+            None
+        }
+    }
+
+    fn encode_unsigned(&self) -> Vec<Vec<u8>> {
+        vec![
+            encode_u64(self.nonce),
+            encode_u256(self.gas_price),
+            encode_u64(self.gas_limit),
+            encode_optional_address(&self.to),
+            encode_u256(self.value),
+            encode_bytes(&self.data),
+        ]
+    }
+
+    fn decode(item: &RlpItem) -> Result<Self, RlpError> {
+        let items = expect_items(item, 9)?;
+        Ok(LegacyTransaction {
+            nonce: items[0].as_u64()?,
+            gas_price: items[1].as_u256()?,
+            gas_limit: items[2].as_u64()?,
+            to: items[3].as_optional_address()?,
+            value: items[4].as_u256()?,
+            data: Bytes::copy_from_slice(items[5].as_bytes()?),
+            signature: Signature::decode(&items[6..])?,
+        })
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct AccessListTransaction {
+    pub chain_id: U256,
+    pub nonce: u64,
+    pub gas_price: U256,
+    pub gas_limit: u64,
+    /// `None` for contract creation.
+    pub to: Option<Address>,
+    pub value: U256,
+    pub data: Bytes,
+    pub access_list: Vec<AccessListItem>,
+    pub signature: Signature,
+}
+
+impl AccessListTransaction {
+    fn encode_unsigned(&self) -> Vec<Vec<u8>> {
+        vec![
+            encode_u256(self.chain_id),
+            encode_u64(self.nonce),
+            encode_u256(self.gas_price),
+            encode_u64(self.gas_limit),
+            encode_optional_address(&self.to),
+            encode_u256(self.value),
+            encode_bytes(&self.data),
+            encode_access_list(&self.access_list),
+        ]
+    }
+
+    fn decode(item: &RlpItem) -> Result<Self, RlpError> {
+        let items = expect_items(item, 11)?;
+        Ok(AccessListTransaction {
+            chain_id: items[0].as_u256()?,
+            nonce: items[1].as_u64()?,
+            gas_price: items[2].as_u256()?,
+            gas_limit: items[3].as_u64()?,
+            to: items[4].as_optional_address()?,
+            value: items[5].as_u256()?,
+            data: Bytes::copy_from_slice(items[6].as_bytes()?),
+            access_list: decode_access_list(&items[7])?,
+            signature: Signature::decode(&items[8..])?,
+        })
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DynamicFeeTransaction {
+    pub chain_id: U256,
+    pub nonce: u64,
+    pub max_priority_fee_per_gas: U256,
+    pub max_fee_per_gas: U256,
+    pub gas_limit: u64,
+    /// `None` for contract creation.
+    pub to: Option<Address>,
+    pub value: U256,
+    pub data: Bytes,
+    pub access_list: Vec<AccessListItem>,
+    pub signature: Signature,
+}
+
+impl DynamicFeeTransaction {
+    fn encode_unsigned(&self) -> Vec<Vec<u8>> {
+        vec![
+            encode_u256(self.chain_id),
+            encode_u64(self.nonce),
+            encode_u256(self.max_priority_fee_per_gas),
+            encode_u256(self.max_fee_per_gas),
+            encode_u64(self.gas_limit),
+            encode_optional_address(&self.to),
+            encode_u256(self.value),
+            encode_bytes(&self.data),
+            encode_access_list(&self.access_list),
+        ]
+    }
+
+    fn decode(item: &RlpItem) -> Result<Self, RlpError> {
+        let items = expect_items(item, 12)?;
+        Ok(DynamicFeeTransaction {
+            chain_id: items[0].as_u256()?,
+            nonce: items[1].as_u64()?,
+            max_priority_fee_per_gas: items[2].as_u256()?,
+            max_fee_per_gas: items[3].as_u256()?,
+            gas_limit: items[4].as_u64()?,
+            to: items[5].as_optional_address()?,
+            value: items[6].as_u256()?,
+            data: Bytes::copy_from_slice(items[7].as_bytes()?),
+            access_list: decode_access_list(&items[8])?,
+            signature: Signature::decode(&items[9..])?,
+        })
+    }
+}
+
+/// https://eips.ethereum.org/EIPS/eip-4844
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct BlobTransaction {
+    pub chain_id: U256,
+    pub nonce: u64,
+    pub max_priority_fee_per_gas: U256,
+    pub max_fee_per_gas: U256,
+    pub gas_limit: u64,
+    /// blob transactions cannot create contracts.
+    pub to: Address,
+    pub value: U256,
+    pub data: Bytes,
+    pub access_list: Vec<AccessListItem>,
+    pub max_fee_per_blob_gas: U256,
+    pub blob_versioned_hashes: Vec<H256>,
+    pub signature: Signature,
+}
+
+impl BlobTransaction {
+    fn encode_unsigned(&self) -> Vec<Vec<u8>> {
+        let hashes: Vec<Vec<u8>> = self.blob_versioned_hashes.iter().map(encode_h256).collect();
+        vec![
+            encode_u256(self.chain_id),
+            encode_u64(self.nonce),
+            encode_u256(self.max_priority_fee_per_gas),
+            encode_u256(self.max_fee_per_gas),
+            encode_u64(self.gas_limit),
+            encode_address(&self.to),
+            encode_u256(self.value),
+            encode_bytes(&self.data),
+            encode_access_list(&self.access_list),
+            encode_u256(self.max_fee_per_blob_gas),
+            encode_list(&hashes),
+        ]
+    }
+
+    fn decode(item: &RlpItem) -> Result<Self, RlpError> {
+        let items = expect_items(item, 14)?;
+        let blob_versioned_hashes = items[10].as_list()?
+            .iter()
+            .map(|hash| hash.as_h256())
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(BlobTransaction {
+            chain_id: items[0].as_u256()?,
+            nonce: items[1].as_u64()?,
+            max_priority_fee_per_gas: items[2].as_u256()?,
+            max_fee_per_gas: items[3].as_u256()?,
+            gas_limit: items[4].as_u64()?,
+            to: items[5].as_address()?,
+            value: items[6].as_u256()?,
+            data: Bytes::copy_from_slice(items[7].as_bytes()?),
+            access_list: decode_access_list(&items[8])?,
+            max_fee_per_blob_gas: items[9].as_u256()?,
+            blob_versioned_hashes,
+            signature: Signature::decode(&items[11..])?,
+        })
+    }
+}
+
+/// https://eips.ethereum.org/EIPS/eip-7702
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SetCodeTransaction {
+    pub chain_id: U256,
+    pub nonce: u64,
+    pub max_priority_fee_per_gas: U256,
+    pub max_fee_per_gas: U256,
+    pub gas_limit: u64,
+    /// set code transactions cannot create contracts.
+    pub to: Address,
+    pub value: U256,
+    pub data: Bytes,
+    pub access_list: Vec<AccessListItem>,
+    pub authorization_list: Vec<Authorization>,
+    pub signature: Signature,
+}
+
+impl SetCodeTransaction {
+    fn encode_unsigned(&self) -> Vec<Vec<u8>> {
+        let authorizations: Vec<Vec<u8>> = self.authorization_list.iter().map(|a| a.encode()).collect();
+        vec![
+            encode_u256(self.chain_id),
+            encode_u64(self.nonce),
+            encode_u256(self.max_priority_fee_per_gas),
+            encode_u256(self.max_fee_per_gas),
+            encode_u64(self.gas_limit),
+            encode_address(&self.to),
+            encode_u256(self.value),
+            encode_bytes(&self.data),
+            encode_access_list(&self.access_list),
+            encode_list(&authorizations),
+        ]
+    }
+
+    fn decode(item: &RlpItem) -> Result<Self, RlpError> {
+        let items = expect_items(item, 13)?;
+        let authorization_list = items[9].as_list()?
+            .iter()
+            .map(Authorization::decode)
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(SetCodeTransaction {
+            chain_id: items[0].as_u256()?,
+            nonce: items[1].as_u64()?,
+            max_priority_fee_per_gas: items[2].as_u256()?,
+            max_fee_per_gas: items[3].as_u256()?,
+            gas_limit: items[4].as_u64()?,
+            to: items[5].as_address()?,
+            value: items[6].as_u256()?,
+            data: Bytes::copy_from_slice(items[7].as_bytes()?),
+            access_list: decode_access_list(&items[8])?,
+            authorization_list,
+            signature: Signature::decode(&items[10..])?,
+        })
+    }
+}
+
+/// transaction envelope
+///
+/// https://eips.ethereum.org/EIPS/eip-2718
+#[derive(Clone, Debug, PartialEq)]
+pub enum Transaction {
+    Legacy(LegacyTransaction),
+    AccessList(AccessListTransaction),
+    DynamicFee(DynamicFeeTransaction),
+    Blob(BlobTransaction),
+    SetCode(SetCodeTransaction),
+}
+
+impl Transaction {
+    /// decode raw transaction given as hex, with or without `0x` prefix.
+    pub fn from_hex(hex: &str) -> Result<Self, TransactionError> {
+        let hex = hex.strip_prefix("0x").unwrap_or(hex);
+        let data = hex::decode(hex).map_err(|_| TransactionError::InvalidHex)?;
+        Self::decode(&data)
+    }
+
+    /// decode raw transaction.
+    ///
+    /// legacy transaction is a RLP list, typed transaction is `type || rlp(fields)`.
+    pub fn decode(data: &[u8]) -> Result<Self, TransactionError> {
+        let tx_type = *data.first().ok_or(RlpError::UnexpectedEnd)?;
+        if tx_type >= 0xc0 {
+            let item = rlp::decode(data)?;
+            return Ok(Transaction::Legacy(LegacyTransaction::decode(&item)?));
+        }
+
+        let item = rlp::decode(&data[1..])?;
+        let tx = match tx_type {
+            ACCESS_LIST_TX_TYPE => Transaction::AccessList(AccessListTransaction::decode(&item)?),
+            DYNAMIC_FEE_TX_TYPE => Transaction::DynamicFee(DynamicFeeTransaction::decode(&item)?),
+            BLOB_TX_TYPE => Transaction::Blob(BlobTransaction::decode(&item)?),
+            SET_CODE_TX_TYPE => Transaction::SetCode(SetCodeTransaction::decode(&item)?),
+            _ => return Err(TransactionError::UnsupportedType(tx_type)),
+        };
+        Ok(tx)
+    }
+
+    /// encode into the raw transaction format accepted by `decode()`.
+    pub fn encode(&self) -> Vec<u8> {
+        let mut fields = self.encode_unsigned();
+        fields.extend(self.signature().encode());
+        self.envelope(&fields)
+    }
+
+    pub fn tx_type(&self) -> u8 {
+        match self {
+            Transaction::Legacy(_) => LEGACY_TX_TYPE,
+            Transaction::AccessList(_) => ACCESS_LIST_TX_TYPE,
+            Transaction::DynamicFee(_) => DYNAMIC_FEE_TX_TYPE,
+            Transaction::Blob(_) => BLOB_TX_TYPE,
+            Transaction::SetCode(_) => SET_CODE_TX_TYPE,
+        }
+    }
+
+    /// transaction hash, i.e. keccak256 of the raw transaction.
+    pub fn hash(&self) -> H256 {
+        keccak256(&self.encode())
+    }
+
+    /// hash the sender signs.
+    ///
+    /// legacy transaction with EIP-155 replay protection appends `[chain_id, 0, 0]` to the fields.
+    pub fn signing_hash(&self) -> H256 {
+        let mut fields = self.encode_unsigned();
+        if let Transaction::Legacy(tx) = self {
+            if let Some(chain_id) = tx.chain_id() {
+                fields.push(encode_u256(chain_id));
+                fields.push(encode_u64(0));
+                fields.push(encode_u64(0));
+            }
+        }
+        keccak256(&self.envelope(&fields))
+    }
+
+    /// recover the sender from the signature.
+    ///
+    /// transaction signed for another chain than `chain_id` is rejected.
+    /// legacy transaction without EIP-155 replay protection is accepted on any chain.
+    pub fn recover_sender(&self, chain_id: U256) -> Result<Address, TransactionError> {
+        if let Some(tx_chain_id) = self.chain_id() {
+            if tx_chain_id != chain_id {
+                return Err(TransactionError::ChainIdMismatch);
+            }
+        }
+
+        let signature = self.signature();
+        let y_parity = self.y_parity().ok_or(TransactionError::InvalidSignature)?;
+
+        // https://eips.ethereum.org/EIPS/eip-2
+        if signature.s > SECP256K1N / 2 {
+            return Err(TransactionError::InvalidSignature);
+        }
+
+        secp256k1::recover_address(&self.signing_hash(), y_parity, signature.r, signature.s)
+            .ok_or(TransactionError::InvalidSignature)
+    }
+
+    /// sign the transaction with `secret_key`. intended for tests.
+    ///
+    /// legacy transaction is signed with EIP-155 replay protection if `v` already encodes a chain id,
+    /// i.e. `v = chain_id * 2 + 35`, otherwise without it.
+    pub fn sign(&mut self, secret_key: &H256) -> Result<(), TransactionError> {
+        let (y_parity, r, s) = secp256k1::sign(&self.signing_hash(), secret_key)
+            .ok_or(TransactionError::InvalidSignature)?;
+
+        let v = match self {
+            Transaction::Legacy(tx) => match tx.chain_id() {
+                Some(chain_id) => chain_id.as_u64() * 2 + 35 + y_parity as u64,
+                None => 27 + y_parity as u64,
+            },
+            _ => y_parity as u64,
+        };
+        let signature = Signature { v, r, s };
+        match self {
+            Transaction::Legacy(tx) => tx.signature = signature,
+            Transaction::AccessList(tx) => tx.signature = signature,
+            Transaction::DynamicFee(tx) => tx.signature = signature,
+            Transaction::Blob(tx) => tx.signature = signature,
+            Transaction::SetCode(tx) => tx.signature = signature,
+        }
+        Ok(())
+    }
+
+    /// recovery id of the signature, `None` if `v` is malformed.
+    fn y_parity(&self) -> Option<u8> {
+        let v = self.signature().v;
+        match self {
+            Transaction::Legacy(_) => match v {
+                27 | 28 => Some((v - 27) as u8),
+                v if v >= 35 => Some(((v - 35) % 2) as u8),
+                _ => None,
+            },
+            _ => match v {
+                0 | 1 => Some(v as u8),
+                _ => None,
+            },
+        }
+    }
+
+    pub fn chain_id(&self) -> Option<U256> {
+        match self {
+            Transaction::Legacy(tx) => tx.chain_id(),
+            Transaction::AccessList(tx) => Some(tx.chain_id),
+            Transaction::DynamicFee(tx) => Some(tx.chain_id),
+            Transaction::Blob(tx) => Some(tx.chain_id),
+            Transaction::SetCode(tx) => Some(tx.chain_id),
+        }
+    }
+
+    pub fn nonce(&self) -> u64 {
+        match self {
+            Transaction::Legacy(tx) => tx.nonce,
+            Transaction::AccessList(tx) => tx.nonce,
+            Transaction::DynamicFee(tx) => tx.nonce,
+            Transaction::Blob(tx) => tx.nonce,
+            Transaction::SetCode(tx) => tx.nonce,
+        }
+    }
+
+    pub fn gas_limit(&self) -> u64 {
+        match self {
+            Transaction::Legacy(tx) => tx.gas_limit,
+            Transaction::AccessList(tx) => tx.gas_limit,
+            Transaction::DynamicFee(tx) => tx.gas_limit,
+            Transaction::Blob(tx) => tx.gas_limit,
+            Transaction::SetCode(tx) => tx.gas_limit,
+        }
+    }
+
+    /// `None` for contract creation.
+    pub fn to(&self) -> Option<Address> {
+        match self {
+            Transaction::Legacy(tx) => tx.to,
+            Transaction::AccessList(tx) => tx.to,
+            Transaction::DynamicFee(tx) => tx.to,
+            Transaction::Blob(tx) => Some(tx.to),
+            Transaction::SetCode(tx) => Some(tx.to),
+        }
+    }
+
+    pub fn value(&self) -> U256 {
+        match self {
+            Transaction::Legacy(tx) => tx.value,
+            Transaction::AccessList(tx) => tx.value,
+            Transaction::DynamicFee(tx) => tx.value,
+            Transaction::Blob(tx) => tx.value,
+            Transaction::SetCode(tx) => tx.value,
+        }
+    }
+
+    pub fn data(&self) -> &Bytes {
+        match self {
+            Transaction::Legacy(tx) => &tx.data,
+            Transaction::AccessList(tx) => &tx.data,
+            Transaction::DynamicFee(tx) => &tx.data,
+            Transaction::Blob(tx) => &tx.data,
+            Transaction::SetCode(tx) => &tx.data,
+        }
+    }
+
+    pub fn signature(&self) -> &Signature {
+        match self {
+            Transaction::Legacy(tx) => &tx.signature,
+            Transaction::AccessList(tx) => &tx.signature,
+            Transaction::DynamicFee(tx) => &tx.signature,
+            Transaction::Blob(tx) => &tx.signature,
+            Transaction::SetCode(tx) => &tx.signature,
+        }
+    }
+
+    /// gas price of legacy and access list transactions.
+    pub fn gas_price(&self) -> Option<U256> {
+        match self {
+            Transaction::Legacy(tx) => Some(tx.gas_price),
+            Transaction::AccessList(tx) => Some(tx.gas_price),
+            _ => None,
+        }
+    }
+
+    /// fee caps of the transaction.
+    ///
+    /// gas price of legacy and access list transactions is used for both caps,
+    /// which makes the effective gas price equal to the gas price as specified in EIP-1559.
+    pub fn dynamic_fee(&self) -> DynamicFee {
+        match self {
+            Transaction::Legacy(tx) => DynamicFee::new(tx.gas_price, tx.gas_price),
+            Transaction::AccessList(tx) => DynamicFee::new(tx.gas_price, tx.gas_price),
+            Transaction::DynamicFee(tx) => DynamicFee::new(tx.max_fee_per_gas, tx.max_priority_fee_per_gas),
+            Transaction::Blob(tx) => DynamicFee::new(tx.max_fee_per_gas, tx.max_priority_fee_per_gas),
+            Transaction::SetCode(tx) => DynamicFee::new(tx.max_fee_per_gas, tx.max_priority_fee_per_gas),
+        }
+    }
+
+    pub fn access_list_items(&self) -> &[AccessListItem] {
+        match self {
+            Transaction::Legacy(_) => &[],
+            Transaction::AccessList(tx) => &tx.access_list,
+            Transaction::DynamicFee(tx) => &tx.access_list,
+            Transaction::Blob(tx) => &tx.access_list,
+            Transaction::SetCode(tx) => &tx.access_list,
+        }
+    }
+
+    /// access list in the form the executor accepts.
+    pub fn access_list(&self) -> AccessList {
+        let mut access_list = AccessList::default();
+        for item in self.access_list_items() {
+            access_list.add_account(item.address);
+            for key in &item.storage_keys {
+                access_list.add_storage(item.address, U256::from_big_endian(&key.0));
+            }
+        }
+        access_list
+    }
+
+    pub fn blob_versioned_hashes(&self) -> &[H256] {
+        match self {
+            Transaction::Blob(tx) => &tx.blob_versioned_hashes,
+            _ => &[],
+        }
+    }
+
+    /// blob fee parameters of a blob transaction.
+    pub fn blob_fee(&self) -> Option<BlobFee> {
+        match self {
+            Transaction::Blob(tx) => Some(BlobFee::new(tx.max_fee_per_blob_gas, tx.blob_versioned_hashes.len())),
+            _ => None,
+        }
+    }
+
+    fn encode_unsigned(&self) -> Vec<Vec<u8>> {
+        match self {
+            Transaction::Legacy(tx) => tx.encode_unsigned(),
+            Transaction::AccessList(tx) => tx.encode_unsigned(),
+            Transaction::DynamicFee(tx) => tx.encode_unsigned(),
+            Transaction::Blob(tx) => tx.encode_unsigned(),
+            Transaction::SetCode(tx) => tx.encode_unsigned(),
+        }
+    }
+
+    fn envelope(&self, fields: &[Vec<u8>]) -> Vec<u8> {
+        let list = encode_list(fields);
+        if self.tx_type() == LEGACY_TX_TYPE {
+            return list;
+        }
+        let mut out = vec![self.tx_type()];
+        out.extend(list);
+        out
+    }
+}
+
+/// root of the transactions trie of a block.
+pub fn transactions_root(txs: &[Transaction]) -> H256 {
+    ordered_trie_root(txs.iter().map(Transaction::encode))
+}
+
+fn expect_items(item: &RlpItem, count: usize) -> Result<&[RlpItem], RlpError> {
+    let items = item.as_list()?;
+    if items.len() != count {
+        return Err(RlpError::InvalidItemCount);
+    }
+    Ok(items)
+}
+
+fn encode_access_list(access_list: &[AccessListItem]) -> Vec<u8> {
+    let items: Vec<Vec<u8>> = access_list.iter()
+        .map(|item| {
+            let keys: Vec<Vec<u8>> = item.storage_keys.iter().map(encode_h256).collect();
+            encode_list(&[encode_address(&item.address), encode_list(&keys)])
+        })
+        .collect();
+    encode_list(&items)
+}
+
+fn decode_access_list(item: &RlpItem) -> Result<Vec<AccessListItem>, RlpError> {
+    item.as_list()?
+        .iter()
+        .map(|item| {
+            let items = expect_items(item, 2)?;
+            let storage_keys = items[1].as_list()?
+                .iter()
+                .map(|key| key.as_h256())
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(AccessListItem {
+                address: items[0].as_address()?,
+                storage_keys,
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,22 @@
+use ethereum_types::{Address, U256};
+
+/// wei per gwei.
+pub const GWEI: u64 = 1_000_000_000;
+
+/// withdrawal from the beacon chain.
+///
+/// https://eips.ethereum.org/EIPS/eip-4895
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Withdrawal {
+    pub index: u64,
+    pub validator_index: u64,
+    pub address: Address,
+    /// amount in gwei.
+    pub amount: u64,
+}
+
+impl Withdrawal {
+    pub fn amount_in_wei(&self) -> U256 {
+        U256::from(self.amount) * U256::from(GWEI)
+    }
+}
@@ -0,0 +1,148 @@
+use bytes::Bytes;
+use ethereum_types::{Address, U256};
+use serde::{Serialize, Serializer};
+
+use crate::executor::callstack::CallScope;
+use crate::interpreter::CallKind;
+use crate::model::{
+    evmc::{FailureKind, StatusCode},
+    revert::RevertReason,
+};
+use super::Tracer;
+
+/// frame of the call tree, serialized in the format of geth's `callTracer`.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CallFrame {
+    #[serde(rename = "type", serialize_with = "serialize_kind")]
+    pub kind: CallKind,
+    pub from: Address,
+    pub to: Address,
+    /// `None` for DELEGATECALL and STATICCALL, which do not transfer value.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<U256>,
+    #[serde(serialize_with = "serialize_gas")]
+    pub gas: i64,
+    #[serde(serialize_with = "serialize_gas")]
+    pub gas_used: i64,
+    #[serde(serialize_with = "serialize_bytes")]
+    pub input: Bytes,
+    #[serde(serialize_with = "serialize_bytes")]
+    pub output: Bytes,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// message of `Error(string)` or meaning of `Panic(uint256)` the frame reverted with.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub revert_reason: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub calls: Vec<CallFrame>,
+}
+
+/// tracer recording the tree of the scopes pushed onto the call stack.
+#[derive(Clone, Debug, Default)]
+pub struct CallTracer {
+    /// frames not exited yet, outermost first.
+    frames: Vec<CallFrame>,
+    root: Option<CallFrame>,
+}
+
+impl CallTracer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// the outermost frame, once it has exited.
+    pub fn result(&self) -> Option<&CallFrame> {
+        self.root.as_ref()
+    }
+
+    pub fn into_result(self) -> Option<CallFrame> {
+        self.root
+    }
+}
+
+impl Tracer for CallTracer {
+    fn call_enter(&mut self, kind: CallKind, scope: &CallScope) {
+        if scope.depth == 0 {
+            self.frames.clear();
+            self.root = None;
+        }
+
+        let value = match kind {
+            CallKind::DelegateCall | CallKind::StaticCall => None,
+            CallKind::Call | CallKind::CallCode => Some(scope.value),
+        };
+        self.frames.push(CallFrame {
+            kind,
+            from: scope.caller,
+            to: scope.to,
+            value,
+            gas: scope.gas_limit,
+            gas_used: 0,
+            input: Bytes::from(scope.calldata.0.clone()),
+            output: Bytes::default(),
+            error: None,
+            revert_reason: None,
+            calls: Vec::new(),
+        });
+    }
+
+    fn call_exit(&mut self, scope: &CallScope, status: &StatusCode, output: &Bytes) {
+        let mut frame = match self.frames.pop() {
+            Some(frame) => frame,
+            None => return,
+        };
+
+        frame.output = output.clone();
+        frame.gas_used = match status {
+            // failure other than revert consumes all the gas.
+            StatusCode::Failure(kind) if *kind != FailureKind::Revert => frame.gas,
+            _ => frame.gas - scope.gas_left.max(0),
+        };
+        if let StatusCode::Failure(kind) = status {
+            frame.error = Some(error_message(kind));
+            if *kind == FailureKind::Revert {
+                frame.revert_reason = RevertReason::decode(output).map(|reason| reason.to_string());
+            }
+        }
+
+        match self.frames.last_mut() {
+            Some(parent) => parent.calls.push(frame),
+            None => self.root = Some(frame),
+        }
+    }
+}
+
+/// error message as geth reports it.
+fn error_message(kind: &FailureKind) -> String {
+    match kind {
+        FailureKind::Revert => "execution reverted".to_string(),
+        FailureKind::OutOfGas => "out of gas".to_string(),
+        FailureKind::InvalidInstruction | FailureKind::UndefinedInstruction => "invalid opcode".to_string(),
+        FailureKind::StackUnderflow => "stack underflow".to_string(),
+        FailureKind::StackOverflow => "stack limit reached 1024".to_string(),
+        FailureKind::BadJumpDestination => "invalid jump destination".to_string(),
+        FailureKind::CallDepthExceeded => "max call depth exceeded".to_string(),
+        FailureKind::StaticModeViolation => "write protection".to_string(),
+        FailureKind::InsufficientBalance => "insufficient balance for transfer".to_string(),
+        FailureKind::Generic(message) | FailureKind::InternalError(message) => message.clone(),
+        _ => format!("{:?}", kind),
+    }
+}
+
+fn serialize_kind<S: Serializer>(kind: &CallKind, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(match kind {
+        CallKind::Call => "CALL",
+        CallKind::CallCode => "CALLCODE",
+        CallKind::DelegateCall => "DELEGATECALL",
+        CallKind::StaticCall => "STATICCALL",
+    })
+}
+
+fn serialize_gas<S: Serializer>(gas: &i64, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&format!("{:#x}", gas))
+}
+
+fn serialize_bytes<S: Serializer>(data: &Bytes, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&format!("0x{}", hex::encode(data)))
+}
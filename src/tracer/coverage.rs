@@ -0,0 +1,161 @@
+use bytes::Bytes;
+use ethereum_types::H256;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write;
+
+use crate::executor::callstack::{CallScope, ExecutionContext};
+use crate::interpreter::CallKind;
+use crate::model::{
+    code::Code,
+    evmc::StatusCode,
+    opcode::OpCode,
+    srcmap::{SourceFile, SourceMap, Sources},
+};
+use crate::utils::keccak256;
+use super::Tracer;
+
+/// line and pc of a JUMPI, and the times it jumped and did not jump if it was executed.
+type Branch = (usize, usize, Option<(u64, u64)>);
+
+/// instructions of a bytecode executed so far.
+#[derive(Clone, Debug, Default)]
+pub struct CodeCoverage {
+    pub code: Code,
+    /// number of executions of the instruction at each pc.
+    pub hits: BTreeMap<usize, u64>,
+    /// number of times each JUMPI jumped and did not jump, keyed by its pc.
+    pub branches: BTreeMap<usize, (u64, u64)>,
+}
+
+impl CodeCoverage {
+    /// pcs of the instructions never executed.
+    pub fn uncovered_pcs(&self) -> Vec<usize> {
+        self.code.instruction_pcs().into_iter()
+            .filter(|pc| !self.hits.contains_key(pc))
+            .collect()
+    }
+}
+
+/// tracer collecting the instructions executed in each bytecode, keyed by code hash.
+///
+/// pass the same collector to many runs to accumulate the coverage of a test suite.
+#[derive(Clone, Debug, Default)]
+pub struct Coverage {
+    pub codes: HashMap<H256, CodeCoverage>,
+    /// code hash of the scopes on the call stack.
+    frames: Vec<H256>,
+}
+
+impl Coverage {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get(&self, code_hash: &H256) -> Option<&CodeCoverage> {
+        self.codes.get(code_hash)
+    }
+
+    /// lcov tracefile of every bytecode whose source map is in `sources`.
+    pub fn lcov_sources(&self, sources: &Sources) -> String {
+        let mut code_hashes: Vec<&H256> = self.codes.keys().collect();
+        code_hashes.sort();
+        code_hashes.into_iter()
+            .filter_map(|code_hash| sources.source_map(code_hash).map(|source_map| (code_hash, source_map)))
+            .map(|(code_hash, source_map)| self.lcov(code_hash, source_map, &sources.files))
+            .collect()
+    }
+
+    /// lcov tracefile of the bytecode `code_hash`, mapped to `sources` through `source_map`.
+    ///
+    /// a line is hit as many times as its most executed instruction.
+    /// each JUMPI is reported as a branch block of two branches, jumped and not jumped.
+    pub fn lcov(&self, code_hash: &H256, source_map: &SourceMap, sources: &[SourceFile]) -> String {
+        let coverage = match self.codes.get(code_hash) {
+            Some(coverage) => coverage,
+            None => return String::new(),
+        };
+
+        // per file, hits of each line and branches of each line.
+        let mut lines: BTreeMap<usize, BTreeMap<usize, u64>> = BTreeMap::new();
+        let mut branches: BTreeMap<usize, Vec<Branch>> = BTreeMap::new();
+        for pc in coverage.code.instruction_pcs() {
+            let (file, offset) = match source_map.get(pc) {
+                Some(range) => match range.file {
+                    Some(file) if file < sources.len() => (file, range.offset),
+                    _ => continue,
+                },
+                None => continue,
+            };
+            let (line, _) = sources[file].line_column(offset);
+            let hits = coverage.hits.get(&pc).copied().unwrap_or(0);
+            let line_hits = lines.entry(file).or_default().entry(line).or_default();
+            *line_hits = (*line_hits).max(hits);
+
+            if coverage.code.0[pc] == OpCode::JUMPI.to_u8() {
+                let taken = coverage.branches.get(&pc).copied();
+                branches.entry(file).or_default().push((line, pc, taken));
+            }
+        }
+
+        let mut out = String::new();
+        for (file, lines) in lines {
+            let _ = writeln!(out, "SF:{}", sources[file].name);
+            for (line, hits) in &lines {
+                let _ = writeln!(out, "DA:{},{}", line, hits);
+            }
+            let _ = writeln!(out, "LF:{}", lines.len());
+            let _ = writeln!(out, "LH:{}", lines.values().filter(|hits| **hits > 0).count());
+
+            let branches = branches.remove(&file).unwrap_or_default();
+            let mut hit = 0;
+            for (line, pc, taken) in &branches {
+                let (jumped, not_jumped) = match taken {
+                    Some((jumped, not_jumped)) => (jumped.to_string(), not_jumped.to_string()),
+                    None => ("-".to_string(), "-".to_string()),
+                };
+                hit += taken.map_or(0, |(jumped, not_jumped)| (jumped > 0) as usize + (not_jumped > 0) as usize);
+                let _ = writeln!(out, "BRDA:{},{},0,{}", line, pc, jumped);
+                let _ = writeln!(out, "BRDA:{},{},1,{}", line, pc, not_jumped);
+            }
+            let _ = writeln!(out, "BRF:{}", branches.len() * 2);
+            let _ = writeln!(out, "BRH:{}", hit);
+            out.push_str("end_of_record\n");
+        }
+        out
+    }
+}
+
+impl Tracer for Coverage {
+    fn step(&mut self, opcode: OpCode, scope: &CallScope, _exec_context: &ExecutionContext) {
+        let coverage = match self.frames.last().and_then(|hash| self.codes.get_mut(hash)) {
+            Some(coverage) => coverage,
+            None => return,
+        };
+        *coverage.hits.entry(scope.pc).or_default() += 1;
+
+        if opcode == OpCode::JUMPI {
+            // condition is the second item from the top.
+            if let Ok(condition) = scope.stack.peek_at(1) {
+                let branch = coverage.branches.entry(scope.pc).or_default();
+                if condition.is_zero() {
+                    branch.1 += 1;
+                }else{
+                    branch.0 += 1;
+                }
+            }
+        }
+    }
+
+    fn call_enter(&mut self, _kind: CallKind, scope: &CallScope) {
+        let code_hash = keccak256(&scope.code.0);
+        self.codes.entry(code_hash).or_insert_with(|| CodeCoverage {
+            code: scope.code.clone(),
+            ..CodeCoverage::default()
+        });
+        self.frames.push(code_hash);
+    }
+
+    fn call_exit(&mut self, _scope: &CallScope, _status: &StatusCode, _output: &Bytes) {
+        self.frames.pop();
+    }
+}
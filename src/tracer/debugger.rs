@@ -0,0 +1,220 @@
+use bytes::Bytes;
+use ethereum_types::{Address, U256};
+use std::collections::HashMap;
+
+use crate::executor::callstack::{CallScope, ExecutionContext};
+use crate::interpreter::{CallKind, stack::Memory};
+use crate::model::{
+    evmc::StatusCode,
+    opcode::OpCode,
+};
+use super::Tracer;
+
+/// condition to pause the execution at.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Breakpoint {
+    /// before the instruction at this pc, in any scope.
+    Pc(usize),
+    /// before any instruction of this opcode.
+    Opcode(OpCode),
+    /// watchpoint, after the storage slot changes.
+    /// a write to a slot whose value was not seen before counts as a change.
+    Storage(Address, U256),
+    /// watchpoint, after a byte in the memory range of the current scope changes, as (offset, size).
+    Memory(usize, usize),
+}
+
+impl Breakpoint {
+    fn is_hit(&self, opcode: OpCode, scope: &CallScope) -> bool {
+        match self {
+            Breakpoint::Pc(pc) => *pc == scope.pc,
+            Breakpoint::Opcode(op) => *op == opcode,
+            _ => false,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PauseReason {
+    /// stepping from the previous instruction.
+    Step,
+    /// the breakpoint at this index of `Debugger::breakpoints`.
+    /// a watchpoint pauses before the instruction following the change.
+    Breakpoint(usize),
+}
+
+/// how to resume from a pause.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Command {
+    /// pause before the next instruction, stepping into calls.
+    Step,
+    /// run until a breakpoint is hit.
+    Continue,
+}
+
+/// scope on the call stack, as seen by the debugger.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Frame {
+    pub kind: CallKind,
+    pub code_address: Address,
+    pub caller: Address,
+    pub to: Address,
+    pub value: U256,
+    pub depth: i64,
+    /// pc of the current instruction, which is the call-like instruction for a caller.
+    pub pc: usize,
+}
+
+/// state of the paused execution, before `opcode` at `scope.pc` is executed.
+pub struct Paused<'a> {
+    pub reason: PauseReason,
+    pub opcode: OpCode,
+    pub scope: &'a CallScope,
+    pub exec_context: &'a ExecutionContext,
+    /// call stack from the outermost scope, the last being `scope`.
+    pub frames: &'a [Frame],
+}
+
+/// tracer pausing the execution before the first instruction and at breakpoints.
+///
+/// while paused, `handler` inspects the state, may add or remove breakpoints,
+/// and returns how to resume.
+pub struct Debugger<F> {
+    pub breakpoints: Vec<Breakpoint>,
+    handler: F,
+    stepping: bool,
+    frames: Vec<Frame>,
+    /// last value seen of each watched storage slot.
+    storage: HashMap<(Address, U256), U256>,
+    /// index and content of each watched memory range of each scope on the call stack,
+    /// before the last instruction of the scope.
+    memory: Vec<Vec<(usize, Vec<u8>)>>,
+    /// watchpoint changed by the previous instruction.
+    hit: Option<usize>,
+}
+
+impl<F: FnMut(&Paused, &mut Vec<Breakpoint>) -> Command> Debugger<F> {
+    pub fn new(handler: F) -> Self {
+        Self {
+            breakpoints: Vec::new(),
+            handler,
+            stepping: true,
+            frames: Vec::new(),
+            storage: HashMap::new(),
+            memory: Vec::new(),
+            hit: None,
+        }
+    }
+
+    /// run until the first breakpoint instead of pausing before the first instruction.
+    pub fn with_breakpoints(mut self, breakpoints: Vec<Breakpoint>) -> Self {
+        self.breakpoints = breakpoints;
+        self.stepping = false;
+        self
+    }
+
+    fn watched_storage(&self, address: Address, key: U256) -> Option<usize> {
+        self.breakpoints.iter()
+            .position(|breakpoint| *breakpoint == Breakpoint::Storage(address, key))
+    }
+
+    /// compare the watched memory ranges of the scope with those before its last instruction.
+    ///
+    /// this is done once the instruction is over, including the return data a call copies.
+    fn check_memory(&mut self, scope: &CallScope) {
+        let watched = match self.memory.last() {
+            Some(watched) => watched,
+            None => return,
+        };
+        for (index, before) in watched {
+            if let Some(Breakpoint::Memory(offset, size)) = self.breakpoints.get(*index) {
+                if is_changed(before, &memory_range(&scope.memory, *offset, *size)) {
+                    self.hit = Some(*index);
+                    break;
+                }
+            }
+        }
+    }
+}
+
+impl<F: FnMut(&Paused, &mut Vec<Breakpoint>) -> Command> Tracer for Debugger<F> {
+    fn step(&mut self, opcode: OpCode, scope: &CallScope, exec_context: &ExecutionContext) {
+        if let Some(frame) = self.frames.last_mut() {
+            frame.pc = scope.pc;
+        }
+        self.check_memory(scope);
+
+        let reason = match self.hit.take() {
+            Some(index) => Some(PauseReason::Breakpoint(index)),
+            None => match self.breakpoints.iter().position(|breakpoint| breakpoint.is_hit(opcode, scope)) {
+                Some(index) => Some(PauseReason::Breakpoint(index)),
+                None => self.stepping.then_some(PauseReason::Step),
+            },
+        };
+        if let Some(reason) = reason {
+            let paused = Paused {
+                reason,
+                opcode,
+                scope,
+                exec_context,
+                frames: &self.frames,
+            };
+            self.stepping = (self.handler)(&paused, &mut self.breakpoints) == Command::Step;
+        }
+
+        let watched = self.breakpoints.iter().enumerate()
+            .filter_map(|(index, breakpoint)| match breakpoint {
+                Breakpoint::Memory(offset, size) => Some((index, memory_range(&scope.memory, *offset, *size))),
+                _ => None,
+            })
+            .collect();
+        if let Some(memory) = self.memory.last_mut() {
+            *memory = watched;
+        }
+    }
+
+    fn call_enter(&mut self, kind: CallKind, scope: &CallScope) {
+        self.frames.push(Frame {
+            kind,
+            code_address: scope.code_address,
+            caller: scope.caller,
+            to: scope.to,
+            value: scope.value,
+            depth: scope.depth,
+            pc: scope.pc,
+        });
+        self.memory.push(Vec::new());
+    }
+
+    fn call_exit(&mut self, scope: &CallScope, _status: &StatusCode, _output: &Bytes) {
+        self.check_memory(scope);
+        self.frames.pop();
+        self.memory.pop();
+    }
+
+    fn storage_read(&mut self, address: Address, key: U256, value: U256) {
+        if self.watched_storage(address, key).is_some() {
+            self.storage.insert((address, key), value);
+        }
+    }
+
+    fn storage_write(&mut self, address: Address, key: U256, value: U256) {
+        if let Some(index) = self.watched_storage(address, key) {
+            if self.storage.insert((address, key), value) != Some(value) {
+                self.hit = Some(index);
+            }
+        }
+    }
+}
+
+/// bytes of the memory range up to the end of the memory, which is all zero beyond.
+fn memory_range(memory: &Memory, offset: usize, size: usize) -> Vec<u8> {
+    let end = offset.saturating_add(size).min(memory.0.len());
+    memory.0.get(offset..end).unwrap_or_default().to_vec()
+}
+
+/// true if the bytes differ, reading zero beyond the end of either.
+fn is_changed(before: &[u8], after: &[u8]) -> bool {
+    let byte = |bytes: &[u8], index: usize| bytes.get(index).copied().unwrap_or(0);
+    (0..before.len().max(after.len())).any(|index| byte(before, index) != byte(after, index))
+}
@@ -0,0 +1,199 @@
+use std::io::{self, Write};
+
+use bytes::Bytes;
+use ethereum_types::H256;
+use serde::Serialize;
+
+use crate::executor::callstack::{CallScope, ExecutionContext};
+use crate::interpreter::CallKind;
+use crate::model::{
+    evmc::{FailureKind, Output, StatusCode},
+    opcode::OpCode,
+    revision::Revision,
+    srcmap::Sources,
+};
+use crate::utils::keccak256;
+use super::Tracer;
+
+/// tracer writing one JSON line per step, as specified in EIP-3155.
+///
+/// lines are comparable with the output of geth's `evm --json` and evmone's tracer.
+/// https://eips.ethereum.org/EIPS/eip-3155
+pub struct Eip3155Tracer<W: Write> {
+    out: W,
+    /// include the whole memory in each line.
+    with_memory: bool,
+    /// step waiting for its gas cost.
+    pending: Option<StepLine>,
+    /// refund counter of the parent scopes when each scope was entered.
+    refund_bases: Vec<i64>,
+    refund: i64,
+    gas_limit: i64,
+    revision: Option<Revision>,
+    error: Option<io::Error>,
+    /// to add the source location of each step.
+    sources: Option<Sources>,
+    /// code hash of the scopes on the call stack, if `sources` is set.
+    frames: Vec<H256>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct StepLine {
+    pc: usize,
+    op: u8,
+    gas: String,
+    gas_cost: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    memory: Option<String>,
+    mem_size: usize,
+    stack: Vec<String>,
+    return_data: String,
+    depth: i64,
+    refund: i64,
+    op_name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+    /// not part of EIP-3155.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    source: Option<String>,
+    /// gas left before the step, to compute the gas cost of a failed step.
+    #[serde(skip)]
+    gas_left: i64,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SummaryLine {
+    state_root: String,
+    output: String,
+    gas_used: String,
+    pass: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    fork: Option<String>,
+}
+
+impl<W: Write> Eip3155Tracer<W> {
+    pub fn new(out: W) -> Self {
+        Self {
+            out,
+            with_memory: false,
+            pending: None,
+            refund_bases: Vec::new(),
+            refund: 0,
+            gas_limit: 0,
+            revision: None,
+            error: None,
+            sources: None,
+            frames: Vec::new(),
+        }
+    }
+
+    /// include the optional `memory` field in each line.
+    pub fn with_memory(mut self) -> Self {
+        self.with_memory = true;
+        self
+    }
+
+    /// add the location in the source of each step, as `file:line:column`, to the steps of known bytecodes.
+    pub fn with_sources(mut self, sources: Sources) -> Self {
+        self.sources = Some(sources);
+        self
+    }
+
+    /// write the summary line of the execution which produced `output`.
+    pub fn write_summary(&mut self, state_root: H256, output: &Output) {
+        let summary = SummaryLine {
+            state_root: format!("{:?}", state_root),
+            output: to_hex(&output.data),
+            gas_used: format!("{:#x}", self.gas_limit - output.gas_left.max(0) - output.effective_gas_refund),
+            pass: output.status_code == StatusCode::Success,
+            fork: self.revision.map(|revision| format!("{:?}", revision)),
+        };
+        self.write_line(&summary);
+    }
+
+    /// the writer, or the first error met while writing to it.
+    pub fn into_inner(self) -> io::Result<W> {
+        match self.error {
+            Some(err) => Err(err),
+            None => Ok(self.out),
+        }
+    }
+
+    fn write_line<T: Serialize>(&mut self, line: &T) {
+        if self.error.is_some() {
+            return;
+        }
+        let result = serde_json::to_writer(&mut self.out, line)
+            .map_err(io::Error::from)
+            .and_then(|_| self.out.write_all(b"\n"));
+        if let Err(err) = result {
+            self.error = Some(err);
+        }
+    }
+
+    fn flush_pending(&mut self, gas_cost: i64, error: Option<String>) {
+        if let Some(mut line) = self.pending.take() {
+            line.gas_cost = format!("{:#x}", gas_cost);
+            line.error = error;
+            self.write_line(&line);
+        }
+    }
+}
+
+impl<W: Write> Tracer for Eip3155Tracer<W> {
+    fn step(&mut self, opcode: OpCode, scope: &CallScope, exec_context: &ExecutionContext) {
+        self.revision = Some(exec_context.revision);
+        self.refund = self.refund_bases.last().copied().unwrap_or(0) + scope.refund_counter;
+
+        self.pending = Some(StepLine {
+            pc: scope.pc,
+            op: opcode.to_u8(),
+            gas: format!("{:#x}", scope.gas_left),
+            gas_cost: String::new(),
+            memory: self.with_memory.then(|| to_hex(&scope.memory.0)),
+            mem_size: scope.memory.0.len(),
+            stack: scope.stack.0.iter().map(|value| format!("{:#x}", value)).collect(),
+            return_data: to_hex(&exec_context.return_data_buffer),
+            depth: scope.depth + 1,
+            refund: self.refund,
+            op_name: format!("{:?}", opcode),
+            error: None,
+            source: self.sources.as_ref()
+                .zip(self.frames.last())
+                .and_then(|(sources, code_hash)| sources.locate(code_hash, scope.pc))
+                .map(|location| location.to_string()),
+            gas_left: scope.gas_left,
+        });
+    }
+
+    fn step_end(&mut self, _opcode: OpCode, gas_cost: i64, _scope: &CallScope, _exec_context: &ExecutionContext) {
+        self.flush_pending(gas_cost, None);
+    }
+
+    fn call_enter(&mut self, _kind: CallKind, scope: &CallScope) {
+        if scope.depth == 0 {
+            self.gas_limit = scope.gas_limit;
+            self.refund = 0;
+        }
+        self.refund_bases.push(self.refund);
+        if self.sources.is_some() {
+            self.frames.push(keccak256(&scope.code.0));
+        }
+    }
+
+    fn call_exit(&mut self, _scope: &CallScope, _status: &StatusCode, _output: &Bytes) {
+        self.refund_bases.pop();
+        self.frames.pop();
+    }
+
+    fn failure(&mut self, kind: &FailureKind, scope: &CallScope) {
+        let gas_cost = self.pending.as_ref().map_or(0, |line| line.gas_left - scope.gas_left.max(0));
+        self.flush_pending(gas_cost, Some(format!("{:?}", kind)));
+    }
+}
+
+fn to_hex(data: &[u8]) -> String {
+    format!("0x{}", hex::encode(data))
+}
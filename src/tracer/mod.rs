@@ -0,0 +1,150 @@
+pub mod call;
+pub mod coverage;
+pub mod debugger;
+pub mod eip3155;
+pub mod prestate;
+pub mod profiler;
+pub mod recorder;
+
+use bytes::Bytes;
+use ethereum_types::{Address, H256, U256};
+
+use crate::executor::callstack::{CallScope, ExecutionContext};
+use crate::interpreter::CallKind;
+use crate::model::{
+    evmc::{FailureKind, StatusCode},
+    opcode::OpCode,
+};
+use crate::utils::keccak256;
+
+/// hooks called by `Executor` while it runs.
+///
+/// every hook does nothing by default, so that a tracer only implements the ones it needs.
+pub trait Tracer {
+    /// before `opcode` at `scope.pc` is executed.
+    fn step(&mut self, _opcode: OpCode, _scope: &CallScope, _exec_context: &ExecutionContext) {}
+
+    /// after `opcode` is executed successfully, with the gas it consumed.
+    /// gas handed to a child scope counts as consumed by the call-like opcode.
+    fn step_end(&mut self, _opcode: OpCode, _gas_cost: i64, _scope: &CallScope, _exec_context: &ExecutionContext) {}
+
+    /// a new scope is pushed on the call stack. `scope` is the callee.
+    fn call_enter(&mut self, _kind: CallKind, _scope: &CallScope) {}
+
+    /// `scope` is popped from the call stack.
+    fn call_exit(&mut self, _scope: &CallScope, _status: &StatusCode, _output: &Bytes) {}
+
+    /// SLOAD of `key` read `value`.
+    fn storage_read(&mut self, _address: Address, _key: U256, _value: U256) {}
+
+    /// SSTORE wrote `value` to `key`.
+    fn storage_write(&mut self, _address: Address, _key: U256, _value: U256) {}
+
+    /// LOG0 to LOG4 emitted a log.
+    fn log(&mut self, _address: Address, _data: &[u8], _topics: &[U256]) {}
+
+    /// the instruction at `scope.pc` failed. `call_exit` follows.
+    fn failure(&mut self, _kind: &FailureKind, _scope: &CallScope) {}
+}
+
+/// tracer which traces nothing, and is optimized out.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct NoopTracer;
+
+impl Tracer for NoopTracer {}
+
+impl<T: Tracer + ?Sized> Tracer for &mut T {
+    fn step(&mut self, opcode: OpCode, scope: &CallScope, exec_context: &ExecutionContext) {
+        (**self).step(opcode, scope, exec_context)
+    }
+    fn step_end(&mut self, opcode: OpCode, gas_cost: i64, scope: &CallScope, exec_context: &ExecutionContext) {
+        (**self).step_end(opcode, gas_cost, scope, exec_context)
+    }
+    fn call_enter(&mut self, kind: CallKind, scope: &CallScope) {
+        (**self).call_enter(kind, scope)
+    }
+    fn call_exit(&mut self, scope: &CallScope, status: &StatusCode, output: &Bytes) {
+        (**self).call_exit(scope, status, output)
+    }
+    fn storage_read(&mut self, address: Address, key: U256, value: U256) {
+        (**self).storage_read(address, key, value)
+    }
+    fn storage_write(&mut self, address: Address, key: U256, value: U256) {
+        (**self).storage_write(address, key, value)
+    }
+    fn log(&mut self, address: Address, data: &[u8], topics: &[U256]) {
+        (**self).log(address, data, topics)
+    }
+    fn failure(&mut self, kind: &FailureKind, scope: &CallScope) {
+        (**self).failure(kind, scope)
+    }
+}
+
+/// trace with both tracers, the first one first.
+impl<A: Tracer, B: Tracer> Tracer for (A, B) {
+    fn step(&mut self, opcode: OpCode, scope: &CallScope, exec_context: &ExecutionContext) {
+        self.0.step(opcode, scope, exec_context);
+        self.1.step(opcode, scope, exec_context);
+    }
+    fn step_end(&mut self, opcode: OpCode, gas_cost: i64, scope: &CallScope, exec_context: &ExecutionContext) {
+        self.0.step_end(opcode, gas_cost, scope, exec_context);
+        self.1.step_end(opcode, gas_cost, scope, exec_context);
+    }
+    fn call_enter(&mut self, kind: CallKind, scope: &CallScope) {
+        self.0.call_enter(kind, scope);
+        self.1.call_enter(kind, scope);
+    }
+    fn call_exit(&mut self, scope: &CallScope, status: &StatusCode, output: &Bytes) {
+        self.0.call_exit(scope, status, output);
+        self.1.call_exit(scope, status, output);
+    }
+    fn storage_read(&mut self, address: Address, key: U256, value: U256) {
+        self.0.storage_read(address, key, value);
+        self.1.storage_read(address, key, value);
+    }
+    fn storage_write(&mut self, address: Address, key: U256, value: U256) {
+        self.0.storage_write(address, key, value);
+        self.1.storage_write(address, key, value);
+    }
+    fn log(&mut self, address: Address, data: &[u8], topics: &[U256]) {
+        self.0.log(address, data, topics);
+        self.1.log(address, data, topics);
+    }
+    fn failure(&mut self, kind: &FailureKind, scope: &CallScope) {
+        self.0.failure(kind, scope);
+        self.1.failure(kind, scope);
+    }
+}
+
+/// tracer which locates where the execution failed or reverted.
+///
+/// the location is the last REVERT or failed instruction,
+/// so a revert bubbled up by a caller is located at the caller.
+/// the code is hashed only when it fails, so that a successful run costs nothing.
+#[derive(Clone, Debug, Default)]
+pub struct FailureLocator {
+    /// code hash and pc of the last REVERT or failed instruction.
+    pub location: Option<(H256, usize)>,
+}
+
+impl FailureLocator {
+    fn locate(&mut self, scope: &CallScope) {
+        self.location = Some((keccak256(&scope.code.0), scope.pc));
+    }
+}
+
+impl Tracer for FailureLocator {
+    fn step(&mut self, opcode: OpCode, scope: &CallScope, _exec_context: &ExecutionContext) {
+        if opcode == OpCode::REVERT {
+            self.locate(scope);
+        }
+    }
+    fn call_enter(&mut self, _kind: CallKind, scope: &CallScope) {
+        if scope.depth == 0 {
+            self.location = None;
+        }
+    }
+    fn failure(&mut self, _kind: &FailureKind, scope: &CallScope) {
+        self.locate(scope);
+    }
+}
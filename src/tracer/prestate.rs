@@ -0,0 +1,306 @@
+use bytes::Bytes;
+use ethereum_types::{Address, H256, U256};
+use serde::{Serialize, Serializer};
+use std::{
+    cell::RefCell,
+    collections::BTreeMap,
+};
+
+use crate::executor::journal::Snapshot;
+use crate::host::{
+    Host,
+    stateful::{Account, LogRecord},
+};
+use crate::model::code::Code;
+use crate::model::evmc::{
+    Message, Output, TxContext, AccessStatus, StorageStatus, FailureKind,
+};
+
+/// state of an account, serialized in the format of geth's `prestateTracer`.
+///
+/// fields which are not set are omitted, such as zero nonce, empty code, or unchanged fields of a diff.
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct AccountState {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub balance: Option<U256>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nonce: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none", serialize_with = "serialize_code")]
+    pub code: Option<Bytes>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub storage: BTreeMap<H256, H256>,
+}
+
+/// state before and after the run of the accounts it changed.
+///
+/// `pre` holds the whole account but only the changed storage slots,
+/// `post` holds only the changed fields. created accounts are absent from `pre`,
+/// deleted accounts are absent from `post`.
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct StateDiff {
+    pub pre: BTreeMap<Address, AccountState>,
+    pub post: BTreeMap<Address, AccountState>,
+}
+
+/// snapshot of an account as it was the first time it was touched.
+#[derive(Clone, Debug)]
+struct Touched {
+    exists: bool,
+    balance: U256,
+    nonce: u64,
+    code: Bytes,
+    storage: BTreeMap<U256, U256>,
+}
+
+/// host recording the state of every account and storage slot touched through it,
+/// before they are changed.
+///
+/// it traces through `Host` rather than `Tracer`, since a `Tracer` hook is called after the state has changed.
+/// debug methods used to set up the state are not recorded.
+pub struct PrestateTracer<H: Host> {
+    inner: H,
+    touched: RefCell<BTreeMap<Address, Touched>>,
+}
+
+impl<H: Host> PrestateTracer<H> {
+    pub fn new(inner: H) -> Self {
+        Self {
+            inner,
+            touched: RefCell::default(),
+        }
+    }
+
+    pub fn inner(&self) -> &H {
+        &self.inner
+    }
+
+    pub fn into_inner(self) -> H {
+        self.inner
+    }
+
+    /// state of the touched accounts before they were touched.
+    pub fn prestate(&self) -> BTreeMap<Address, AccountState> {
+        self.touched.borrow().iter()
+            .map(|(address, touched)| (*address, touched.state(touched.storage.keys())))
+            .collect()
+    }
+
+    /// state the run changed, before and after.
+    pub fn diff(&self) -> StateDiff {
+        let mut diff = StateDiff::default();
+        for (address, pre) in self.touched.borrow().iter() {
+            let post = self.current(*address, pre.storage.keys());
+            let changed_keys: Vec<&U256> = pre.storage.keys()
+                .filter(|key| pre.storage[key] != post.storage[key])
+                .collect();
+
+            if pre.exists {
+                if post.exists && pre.balance == post.balance && pre.nonce == post.nonce
+                    && pre.code == post.code && changed_keys.is_empty() {
+                    continue;
+                }
+                diff.pre.insert(*address, pre.state(changed_keys.iter().copied()));
+            }
+
+            if post.exists {
+                let mut state = AccountState {
+                    balance: (!pre.exists || pre.balance != post.balance).then_some(post.balance),
+                    nonce: (!pre.exists || pre.nonce != post.nonce).then_some(post.nonce).filter(|nonce| *nonce != 0),
+                    code: (!pre.exists || pre.code != post.code).then(|| post.code.clone()).filter(|code| !code.is_empty()),
+                    storage: BTreeMap::new(),
+                };
+                for key in changed_keys {
+                    // cleared slots are omitted, as geth does.
+                    if !post.storage[key].is_zero() {
+                        state.storage.insert(to_h256(*key), to_h256(post.storage[key]));
+                    }
+                }
+                diff.post.insert(*address, state);
+            }
+        }
+        diff
+    }
+
+    fn current<'a>(&self, address: Address, keys: impl Iterator<Item = &'a U256>) -> Touched {
+        let code_size = self.inner.get_code_size(address).as_usize();
+        Touched {
+            exists: self.inner.account_exists(address),
+            balance: self.inner.get_balance(address),
+            nonce: self.inner.get_nonce(address),
+            code: self.inner.get_code(address, 0, code_size),
+            storage: keys.map(|key| (*key, self.inner.debug_get_storage(address, *key))).collect(),
+        }
+    }
+
+    fn touch_account(&self, address: Address) {
+        if self.touched.borrow().contains_key(&address) {
+            return;
+        }
+        let touched = self.current(address, std::iter::empty());
+        self.touched.borrow_mut().insert(address, touched);
+    }
+
+    fn touch_storage(&self, address: Address, key: U256) {
+        self.touch_account(address);
+        let value = self.inner.debug_get_storage(address, key);
+        if let Some(touched) = self.touched.borrow_mut().get_mut(&address) {
+            touched.storage.entry(key).or_insert(value);
+        }
+    }
+}
+
+impl Touched {
+    fn state<'a>(&self, keys: impl Iterator<Item = &'a U256>) -> AccountState {
+        AccountState {
+            balance: Some(self.balance),
+            nonce: Some(self.nonce).filter(|nonce| *nonce != 0),
+            code: Some(self.code.clone()).filter(|code| !code.is_empty()),
+            storage: keys.map(|key| (to_h256(*key), to_h256(self.storage[key]))).collect(),
+        }
+    }
+}
+
+impl<H: Host> Host for PrestateTracer<H> {
+    fn account_exists(&self, address: Address) -> bool {
+        self.touch_account(address);
+        self.inner.account_exists(address)
+    }
+    fn get_storage(&self, address: Address, key: U256) -> U256 {
+        self.touch_storage(address, key);
+        self.inner.get_storage(address, key)
+    }
+    fn set_storage(&mut self, address: Address, key: U256, value: U256) -> StorageStatus {
+        self.touch_storage(address, key);
+        self.inner.set_storage(address, key, value)
+    }
+    fn get_balance(&self, address: Address) -> U256 {
+        self.touch_account(address);
+        self.inner.get_balance(address)
+    }
+    fn get_code_size(&self, address: Address) -> U256 {
+        self.touch_account(address);
+        self.inner.get_code_size(address)
+    }
+    fn get_code_hash(&self, address: Address) -> U256 {
+        self.touch_account(address);
+        self.inner.get_code_hash(address)
+    }
+    fn copy_code(&self, address: Address, code_offset: usize, memory_offset: usize, size: usize) {
+        self.touch_account(address);
+        self.inner.copy_code(address, code_offset, memory_offset, size)
+    }
+    fn self_destruct(&mut self, address: Address, beneficiary: Address) {
+        self.touch_account(address);
+        self.touch_account(beneficiary);
+        self.inner.self_destruct(address, beneficiary)
+    }
+    fn call(&mut self, msg: &Message) -> Output {
+        self.inner.call(msg)
+    }
+    fn get_tx_context(&self) -> TxContext {
+        self.inner.get_tx_context()
+    }
+    fn emit_log(&mut self, address: Address, data: &[u8], topics: &[U256]) {
+        self.inner.emit_log(address, data, topics)
+    }
+    fn access_account(&mut self, address: Address) -> AccessStatus {
+        self.inner.access_account(address)
+    }
+    fn access_storage(&mut self, address: Address, key: U256) -> AccessStatus {
+        self.inner.access_storage(address, key)
+    }
+
+    fn add_account(&mut self, address: Address, account: Account) {
+        self.inner.add_account(address, account)
+    }
+    fn debug_get_storage(&self, address: Address, key: U256) -> U256 {
+        self.inner.debug_get_storage(address, key)
+    }
+    fn debug_set_storage(&mut self, address: Address, key: U256, new_value: U256) {
+        self.inner.debug_set_storage(address, key, new_value)
+    }
+    fn debug_set_storage_as_warm(&mut self) {
+        self.inner.debug_set_storage_as_warm()
+    }
+    fn debug_deploy_contract(&mut self, address_hex: &str, code: Code, balance: U256) {
+        self.inner.debug_deploy_contract(address_hex, code, balance)
+    }
+    fn debug_deploy_contract2(&mut self, address: Address, code: Code, balance: U256) {
+        self.inner.debug_deploy_contract2(address, code, balance)
+    }
+    fn get_blockhash(&self, height: usize) -> U256 {
+        self.inner.get_blockhash(height)
+    }
+    fn set_block_hash(&mut self, number: u64, hash: H256) {
+        self.inner.set_block_hash(number, hash)
+    }
+    fn get_code(&self, address: Address, offset: usize, size: usize) -> Bytes {
+        self.touch_account(address);
+        self.inner.get_code(address, offset, size)
+    }
+    fn add_balance(&mut self, address: Address, amount: U256) {
+        self.touch_account(address);
+        self.inner.add_balance(address, amount)
+    }
+    fn subtract_balance(&mut self, address: Address, amount: U256) {
+        self.touch_account(address);
+        self.inner.subtract_balance(address, amount)
+    }
+    fn get_nonce(&self, address: Address) -> u64 {
+        self.touch_account(address);
+        self.inner.get_nonce(address)
+    }
+    fn set_nonce(&mut self, address: Address, nonce: u64) {
+        self.touch_account(address);
+        self.inner.set_nonce(address, nonce)
+    }
+    fn set_code(&mut self, address: Address, code: Bytes) {
+        self.touch_account(address);
+        self.inner.set_code(address, code)
+    }
+    fn clear_empty_accounts(&mut self) {
+        self.inner.clear_empty_accounts()
+    }
+    fn take_snapshot(&self) -> Snapshot {
+        self.inner.take_snapshot()
+    }
+    fn rollback(&mut self, snapshot: &Snapshot) {
+        self.inner.rollback(snapshot)
+    }
+    fn force_update_storage(&mut self, address: Address, key: U256, value: U256) {
+        self.touch_storage(address, key);
+        self.inner.force_update_storage(address, key, value)
+    }
+    fn set_tx_context(&mut self, context: TxContext) {
+        self.inner.set_tx_context(context)
+    }
+    fn burn(&mut self, amount: U256) {
+        self.inner.burn(amount)
+    }
+    fn get_burnt_amount(&self) -> U256 {
+        self.inner.get_burnt_amount()
+    }
+    fn get_logs(&self) -> Vec<LogRecord> {
+        self.inner.get_logs()
+    }
+    fn commit(&mut self) {
+        self.inner.commit()
+    }
+    fn state_root(&self) -> H256 {
+        self.inner.state_root()
+    }
+    fn failure(&self) -> Option<FailureKind> {
+        self.inner.failure()
+    }
+}
+
+fn to_h256(value: U256) -> H256 {
+    H256(value.into())
+}
+
+fn serialize_code<S: Serializer>(code: &Option<Bytes>, serializer: S) -> Result<S::Ok, S::Error> {
+    match code {
+        Some(code) => serializer.serialize_str(&format!("0x{}", hex::encode(code))),
+        None => serializer.serialize_none(),
+    }
+}
@@ -0,0 +1,163 @@
+use bytes::Bytes;
+use ethereum_types::Address;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+use crate::executor::callstack::{CallScope, ExecutionContext};
+use crate::interpreter::CallKind;
+use crate::model::{
+    evmc::{FailureKind, StatusCode},
+    opcode::OpCode,
+};
+use super::Tracer;
+
+/// gas consumed and number of executions, or of calls for contracts and functions.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct GasStat {
+    pub count: u64,
+    pub gas: i64,
+}
+
+impl GasStat {
+    fn add(&mut self, gas: i64) {
+        self.count += 1;
+        self.gas += gas;
+    }
+}
+
+/// tracer aggregating the gas consumed by each instruction.
+///
+/// gas of a call-like instruction excludes the gas consumed by the callee,
+/// which is attributed to the instructions of the callee.
+#[derive(Clone, Debug, Default)]
+pub struct GasProfiler {
+    /// keyed by opcode byte.
+    pub opcodes: BTreeMap<u8, GasStat>,
+    pub pcs: BTreeMap<(Address, usize), GasStat>,
+    /// keyed by code address.
+    pub contracts: BTreeMap<Address, GasStat>,
+    /// keyed by the first 4 bytes of calldata.
+    pub selectors: BTreeMap<[u8; 4], GasStat>,
+    /// gas consumed by each frame itself, keyed by the frames from the outermost joined by `;`.
+    folded: BTreeMap<String, i64>,
+    frames: Vec<Frame>,
+}
+
+#[derive(Clone, Debug)]
+struct Frame {
+    code_address: Address,
+    selector: Option<[u8; 4]>,
+    name: String,
+    /// step whose gas is not known until the next step, as (pc, opcode, gas left before it).
+    pending: Option<(usize, u8, i64)>,
+    /// gas consumed by callees of the pending step.
+    callee_gas: i64,
+}
+
+impl GasProfiler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// per opcode, per contract and per function tables, each sorted by gas.
+    pub fn table(&self) -> String {
+        let mut out = String::new();
+
+        let opcodes = self.opcodes.iter()
+            .map(|(op, stat)| (OpCode::from_u8(*op).map_or(format!("0x{:02x}", op), |op| format!("{:?}", op)), *stat));
+        write_table(&mut out, "opcode", opcodes);
+
+        let contracts = self.contracts.iter().map(|(address, stat)| (format!("{:?}", address), *stat));
+        write_table(&mut out, "contract", contracts);
+
+        let selectors = self.selectors.iter().map(|(selector, stat)| (format!("0x{}", hex::encode(selector)), *stat));
+        write_table(&mut out, "function", selectors);
+
+        out
+    }
+
+    /// one line per call stack with the gas consumed in its innermost frame,
+    /// as consumed by flamegraph tools.
+    pub fn folded_stacks(&self) -> String {
+        self.folded.iter()
+            .filter(|(_, gas)| **gas > 0)
+            .map(|(stack, gas)| format!("{} {}\n", stack, gas))
+            .collect()
+    }
+
+    fn record(&mut self, gas_left: i64) {
+        let frame = match self.frames.last_mut() {
+            Some(frame) => frame,
+            None => return,
+        };
+        let (pc, op, gas_before) = match frame.pending.take() {
+            Some(pending) => pending,
+            None => return,
+        };
+        let gas = gas_before - gas_left - std::mem::take(&mut frame.callee_gas);
+
+        self.opcodes.entry(op).or_default().add(gas);
+        self.pcs.entry((frame.code_address, pc)).or_default().add(gas);
+        self.contracts.entry(frame.code_address).or_default().gas += gas;
+        if let Some(selector) = frame.selector {
+            self.selectors.entry(selector).or_default().gas += gas;
+        }
+
+        let stack = self.frames.iter().map(|frame| frame.name.as_str()).collect::<Vec<_>>().join(";");
+        *self.folded.entry(stack).or_default() += gas;
+    }
+}
+
+impl Tracer for GasProfiler {
+    fn step(&mut self, opcode: OpCode, scope: &CallScope, _exec_context: &ExecutionContext) {
+        self.record(scope.gas_left);
+        if let Some(frame) = self.frames.last_mut() {
+            frame.pending = Some((scope.pc, opcode.to_u8(), scope.gas_left));
+        }
+    }
+
+    fn call_enter(&mut self, _kind: CallKind, scope: &CallScope) {
+        let selector = scope.calldata.0.get(..4).map(|bytes| [bytes[0], bytes[1], bytes[2], bytes[3]]);
+        let name = match selector {
+            Some(selector) => format!("{:?}.0x{}", scope.code_address, hex::encode(selector)),
+            None => format!("{:?}", scope.code_address),
+        };
+
+        self.contracts.entry(scope.code_address).or_default().count += 1;
+        if let Some(selector) = selector {
+            self.selectors.entry(selector).or_default().count += 1;
+        }
+        self.frames.push(Frame {
+            code_address: scope.code_address,
+            selector,
+            name,
+            pending: None,
+            callee_gas: 0,
+        });
+    }
+
+    fn call_exit(&mut self, scope: &CallScope, status: &StatusCode, _output: &Bytes) {
+        // failure other than revert consumes all the gas.
+        let gas_left = match status {
+            StatusCode::Failure(kind) if *kind != FailureKind::Revert => 0,
+            _ => scope.gas_left.max(0),
+        };
+        self.record(gas_left);
+        self.frames.pop();
+
+        if let Some(parent) = self.frames.last_mut() {
+            parent.callee_gas += scope.gas_limit - gas_left;
+        }
+    }
+}
+
+fn write_table(out: &mut String, title: &str, rows: impl Iterator<Item = (String, GasStat)>) {
+    let mut rows: Vec<(String, GasStat)> = rows.collect();
+    rows.sort_by(|a, b| b.1.gas.cmp(&a.1.gas).then_with(|| a.0.cmp(&b.0)));
+
+    let _ = writeln!(out, "{:<44} {:>10} {:>12}", title, "count", "gas");
+    for (name, stat) in rows {
+        let _ = writeln!(out, "{:<44} {:>10} {:>12}", name, stat.count, stat.gas);
+    }
+    out.push('\n');
+}
@@ -0,0 +1,371 @@
+use bytes::Bytes;
+use ethereum_types::{Address, U256};
+
+use crate::executor::callstack::{CallScope, ExecutionContext};
+use crate::interpreter::{
+    CallKind,
+    stack::{Memory, Stack},
+};
+use crate::model::{
+    evmc::StatusCode,
+    opcode::OpCode,
+};
+use super::Tracer;
+
+/// change of the stack made by a step, as the items removed from the top and the items then pushed.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct StackDelta {
+    /// bottom first.
+    pub popped: Vec<U256>,
+    /// bottom first.
+    pub pushed: Vec<U256>,
+}
+
+/// change of the memory made by a step.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MemoryDelta {
+    /// memory size before and after the step.
+    pub size: (usize, usize),
+    /// offset of the first changed byte.
+    pub offset: usize,
+    /// changed bytes before the step, zero beyond the previous size.
+    pub old: Vec<u8>,
+    /// changed bytes after the step.
+    pub new: Vec<u8>,
+}
+
+/// instruction executed, with the changes it made.
+///
+/// the changes of a call-like instruction include the result the callee pushed and copied to memory.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RecordedStep {
+    pub pc: usize,
+    pub opcode: OpCode,
+    pub gas_left: i64,
+    pub depth: i64,
+    /// index of the scope in `Recording::frames`.
+    pub frame: usize,
+    pub stack: StackDelta,
+    pub memory: Option<MemoryDelta>,
+    /// storage written by the step, as (address, key, value).
+    pub storage: Vec<(Address, U256, U256)>,
+}
+
+/// scope executed, in the order of entry.
+#[derive(Clone, Debug)]
+pub struct RecordedFrame {
+    pub kind: CallKind,
+    pub code_address: Address,
+    /// stack and memory when the scope was entered.
+    pub stack: Stack,
+    pub memory: Memory,
+    /// indices of the steps executed in this scope.
+    pub steps: Vec<usize>,
+}
+
+/// execution log of a run.
+#[derive(Clone, Debug, Default)]
+pub struct Recording {
+    pub steps: Vec<RecordedStep>,
+    pub frames: Vec<RecordedFrame>,
+}
+
+impl Recording {
+    pub fn len(&self) -> usize {
+        self.steps.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.steps.is_empty()
+    }
+
+    /// stack and memory before the step `index`, replaying the steps of its scope.
+    pub fn state_at(&self, index: usize) -> (Stack, Memory) {
+        let frame = &self.frames[self.steps[index].frame];
+        let mut stack = frame.stack.clone();
+        let mut memory = frame.memory.clone();
+        for step in frame.steps.iter().take_while(|step| **step < index) {
+            apply(&self.steps[*step], &mut stack, &mut memory);
+        }
+        (stack, memory)
+    }
+
+    pub fn stack_at(&self, index: usize) -> Stack {
+        self.state_at(index).0
+    }
+
+    pub fn memory_at(&self, index: usize) -> Memory {
+        self.state_at(index).1
+    }
+
+    /// cursor before the last step.
+    pub fn cursor(&self) -> Cursor<'_> {
+        Cursor::new(self, self.steps.len().saturating_sub(1))
+    }
+}
+
+/// position in a recording, moving forward and backward through the steps.
+///
+/// moving within a scope applies or reverts the changes of a single step.
+pub struct Cursor<'a> {
+    recording: &'a Recording,
+    index: usize,
+    stack: Stack,
+    memory: Memory,
+}
+
+impl<'a> Cursor<'a> {
+    /// cursor before the step `index`.
+    pub fn new(recording: &'a Recording, index: usize) -> Self {
+        let (stack, memory) = match recording.steps.get(index) {
+            Some(_) => recording.state_at(index),
+            None => (Stack::default(), Memory::default()),
+        };
+        Self {
+            recording,
+            index,
+            stack,
+            memory,
+        }
+    }
+
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    pub fn step(&self) -> Option<&'a RecordedStep> {
+        self.recording.steps.get(self.index)
+    }
+
+    /// stack before the current step.
+    pub fn stack(&self) -> &Stack {
+        &self.stack
+    }
+
+    /// memory before the current step.
+    pub fn memory(&self) -> &Memory {
+        &self.memory
+    }
+
+    /// move to the previous step, returning false at the first step.
+    pub fn step_back(&mut self) -> bool {
+        if self.index == 0 || self.index >= self.recording.len() {
+            return false;
+        }
+        let steps = &self.recording.steps;
+        let previous = &steps[self.index - 1];
+        if previous.frame == steps[self.index].frame {
+            revert(previous, &mut self.stack, &mut self.memory);
+        }else{
+            (self.stack, self.memory) = self.recording.state_at(self.index - 1);
+        }
+        self.index -= 1;
+        true
+    }
+
+    /// move to the next step, returning false at the last step.
+    pub fn step_forward(&mut self) -> bool {
+        if self.index + 1 >= self.recording.len() {
+            return false;
+        }
+        let steps = &self.recording.steps;
+        let current = &steps[self.index];
+        if current.frame == steps[self.index + 1].frame {
+            apply(current, &mut self.stack, &mut self.memory);
+        }else{
+            (self.stack, self.memory) = self.recording.state_at(self.index + 1);
+        }
+        self.index += 1;
+        true
+    }
+}
+
+fn apply(step: &RecordedStep, stack: &mut Stack, memory: &mut Memory) {
+    let len = stack.len() - step.stack.popped.len();
+    stack.0.truncate(len);
+    stack.0.extend(step.stack.pushed.iter().copied());
+
+    if let Some(delta) = &step.memory {
+        memory.0.resize(delta.size.1, 0);
+        memory.set_range(delta.offset, &delta.new);
+    }
+}
+
+fn revert(step: &RecordedStep, stack: &mut Stack, memory: &mut Memory) {
+    let len = stack.len() - step.stack.pushed.len();
+    stack.0.truncate(len);
+    stack.0.extend(step.stack.popped.iter().copied());
+
+    if let Some(delta) = &step.memory {
+        memory.set_range(delta.offset, &delta.old);
+        memory.0.truncate(delta.size.0);
+    }
+}
+
+/// tracer recording the changes each step makes to the stack, the memory and the storage,
+/// so that the state at any step can be reconstructed without executing again.
+///
+/// storage rolled back by a revert is not recorded.
+#[derive(Clone, Debug, Default)]
+pub struct Recorder {
+    recording: Recording,
+    /// scopes on the call stack.
+    active: Vec<Active>,
+}
+
+/// scope on the call stack, with its state as of its last recorded step.
+#[derive(Clone, Debug)]
+struct Active {
+    frame: usize,
+    /// step whose changes are not known until the next step of the scope.
+    pending: Option<usize>,
+    stack: Vec<U256>,
+    memory_size: usize,
+    /// range the pending step may write to, as (offset, size), with its bytes up to the end of the memory before the step.
+    written: Option<(usize, usize, Vec<u8>)>,
+}
+
+impl Recorder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn recording(&self) -> &Recording {
+        &self.recording
+    }
+
+    pub fn into_recording(self) -> Recording {
+        self.recording
+    }
+
+    /// record the changes of the pending step of the current scope, which is now in `scope`.
+    fn finish_pending(&mut self, scope: &CallScope) {
+        let active = match self.active.last_mut() {
+            Some(active) => active,
+            None => return,
+        };
+        let index = match active.pending.take() {
+            Some(index) => index,
+            None => return,
+        };
+        let step = &mut self.recording.steps[index];
+
+        let stack = &scope.stack.0;
+        let common = active.stack.iter().zip(stack.iter())
+            .take_while(|(old, new)| old == new)
+            .count();
+        step.stack = StackDelta {
+            popped: active.stack.split_off(common),
+            pushed: stack[common..].to_vec(),
+        };
+        active.stack.extend_from_slice(&stack[common..]);
+
+        let memory = &scope.memory.0;
+        let written = active.written.take();
+        if memory.len() != active.memory_size || written.is_some() {
+            step.memory = memory_delta(active.memory_size, written, memory);
+            active.memory_size = memory.len();
+        }
+    }
+}
+
+impl Tracer for Recorder {
+    fn step(&mut self, opcode: OpCode, scope: &CallScope, _exec_context: &ExecutionContext) {
+        self.finish_pending(scope);
+        let active = match self.active.last_mut() {
+            Some(active) => active,
+            None => return,
+        };
+
+        let index = self.recording.steps.len();
+        self.recording.steps.push(RecordedStep {
+            pc: scope.pc,
+            opcode,
+            gas_left: scope.gas_left,
+            depth: scope.depth,
+            frame: active.frame,
+            stack: StackDelta::default(),
+            memory: None,
+            storage: Vec::new(),
+        });
+        self.recording.frames[active.frame].steps.push(index);
+        active.pending = Some(index);
+
+        let memory = &scope.memory.0;
+        active.written = written_range(opcode, &scope.stack).map(|(offset, size)| {
+            let end = offset.saturating_add(size).min(memory.len());
+            (offset, size, memory.get(offset..end).unwrap_or_default().to_vec())
+        });
+    }
+
+    fn call_enter(&mut self, kind: CallKind, scope: &CallScope) {
+        let frame = self.recording.frames.len();
+        self.recording.frames.push(RecordedFrame {
+            kind,
+            code_address: scope.code_address,
+            stack: scope.stack.clone(),
+            memory: scope.memory.clone(),
+            steps: Vec::new(),
+        });
+        self.active.push(Active {
+            frame,
+            pending: None,
+            stack: scope.stack.0.to_vec(),
+            memory_size: scope.memory.0.len(),
+            written: None,
+        });
+    }
+
+    fn call_exit(&mut self, scope: &CallScope, _status: &StatusCode, _output: &Bytes) {
+        self.finish_pending(scope);
+        self.active.pop();
+    }
+
+    fn storage_write(&mut self, address: Address, key: U256, value: U256) {
+        if let Some(step) = self.recording.steps.last_mut() {
+            step.storage.push((address, key, value));
+        }
+    }
+}
+
+/// memory range the opcode may write to, as (offset, size), read from the operands on the stack.
+/// a call-like opcode copies the return data of the callee.
+fn written_range(opcode: OpCode, stack: &Stack) -> Option<(usize, usize)> {
+    let operand = |index: usize| stack.peek_at(index).ok();
+    let (offset, size) = match opcode {
+        OpCode::MSTORE => (operand(0)?, U256::from(32)),
+        OpCode::MSTORE8 => (operand(0)?, U256::one()),
+        OpCode::CALLDATACOPY | OpCode::CODECOPY | OpCode::RETURNDATACOPY => (operand(0)?, operand(2)?),
+        OpCode::EXTCODECOPY => (operand(1)?, operand(3)?),
+        OpCode::CALL | OpCode::CALLCODE => (operand(5)?, operand(6)?),
+        OpCode::DELEGATECALL | OpCode::STATICCALL => (operand(4)?, operand(5)?),
+        _ => return None,
+    };
+    // the step fails for lack of gas with a range this large, writing nothing.
+    Some((usize::try_from(offset).ok()?, usize::try_from(size).ok()?))
+}
+
+/// change of the memory of `old_size` into `new`, from the first to the last changed byte of the written range.
+fn memory_delta(old_size: usize, written: Option<(usize, usize, Vec<u8>)>, new: &[u8]) -> Option<MemoryDelta> {
+    let (offset, size, old) = written.unwrap_or_default();
+    // the range is in memory after the step, unless the step failed.
+    let end = offset.saturating_add(size).min(new.len());
+    let new_range = new.get(offset..end).unwrap_or_default();
+
+    let byte = |memory: &[u8], index: usize| memory.get(index).copied().unwrap_or(0);
+    let is_changed = |index: &usize| byte(&old, *index) != new_range[*index];
+    let first = (0..new_range.len()).find(is_changed);
+    if first.is_none() && old_size == new.len() {
+        return None;
+    }
+    let (first, last) = match first {
+        Some(first) => (first, (first..new_range.len()).rev().find(is_changed).unwrap_or(first) + 1),
+        None => (0, 0),
+    };
+    Some(MemoryDelta {
+        size: (old_size, new.len()),
+        offset: if first < last { offset + first } else { 0 },
+        old: (first..last).map(|index| byte(&old, index)).collect(),
+        new: new_range[first..last].to_vec(),
+    })
+}
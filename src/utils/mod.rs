@@ -1,6 +1,18 @@
 pub mod i256;
+/// Recursive Length Prefix serialization.
+/// https://ethereum.org/en/developers/docs/data-structures-and-encoding/rlp/
+pub mod rlp;
+/// ECDSA over secp256k1, used for transaction signatures and the ECRECOVER precompile.
+pub mod secp256k1;
+/// Merkle Patricia Trie, which commits to the state, transactions and receipts.
+pub mod trie;
 
 use ethereum_types::{U256, H256, Address};
+use hex_literal::hex;
+use tiny_keccak::{Hasher, Keccak};
+
+/// keccak256 of empty bytes, the code hash of accounts without code.
+pub const KECCAK_EMPTY: H256 = H256(hex!("c5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470"));
 
 
 #[allow(dead_code)]
@@ -11,4 +23,12 @@ pub(crate) fn u256_to_address(v: U256) -> Address {
 #[allow(dead_code)]
 pub(crate) fn address_to_u256(v: Address) -> U256 {
     U256::from_big_endian(&v.0)
+}
+
+pub fn keccak256(data: &[u8]) -> H256 {
+    let mut hasher = Keccak::v256();
+    hasher.update(data);
+    let mut output = [0u8; 32];
+    hasher.finalize(&mut output);
+    H256(output)
 }
\ No newline at end of file
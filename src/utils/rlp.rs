@@ -0,0 +1,290 @@
+use ethereum_types::{Address, H256, U256};
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum RlpError {
+    /// input ended before the item did.
+    UnexpectedEnd,
+    /// bytes left after the top-level item.
+    TrailingBytes,
+    /// length prefix is not the shortest possible one.
+    NonCanonicalSize,
+    /// integer has leading zero bytes or does not fit.
+    InvalidInteger,
+    /// expected a byte string but found a list, or the other way around.
+    UnexpectedType,
+    /// byte string has an unexpected length, e.g. an address that is not 20 bytes long.
+    InvalidLength,
+    /// list has an unexpected number of items.
+    InvalidItemCount,
+    /// lists are nested deeper than `MAX_DEPTH`.
+    TooDeep,
+}
+
+/// maximum nesting depth of lists, which bounds the recursion of decoding.
+pub const MAX_DEPTH: usize = 128;
+
+/// decoded RLP item.
+#[derive(Clone, Debug, PartialEq)]
+pub enum RlpItem {
+    Bytes(Vec<u8>),
+    List(Vec<RlpItem>),
+}
+
+impl RlpItem {
+    pub fn as_bytes(&self) -> Result<&[u8], RlpError> {
+        match self {
+            RlpItem::Bytes(bytes) => Ok(bytes),
+            RlpItem::List(_) => Err(RlpError::UnexpectedType),
+        }
+    }
+
+    pub fn as_list(&self) -> Result<&[RlpItem], RlpError> {
+        match self {
+            RlpItem::List(items) => Ok(items),
+            RlpItem::Bytes(_) => Err(RlpError::UnexpectedType),
+        }
+    }
+
+    pub fn as_u256(&self) -> Result<U256, RlpError> {
+        let bytes = self.as_bytes()?;
+        if bytes.len() > 32 || bytes.first() == Some(&0) {
+            return Err(RlpError::InvalidInteger);
+        }
+        Ok(U256::from_big_endian(bytes))
+    }
+
+    pub fn as_u64(&self) -> Result<u64, RlpError> {
+        let value = self.as_u256()?;
+        if value > U256::from(u64::MAX) {
+            return Err(RlpError::InvalidInteger);
+        }
+        Ok(value.as_u64())
+    }
+
+    pub fn as_address(&self) -> Result<Address, RlpError> {
+        let bytes = self.as_bytes()?;
+        if bytes.len() != 20 {
+            return Err(RlpError::InvalidLength);
+        }
+        Ok(Address::from_slice(bytes))
+    }
+
+    /// empty byte string is decoded as `None`.
+    pub fn as_optional_address(&self) -> Result<Option<Address>, RlpError> {
+        if self.as_bytes()?.is_empty() {
+            return Ok(None);
+        }
+        self.as_address().map(Some)
+    }
+
+    pub fn as_h256(&self) -> Result<H256, RlpError> {
+        let bytes = self.as_bytes()?;
+        if bytes.len() != 32 {
+            return Err(RlpError::InvalidLength);
+        }
+        Ok(H256::from_slice(bytes))
+    }
+}
+
+/// encode a byte string.
+pub fn encode_bytes(bytes: &[u8]) -> Vec<u8> {
+    if bytes.len() == 1 && bytes[0] < 0x80 {
+        return vec![bytes[0]];
+    }
+    let mut out = encode_length(bytes.len(), 0x80);
+    out.extend_from_slice(bytes);
+    out
+}
+
+/// encode a list whose items are already encoded.
+pub fn encode_list(items: &[Vec<u8>]) -> Vec<u8> {
+    let payload_size = items.iter().map(|item| item.len()).sum();
+    let mut out = encode_length(payload_size, 0xc0);
+    for item in items {
+        out.extend_from_slice(item);
+    }
+    out
+}
+
+/// encode an integer as a big endian byte string without leading zeros.
+pub fn encode_u256(value: U256) -> Vec<u8> {
+    let mut word = [0u8; 32];
+    value.to_big_endian(&mut word);
+    let leading_zeros = word.iter().take_while(|b| **b == 0).count();
+    encode_bytes(&word[leading_zeros..])
+}
+
+pub fn encode_u64(value: u64) -> Vec<u8> {
+    encode_u256(U256::from(value))
+}
+
+pub fn encode_address(address: &Address) -> Vec<u8> {
+    encode_bytes(&address.0)
+}
+
+/// `None` is encoded as an empty byte string.
+pub fn encode_optional_address(address: &Option<Address>) -> Vec<u8> {
+    match address {
+        Some(address) => encode_address(address),
+        None => encode_bytes(&[]),
+    }
+}
+
+pub fn encode_h256(hash: &H256) -> Vec<u8> {
+    encode_bytes(&hash.0)
+}
+
+fn encode_length(size: usize, offset: u8) -> Vec<u8> {
+    if size <= 55 {
+        return vec![offset + size as u8];
+    }
+    let size_bytes = size.to_be_bytes();
+    let leading_zeros = size_bytes.iter().take_while(|b| **b == 0).count();
+    let size_bytes = &size_bytes[leading_zeros..];
+
+    let mut out = vec![offset + 55 + size_bytes.len() as u8];
+    out.extend_from_slice(size_bytes);
+    out
+}
+
+/// decode exactly one item spanning the whole input.
+pub fn decode(data: &[u8]) -> Result<RlpItem, RlpError> {
+    let (item, consumed) = decode_item(data)?;
+    if consumed != data.len() {
+        return Err(RlpError::TrailingBytes);
+    }
+    Ok(item)
+}
+
+/// decode the first item in `data`, returning it with the number of bytes consumed.
+pub fn decode_item(data: &[u8]) -> Result<(RlpItem, usize), RlpError> {
+    decode_item_at(data, 0)
+}
+
+fn decode_item_at(data: &[u8], depth: usize) -> Result<(RlpItem, usize), RlpError> {
+    let prefix = *data.first().ok_or(RlpError::UnexpectedEnd)?;
+    match prefix {
+        0x00..=0x7f => Ok((RlpItem::Bytes(vec![prefix]), 1)),
+        0x80..=0xbf => {
+            let (offset, size) = decode_length(data, 0x80)?;
+            let bytes = data.get(offset..end_of(offset, size)?).ok_or(RlpError::UnexpectedEnd)?;
+            if size == 1 && bytes[0] < 0x80 {
+                // a single byte below 0x80 is its own encoding.
+                return Err(RlpError::NonCanonicalSize);
+            }
+            Ok((RlpItem::Bytes(bytes.to_vec()), offset + size))
+        },
+        0xc0..=0xff => {
+            if depth >= MAX_DEPTH {
+                return Err(RlpError::TooDeep);
+            }
+            let (offset, size) = decode_length(data, 0xc0)?;
+            let mut payload = data.get(offset..end_of(offset, size)?).ok_or(RlpError::UnexpectedEnd)?;
+            let mut items = Vec::new();
+            while !payload.is_empty() {
+                let (item, consumed) = decode_item_at(payload, depth + 1)?;
+                items.push(item);
+                payload = &payload[consumed..];
+            }
+            Ok((RlpItem::List(items), offset + size))
+        },
+    }
+}
+
+fn end_of(offset: usize, size: usize) -> Result<usize, RlpError> {
+    offset.checked_add(size).ok_or(RlpError::UnexpectedEnd)
+}
+
+/// returns (offset of the payload, size of the payload).
+fn decode_length(data: &[u8], offset: u8) -> Result<(usize, usize), RlpError> {
+    let prefix = data[0] - offset;
+    if prefix <= 55 {
+        return Ok((1, prefix as usize));
+    }
+
+    let size_of_size = (prefix - 55) as usize;
+    let size_bytes = data.get(1..1 + size_of_size).ok_or(RlpError::UnexpectedEnd)?;
+    if size_bytes[0] == 0 || size_of_size > std::mem::size_of::<usize>() {
+        return Err(RlpError::NonCanonicalSize);
+    }
+    let size = size_bytes.iter().fold(0usize, |acc, b| (acc << 8) | *b as usize);
+    if size <= 55 {
+        return Err(RlpError::NonCanonicalSize);
+    }
+    Ok((1 + size_of_size, size))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::utils::rlp::*;
+    use hex::decode as decode_hex;
+
+    #[test]
+    pub fn test_encode_bytes() {
+        assert_eq!(vec![0x80], encode_bytes(&[]));
+        assert_eq!(vec![0x00], encode_bytes(&[0x00]));
+        assert_eq!(vec![0x7f], encode_bytes(&[0x7f]));
+        assert_eq!(vec![0x81, 0x80], encode_bytes(&[0x80]));
+        assert_eq!(decode_hex("83646f67").unwrap(), encode_bytes(b"dog"));
+
+        let long = [0x61u8; 56];
+        let encoded = encode_bytes(&long);
+        assert_eq!(vec![0xb8, 56], encoded[..2].to_vec());
+        assert_eq!(58, encoded.len());
+    }
+
+    #[test]
+    pub fn test_encode_integer() {
+        assert_eq!(vec![0x80], encode_u64(0));
+        assert_eq!(vec![0x0f], encode_u64(15));
+        assert_eq!(vec![0x82, 0x04, 0x00], encode_u64(1024));
+        assert_eq!(decode_hex("8504a817c800").unwrap(), encode_u256(U256::from(20_000_000_000u64)));
+    }
+
+    #[test]
+    pub fn test_encode_list() {
+        assert_eq!(vec![0xc0], encode_list(&[]));
+
+        // [ "cat", "dog" ]
+        let list = encode_list(&[encode_bytes(b"cat"), encode_bytes(b"dog")]);
+        assert_eq!(decode_hex("c88363617483646f67").unwrap(), list);
+
+        // [ [], [[]], [ [], [[]] ] ]
+        let empty = encode_list(&[]);
+        let nested = encode_list(std::slice::from_ref(&empty));
+        let list = encode_list(&[empty.clone(), nested.clone(), encode_list(&[empty, nested])]);
+        assert_eq!(decode_hex("c7c0c1c0c3c0c1c0").unwrap(), list);
+    }
+
+    #[test]
+    pub fn test_decode() {
+        let item = decode(&decode_hex("c88363617483646f67").unwrap()).unwrap();
+        assert_eq!(RlpItem::List(vec![RlpItem::Bytes(b"cat".to_vec()), RlpItem::Bytes(b"dog".to_vec())]), item);
+
+        let item = decode(&decode_hex("820400").unwrap()).unwrap();
+        assert_eq!(1024, item.as_u64().unwrap());
+
+        let long = [0x61u8; 1024];
+        let item = decode(&encode_bytes(&long)).unwrap();
+        assert_eq!(&long[..], item.as_bytes().unwrap());
+    }
+
+    #[test]
+    pub fn test_decode_invalid() {
+        assert_eq!(Err(RlpError::UnexpectedEnd), decode(&[]));
+        assert_eq!(Err(RlpError::UnexpectedEnd), decode(&[0x83, b'd', b'o']));
+        assert_eq!(Err(RlpError::TrailingBytes), decode(&[0x01, 0x02]));
+        assert_eq!(Err(RlpError::NonCanonicalSize), decode(&[0x81, 0x01]));
+        assert_eq!(Err(RlpError::NonCanonicalSize), decode(&[0xb8, 0x01, 0xff]));
+        assert_eq!(Err(RlpError::InvalidInteger), decode(&[0x82, 0x00, 0x01]).unwrap().as_u64());
+        assert_eq!(Err(RlpError::UnexpectedType), decode(&[0xc0]).unwrap().as_bytes());
+    }
+
+    #[test]
+    pub fn test_decode_depth() {
+        // [[[...]]] nested `depth` times.
+        let nested = |depth: usize| (0..depth).fold(encode_list(&[]), |inner, _| encode_list(&[inner]));
+        assert!(decode(&nested(MAX_DEPTH - 1)).is_ok());
+        assert_eq!(Err(RlpError::TooDeep), decode(&nested(MAX_DEPTH)));
+    }
+}
@@ -0,0 +1,101 @@
+use ethereum_types::{Address, H256, U256};
+use k256::ecdsa::{RecoveryId, Signature, SigningKey, VerifyingKey};
+
+use super::keccak256;
+
+/// order of the secp256k1 curve.
+pub const SECP256K1N: U256 = U256([
+    0xbfd25e8cd0364141,
+    0xbaaedce6af48a03b,
+    0xfffffffffffffffe,
+    0xffffffffffffffff,
+]);
+
+/// recover the address which signed `hash`.
+///
+/// `y_parity` is the recovery id, i.e. `0` or `1`.
+/// signatures with `s` in the upper half of the curve order are accepted as ECRECOVER does.
+/// returns `None` if the signature is invalid.
+pub fn recover_address(hash: &H256, y_parity: u8, r: U256, s: U256) -> Option<Address> {
+    if y_parity > 1 || r.is_zero() || s.is_zero() || r >= SECP256K1N || s >= SECP256K1N {
+        return None;
+    }
+
+    let signature = Signature::from_scalars(to_bytes(r), to_bytes(s)).ok()?;
+    let (signature, y_parity) = match signature.normalize_s() {
+        // (r, n - s) with the opposite parity is the same signature.
+        Some(normalized) => (normalized, y_parity ^ 1),
+        None => (signature, y_parity),
+    };
+    let recovery_id = RecoveryId::from_byte(y_parity)?;
+
+    let key = VerifyingKey::recover_from_prehash(hash.as_bytes(), &signature, recovery_id).ok()?;
+    Some(public_key_to_address(&key))
+}
+
+/// sign `hash` with `secret_key`, returning `(y_parity, r, s)` with `s` in the lower half of the curve order.
+///
+/// returns `None` if `secret_key` is not a valid secret key.
+pub fn sign(hash: &H256, secret_key: &H256) -> Option<(u8, U256, U256)> {
+    let key = SigningKey::from_slice(secret_key.as_bytes()).ok()?;
+    let (signature, recovery_id) = key.sign_prehash_recoverable(hash.as_bytes()).ok()?;
+    let (r, s) = signature.split_bytes();
+    Some((
+        recovery_id.to_byte(),
+        U256::from_big_endian(&r),
+        U256::from_big_endian(&s),
+    ))
+}
+
+/// address of the account controlled by `secret_key`.
+pub fn secret_key_to_address(secret_key: &H256) -> Option<Address> {
+    let key = SigningKey::from_slice(secret_key.as_bytes()).ok()?;
+    Some(public_key_to_address(key.verifying_key()))
+}
+
+/// the last 20 bytes of keccak256 of the uncompressed public key without its `0x04` prefix.
+fn public_key_to_address(key: &VerifyingKey) -> Address {
+    let point = key.to_encoded_point(false);
+    let hash = keccak256(&point.as_bytes()[1..]);
+    Address::from_slice(&hash.as_bytes()[12..])
+}
+
+fn to_bytes(value: U256) -> [u8; 32] {
+    let mut bytes = [0u8; 32];
+    value.to_big_endian(&mut bytes);
+    bytes
+}
+
+#[cfg(test)]
+mod test {
+    use crate::utils::secp256k1::*;
+    use hex_literal::hex;
+
+    #[test]
+    pub fn test_sign_and_recover() {
+        let secret_key = H256::from(hex!("4646464646464646464646464646464646464646464646464646464646464646"));
+        let address = Address::from(hex!("9d8a62f656a8d1615c1294fd71e9cfb3e4855a4f"));
+        assert_eq!(Some(address), secret_key_to_address(&secret_key));
+
+        let hash = keccak256(b"evmstar");
+        let (y_parity, r, s) = sign(&hash, &secret_key).unwrap();
+        assert!(s <= SECP256K1N / 2);
+        assert_eq!(Some(address), recover_address(&hash, y_parity, r, s));
+
+        // high s with flipped parity is accepted.
+        assert_eq!(Some(address), recover_address(&hash, y_parity ^ 1, r, SECP256K1N - s));
+
+        // wrong parity recovers another key.
+        assert_ne!(Some(address), recover_address(&hash, y_parity ^ 1, r, s));
+    }
+
+    #[test]
+    pub fn test_recover_invalid() {
+        let hash = keccak256(b"evmstar");
+        assert_eq!(None, recover_address(&hash, 0, U256::zero(), U256::one()));
+        assert_eq!(None, recover_address(&hash, 0, U256::one(), U256::zero()));
+        assert_eq!(None, recover_address(&hash, 0, SECP256K1N, U256::one()));
+        assert_eq!(None, recover_address(&hash, 2, U256::one(), U256::one()));
+        assert_eq!(None, sign(&hash, &H256::zero()));
+    }
+}
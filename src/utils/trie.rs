@@ -0,0 +1,379 @@
+use std::collections::{BTreeMap, HashMap};
+
+use ethereum_types::H256;
+use hex_literal::hex;
+
+use super::keccak256;
+use super::rlp::{self, encode_bytes, encode_list, encode_u64, RlpError, RlpItem};
+
+/// root hash of the empty trie, i.e. keccak256 of the RLP empty string.
+pub const EMPTY_ROOT: H256 = H256(hex!("56e81f171bcc55a6ff8345e692c0f86e5b48e01b996cadc001622fb5e363b421"));
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ProofError {
+    Rlp(RlpError),
+    /// proof ended before reaching the key.
+    MissingNode,
+    /// node does not hash to the reference in its parent, or the first node to the root.
+    HashMismatch,
+    /// node is neither a branch, an extension nor a leaf.
+    InvalidNode,
+    /// proven value differs from the claimed one.
+    ValueMismatch,
+}
+
+impl From<RlpError> for ProofError {
+    fn from(err: RlpError) -> Self {
+        ProofError::Rlp(err)
+    }
+}
+
+/// in-memory Merkle Patricia Trie.
+///
+/// nodes are not kept; they are rebuilt from the sorted entries whenever the root is computed.
+/// https://ethereum.org/en/developers/docs/data-structures-and-encoding/patricia-merkle-trie/
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Trie {
+    entries: BTreeMap<Vec<u8>, Vec<u8>>,
+}
+
+impl Trie {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// insert `value` at `key`. empty `value` removes the entry.
+    pub fn insert(&mut self, key: &[u8], value: Vec<u8>) {
+        if value.is_empty() {
+            self.entries.remove(key);
+        }else{
+            self.entries.insert(key.to_vec(), value);
+        }
+    }
+
+    pub fn get(&self, key: &[u8]) -> Option<&[u8]> {
+        self.entries.get(key).map(|value| value.as_slice())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn root_hash(&self) -> H256 {
+        let entries = self.nibble_entries();
+        keccak256(&encode_node(&entries, 0))
+    }
+
+    /// RLP encoded nodes on the path from the root to `key`, which prove the value at `key` or its absence.
+    ///
+    /// nodes embedded in their parent are not listed separately.
+    pub fn prove(&self, key: &[u8]) -> Vec<Vec<u8>> {
+        let entries = self.nibble_entries();
+        let mut proof = vec![encode_node(&entries, 0)];
+        prove_node(&entries, 0, &to_nibbles(key), &mut proof);
+        proof
+    }
+
+    fn nibble_entries(&self) -> Vec<(Vec<u8>, &[u8])> {
+        self.entries
+            .iter()
+            .map(|(key, value)| (to_nibbles(key), value.as_slice()))
+            .collect()
+    }
+}
+
+/// root of the trie keyed by RLP encoded index, e.g. transactions root and receipts root.
+pub fn ordered_trie_root<I>(items: I) -> H256
+    where I: IntoIterator<Item = Vec<u8>>
+{
+    let mut trie = Trie::new();
+    for (index, item) in items.into_iter().enumerate() {
+        trie.insert(&encode_u64(index as u64), item);
+    }
+    trie.root_hash()
+}
+
+fn to_nibbles(key: &[u8]) -> Vec<u8> {
+    key.iter().flat_map(|b| [b >> 4, b & 0x0f]).collect()
+}
+
+/// compact encoding of a nibble path with a flag telling leaf from extension.
+fn hex_prefix(nibbles: &[u8], is_leaf: bool) -> Vec<u8> {
+    let flag = if is_leaf { 2 } else { 0 };
+    let mut encoded = Vec::with_capacity(nibbles.len() / 2 + 1);
+    let rest = if nibbles.len() % 2 == 1 {
+        encoded.push(((flag + 1) << 4) | nibbles[0]);
+        &nibbles[1..]
+    }else{
+        encoded.push(flag << 4);
+        nibbles
+    };
+    for pair in rest.chunks(2) {
+        encoded.push((pair[0] << 4) | pair[1]);
+    }
+    encoded
+}
+
+/// node shorter than 32 bytes is embedded in its parent, otherwise referenced by its hash.
+fn node_reference(node: Vec<u8>) -> Vec<u8> {
+    if node.len() < 32 {
+        node
+    }else{
+        encode_bytes(keccak256(&node).as_bytes())
+    }
+}
+
+/// the nodes below the node holding `entries` on the path to `path`.
+fn prove_node(entries: &[(Vec<u8>, &[u8])], depth: usize, path: &[u8], proof: &mut Vec<Vec<u8>>) {
+    if entries.len() < 2 {
+        // empty trie or leaf.
+        return;
+    }
+    let first = &entries[0].0;
+    let last = &entries[entries.len() - 1].0;
+    let prefix_len = first[depth..].iter()
+        .zip(&last[depth..])
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    let (children, depth) = if prefix_len > 0 {
+        if !path[depth..].starts_with(&first[depth..depth + prefix_len]) {
+            return;
+        }
+        (entries, depth + prefix_len)
+    }else{
+        let Some(&nibble) = path.get(depth) else {
+            // value of the branch itself.
+            return;
+        };
+        let children: &[_] = if first.len() == depth { &entries[1..] } else { entries };
+        let start = children.iter().take_while(|(key, _)| key[depth] < nibble).count();
+        let count = children[start..].iter().take_while(|(key, _)| key[depth] == nibble).count();
+        if count == 0 {
+            return;
+        }
+        (&children[start..start + count], depth + 1)
+    };
+
+    let child = encode_node(children, depth);
+    if child.len() >= 32 {
+        proof.push(child);
+    }
+    prove_node(children, depth, path, proof);
+}
+
+/// value at `key` in the trie with `root`, or `None` if `proof` shows the key is absent.
+pub fn verify_proof(root: H256, key: &[u8], proof: &[Vec<u8>]) -> Result<Option<Vec<u8>>, ProofError> {
+    let mut nodes = proof.iter();
+    lookup(root, key, |hash| {
+        let encoded = nodes.next().ok_or(ProofError::MissingNode)?;
+        if keccak256(encoded) != hash {
+            return Err(ProofError::HashMismatch);
+        }
+        Ok(encoded)
+    })
+}
+
+/// value at `key` in the trie with `root`, resolving hash references with `nodes` keyed by their hash.
+///
+/// fails with `ProofError::MissingNode` if the path goes through a node not in `nodes`.
+pub fn get_from_nodes(root: H256, key: &[u8], nodes: &HashMap<H256, Vec<u8>>) -> Result<Option<Vec<u8>>, ProofError> {
+    lookup(root, key, |hash| nodes.get(&hash).map(Vec::as_slice).ok_or(ProofError::MissingNode))
+}
+
+fn lookup<'a, F>(root: H256, key: &[u8], mut resolve: F) -> Result<Option<Vec<u8>>, ProofError>
+    where F: FnMut(H256) -> Result<&'a [u8], ProofError>
+{
+    if root == EMPTY_ROOT {
+        return Ok(None);
+    }
+    let path = to_nibbles(key);
+    let mut depth = 0;
+    let mut reference = RlpItem::Bytes(root.as_bytes().to_vec());
+    loop {
+        let node = match reference {
+            RlpItem::Bytes(hash) if hash.len() == 32 => rlp::decode(resolve(H256::from_slice(&hash))?)?,
+            RlpItem::Bytes(empty) if empty.is_empty() => return Ok(None),
+            RlpItem::Bytes(_) => return Err(ProofError::InvalidNode),
+            embedded => embedded,
+        };
+
+        let mut items = match node {
+            RlpItem::List(items) => items,
+            _ => return Err(ProofError::InvalidNode),
+        };
+        match items.len() {
+            17 => {
+                if depth == path.len() {
+                    let value = items[16].as_bytes()?;
+                    return Ok(if value.is_empty() { None } else { Some(value.to_vec()) });
+                }
+                reference = items.swap_remove(path[depth] as usize);
+                depth += 1;
+            },
+            2 => {
+                let (nibbles, is_leaf) = decode_hex_prefix(items[0].as_bytes()?)?;
+                if is_leaf {
+                    return Ok(if path[depth..] == nibbles[..] { Some(items[1].as_bytes()?.to_vec()) } else { None });
+                }
+                if !path[depth..].starts_with(&nibbles) {
+                    return Ok(None);
+                }
+                reference = items.swap_remove(1);
+                depth += nibbles.len();
+            },
+            _ => return Err(ProofError::InvalidNode),
+        }
+    }
+}
+
+/// inverse of `hex_prefix()`, returning the nibbles and whether the node is a leaf.
+fn decode_hex_prefix(encoded: &[u8]) -> Result<(Vec<u8>, bool), ProofError> {
+    let first = *encoded.first().ok_or(ProofError::InvalidNode)?;
+    let flag = first >> 4;
+    if flag > 3 {
+        return Err(ProofError::InvalidNode);
+    }
+    let mut nibbles = Vec::with_capacity(encoded.len() * 2);
+    if flag & 1 == 1 {
+        nibbles.push(first & 0x0f);
+    }
+    nibbles.extend(to_nibbles(&encoded[1..]));
+    Ok((nibbles, flag & 2 == 2))
+}
+
+/// RLP encoding of the node holding sorted `entries`, all of which share the first `depth` nibbles.
+fn encode_node(entries: &[(Vec<u8>, &[u8])], depth: usize) -> Vec<u8> {
+    match entries {
+        [] => encode_bytes(&[]),
+        [(key, value)] => encode_list(&[
+            encode_bytes(&hex_prefix(&key[depth..], true)),
+            encode_bytes(value),
+        ]),
+        _ => {
+            // entries are sorted, so the first and the last have the shortest common prefix.
+            let first = &entries[0].0;
+            let last = &entries[entries.len() - 1].0;
+            let prefix_len = first[depth..].iter()
+                .zip(&last[depth..])
+                .take_while(|(a, b)| a == b)
+                .count();
+
+            if prefix_len > 0 {
+                let child = encode_node(entries, depth + prefix_len);
+                return encode_list(&[
+                    encode_bytes(&hex_prefix(&first[depth..depth + prefix_len], false)),
+                    node_reference(child),
+                ]);
+            }
+
+            let (value, mut rest) = if first.len() == depth {
+                (entries[0].1, &entries[1..])
+            }else{
+                (&[][..], entries)
+            };
+            let mut items = Vec::with_capacity(17);
+            for nibble in 0..16u8 {
+                let count = rest.iter().take_while(|(key, _)| key[depth] == nibble).count();
+                let (children, tail) = rest.split_at(count);
+                items.push(match children {
+                    [] => encode_bytes(&[]),
+                    _ => node_reference(encode_node(children, depth + 1)),
+                });
+                rest = tail;
+            }
+            items.push(encode_bytes(value));
+            encode_list(&items)
+        },
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::utils::trie::*;
+    use hex_literal::hex;
+
+    #[test]
+    pub fn test_empty_trie() {
+        assert_eq!(EMPTY_ROOT, Trie::new().root_hash());
+        assert_eq!(EMPTY_ROOT, ordered_trie_root(vec![]));
+    }
+
+    #[test]
+    pub fn test_trie_root() {
+        // https://github.com/ethereum/tests/blob/develop/TrieTests/trieanyorder.json
+        let mut trie = Trie::new();
+        trie.insert(b"doe", b"reindeer".to_vec());
+        trie.insert(b"dog", b"puppy".to_vec());
+        trie.insert(b"dogglesworth", b"cat".to_vec());
+        assert_eq!(H256(hex!("8aad789dff2f538bca5d8ea56e8abe10f4c7ba3a5dea95fea4cd6e7c3a1168d3")), trie.root_hash());
+
+        let mut trie = Trie::new();
+        trie.insert(b"do", b"verb".to_vec());
+        trie.insert(b"horse", b"stallion".to_vec());
+        trie.insert(b"doge", b"coin".to_vec());
+        trie.insert(b"dog", b"puppy".to_vec());
+        assert_eq!(H256(hex!("5991bb8c6514148a29db676a14ac506cd2cd5775ace63c30a4fe457715e9ac84")), trie.root_hash());
+        assert_eq!(Some(&b"coin"[..]), trie.get(b"doge"));
+
+        // removing an entry restores the previous root.
+        let root = trie.root_hash();
+        trie.insert(b"dogecoin", b"moon".to_vec());
+        assert_ne!(root, trie.root_hash());
+        trie.insert(b"dogecoin", vec![]);
+        assert_eq!(root, trie.root_hash());
+    }
+
+    #[test]
+    pub fn test_proof() {
+        let mut trie = Trie::new();
+        trie.insert(b"do", b"verb".to_vec());
+        trie.insert(b"horse", b"stallion".to_vec());
+        trie.insert(b"doge", b"coin".to_vec());
+        trie.insert(b"dog", b"puppy".to_vec());
+        for i in 0..64u64 {
+            trie.insert(keccak256(&i.to_be_bytes()).as_bytes(), vec![0x42; 40]);
+        }
+        let root = trie.root_hash();
+
+        for key in [&b"do"[..], b"dog", b"doge", b"horse", keccak256(&7u64.to_be_bytes()).as_bytes()] {
+            let proof = trie.prove(key);
+            assert_eq!(Ok(trie.get(key).map(|v| v.to_vec())), verify_proof(root, key, &proof));
+        }
+        for key in [&b"d"[..], b"dogs", b"cat", b"", keccak256(b"absent").as_bytes()] {
+            let proof = trie.prove(key);
+            assert_eq!(Ok(None), verify_proof(root, key, &proof));
+        }
+
+        // tampered or truncated proofs.
+        let key = keccak256(&7u64.to_be_bytes());
+        let mut proof = trie.prove(key.as_bytes());
+        assert_eq!(Err(ProofError::HashMismatch), verify_proof(H256::zero(), key.as_bytes(), &proof));
+        proof.pop();
+        assert_eq!(Err(ProofError::MissingNode), verify_proof(root, key.as_bytes(), &proof));
+
+        assert_eq!(Ok(None), verify_proof(EMPTY_ROOT, b"dog", &Trie::new().prove(b"dog")));
+
+        // nodes of every proof resolve every key.
+        let mut nodes = HashMap::new();
+        for key in [&b"dog"[..], b"horse"] {
+            for node in trie.prove(key) {
+                nodes.insert(keccak256(&node), node);
+            }
+        }
+        assert_eq!(Ok(Some(b"puppy".to_vec())), get_from_nodes(root, b"dog", &nodes));
+        assert_eq!(Ok(Some(b"verb".to_vec())), get_from_nodes(root, b"do", &nodes));
+        assert_eq!(Ok(Some(b"stallion".to_vec())), get_from_nodes(root, b"horse", &nodes));
+        assert_eq!(Err(ProofError::MissingNode), get_from_nodes(root, keccak256(&7u64.to_be_bytes()).as_bytes(), &nodes));
+    }
+
+    #[test]
+    pub fn test_hex_prefix() {
+        assert_eq!(vec![0x11, 0x23, 0x45], hex_prefix(&[1, 2, 3, 4, 5], false));
+        assert_eq!(vec![0x00, 0x01, 0x23, 0x45], hex_prefix(&[0, 1, 2, 3, 4, 5], false));
+        assert_eq!(vec![0x20, 0x0f, 0x1c, 0xb8], hex_prefix(&[0, 15, 1, 12, 11, 8], true));
+        assert_eq!(vec![0x3f, 0x1c, 0xb8], hex_prefix(&[15, 1, 12, 11, 8], true));
+        assert_eq!(Ok((vec![0, 15, 1, 12, 11, 8], true)), decode_hex_prefix(&[0x20, 0x0f, 0x1c, 0xb8]));
+        assert_eq!(Ok((vec![1, 2, 3, 4, 5], false)), decode_hex_prefix(&[0x11, 0x23, 0x45]));
+    }
+}
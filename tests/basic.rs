@@ -34,7 +34,7 @@ fn test_empty_with_intrinsic_cost() {
     for revision in Revision::iter() {
         let host = TransientHost::new();
         let host = Rc::new(RefCell::new(host));
-        let mut executor = Executor::new_with_execution_cost(host.clone(), true, revision);
+        let mut executor = Executor::new_with_execution_cost(host.clone(), revision);
 
         let output = executor.execute_raw(code);
         assert_eq!(StatusCode::Success, output.status_code);
@@ -47,7 +47,7 @@ fn test_calldata_cost() {
     for revision in Revision::iter() {
         let host = TransientHost::new();
         let host = Rc::new(RefCell::new(host));
-        let mut executor = Executor::new_with_execution_cost(host.clone(), true, revision);
+        let mut executor = Executor::new_with_execution_cost(host.clone(), revision);
 
         let mut context = CallScope::default();
         let mut vec: Vec<u8> = Vec::new();
@@ -84,7 +84,7 @@ fn test_too_large_code() {
     for revision in Revision::iter() {
         let host = TransientHost::new();
         let host = Rc::new(RefCell::new(host));
-        let mut executor = Executor::new_with(host.clone(), false, revision);
+        let mut executor = Executor::new_with(host.clone(), revision);
         
         let output = executor.execute_raw(&code);
         if revision >= Revision::Spurious {
@@ -0,0 +1,439 @@
+use std::rc::Rc;
+use std::cell::RefCell;
+
+use bytes::Bytes;
+use ethereum_types::{U256, H256, Address, BloomInput};
+use evmstar::model::{
+    code::{
+        Code, Append,
+    },
+    opcode::OpCode,
+    evmc::{
+        StatusCode, FailureKind,
+        TxContext,
+    },
+    revision::Revision,
+    receipt::receipts_root,
+    transaction::*,
+    withdrawal::Withdrawal,
+};
+use evmstar::executor::block::{BlockExecutor, BlockError, Ommer};
+use evmstar::host::{Host, stateful::{StatefulHost, LogRecord}};
+use hex_literal::hex;
+
+fn address_sender() -> Address {
+    Address::from(hex!("9d8a62f656a8d1615c1294fd71e9cfb3e4855a4f"))
+}
+fn address_contract() -> Address {
+    Address::from_low_u64_be(0xaaaaaaaa)
+}
+fn address_coinbase() -> Address {
+    Address::from_low_u64_be(0xabcd)
+}
+
+fn get_default_context() -> TxContext {
+    TxContext {
+        gas_price: U256::zero(),
+        origin: Address::zero(),
+        coinbase: address_coinbase(),
+        block_number: 0x1111,
+        block_timestamp: 0x2222,
+        gas_limit: 100_000,
+        base_fee: U256::from(10),
+        chain_id: U256::from(0x01),
+        difficulty: U256::from(0x5555),
+        excess_blob_gas: 0,
+        blob_hashes: vec![],
+    }
+}
+
+/// emits LOG1 with topic `0xff` and CALLVALUE as data.
+fn log_code() -> Code {
+    Code::builder()
+        .append(OpCode::CALLVALUE)
+        .append(OpCode::PUSH1)
+        .append("00")
+        .append(OpCode::MSTORE)
+        .append(OpCode::PUSH1)
+        .append("ff")
+        .append(OpCode::PUSH1)
+        .append("20")
+        .append(OpCode::PUSH1)
+        .append("00")
+        .append(OpCode::LOG1)
+        .clone()
+}
+const LOG_CODE_GAS: u64 = 2 + 3 + 6 + 3 + 3 + 3 + (375 + 375 + 8 * 32);
+
+fn transaction(gas_limit: u64, value: u64) -> Transaction {
+    transaction_to(address_contract(), gas_limit, value)
+}
+
+fn transaction_to(to: Address, gas_limit: u64, value: u64) -> Transaction {
+    Transaction::DynamicFee(DynamicFeeTransaction {
+        chain_id: U256::one(),
+        nonce: 0,
+        max_priority_fee_per_gas: U256::from(2),
+        max_fee_per_gas: U256::from(100),
+        gas_limit,
+        to: Some(to),
+        value: U256::from(value),
+        data: Bytes::default(),
+        access_list: vec![],
+        signature: Signature::default(),
+    })
+}
+
+fn legacy_transaction_to(to: Address, gas_limit: u64, gas_price: u64) -> Transaction {
+    Transaction::Legacy(LegacyTransaction {
+        nonce: 0,
+        gas_price: U256::from(gas_price),
+        gas_limit,
+        to: Some(to),
+        value: U256::zero(),
+        data: Bytes::default(),
+        signature: Signature::default(),
+    })
+}
+
+fn transaction_with_nonce(nonce: u64, gas_limit: u64, value: u64) -> Transaction {
+    let mut tx = transaction(gas_limit, value);
+    if let Transaction::DynamicFee(tx) = &mut tx {
+        tx.nonce = nonce;
+    }
+    tx
+}
+
+fn host_with(context: TxContext, code: Code) -> Rc<RefCell<StatefulHost>> {
+    let mut host = StatefulHost::new_with(context);
+    host.debug_deploy_contract2(address_sender(), Code::empty(), U256::from(100_000_000));
+    host.debug_deploy_contract2(address_contract(), code, U256::zero());
+    Rc::new(RefCell::new(host))
+}
+
+#[test]
+fn test_block_receipts() {
+    let host = host_with(get_default_context(), log_code());
+    let mut block = BlockExecutor::new(host.clone(), Revision::London);
+
+    let receipt = block.apply_transaction(&transaction(30_000, 1), address_sender()).unwrap();
+    assert_eq!(StatusCode::Success, receipt.status_code);
+    assert_eq!(21000 + LOG_CODE_GAS, receipt.gas_used);
+    assert_eq!(21000 + LOG_CODE_GAS, receipt.cumulative_gas_used);
+    assert_eq!(
+        vec![LogRecord {
+            creator: address_contract(),
+            data: Bytes::from(H256::from_low_u64_be(1).as_bytes().to_vec()),
+            topics: vec![U256::from(0xff)],
+        }],
+        receipt.logs
+    );
+
+    let receipt = block.apply_transaction(&transaction_with_nonce(1, 30_000, 2), address_sender()).unwrap();
+    assert_eq!(2 * (21000 + LOG_CODE_GAS), receipt.cumulative_gas_used);
+    assert_eq!(Bytes::from(H256::from_low_u64_be(2).as_bytes().to_vec()), receipt.logs[0].data);
+
+    let output = block.finish();
+    let gas_used = 2 * (21000 + LOG_CODE_GAS);
+    assert_eq!(2, output.receipts.len());
+    assert_eq!(gas_used, output.gas_used);
+    assert_eq!(U256::from(gas_used * 10), output.burnt);
+    assert!(output.logs_bloom.contains_input(BloomInput::Raw(address_contract().as_bytes())));
+    assert!(output.logs_bloom.contains_input(BloomInput::Raw(H256::from_low_u64_be(0xff).as_bytes())));
+    assert!(!output.logs_bloom.contains_input(BloomInput::Raw(address_sender().as_bytes())));
+    assert_eq!(receipts_root(&output.receipts), output.receipts_root);
+    assert_eq!(host.borrow().state_root(), output.state_root);
+
+    let host = host.borrow();
+    assert_eq!(U256::from(100_000_000 - 3 - gas_used * 12), host.get_balance(address_sender()));
+    assert_eq!(U256::from(3), host.get_balance(address_contract()));
+    assert_eq!(U256::from(gas_used * 2), host.get_balance(address_coinbase()));
+}
+
+#[test]
+fn test_block_reverted_transaction() {
+    let code = log_code()
+        .append(OpCode::PUSH1)
+        .append("00")
+        .append(OpCode::DUP1)
+        .append(OpCode::REVERT)
+        .clone();
+
+    let host = host_with(get_default_context(), code);
+    let mut block = BlockExecutor::new(host, Revision::London);
+
+    // logs of reverted transaction are discarded, but gas is still used.
+    let receipt = block.apply_transaction(&transaction(30_000, 1), address_sender()).unwrap();
+    assert_eq!(StatusCode::Failure(FailureKind::Revert), receipt.status_code);
+    assert!(!receipt.is_success());
+    assert_eq!(21000 + LOG_CODE_GAS + 6, receipt.gas_used);
+    assert!(receipt.logs.is_empty());
+
+    let output = block.finish();
+    assert_eq!(21000 + LOG_CODE_GAS + 6, output.gas_used);
+    assert!(output.logs_bloom.is_zero());
+}
+
+#[test]
+fn test_block_gas_limit() {
+    let mut context = get_default_context();
+    context.gas_limit = 50_000;
+
+    let host = host_with(context, log_code());
+    let mut block = BlockExecutor::new(host.clone(), Revision::London);
+
+    assert!(block.apply_transaction(&transaction(30_000, 1), address_sender()).is_ok());
+    // gas limit of the transaction, not gas actually used, must fit in the block.
+    assert_eq!(
+        Err(BlockError::GasLimitExceeded { index: 1 }),
+        block.apply_transaction(&transaction_with_nonce(1, 30_000, 1), address_sender()).cloned()
+    );
+    assert!(block.apply_transaction(&transaction_with_nonce(1, 50_000 - 21000 - LOG_CODE_GAS, 1), address_sender()).is_ok());
+
+    let output = block.finish();
+    assert_eq!(2, output.receipts.len());
+    assert_eq!(U256::from(2), host.borrow().get_balance(address_contract()));
+}
+
+#[test]
+fn test_block_gas_limit_overflow() {
+    let host = host_with(get_default_context(), log_code());
+    let mut block = BlockExecutor::new(host.clone(), Revision::London);
+
+    assert!(block.apply_transaction(&transaction(30_000, 1), address_sender()).is_ok());
+    assert_eq!(
+        Err(BlockError::InvalidTransaction { index: 1, kind: FailureKind::Rejected }),
+        block.apply_transaction(&transaction_with_nonce(1, u64::MAX, 1), address_sender()).cloned()
+    );
+}
+
+#[test]
+fn test_block_intrinsic_gas() {
+    let host = host_with(get_default_context(), log_code());
+    let balance = host.borrow().get_balance(address_sender());
+    let mut block = BlockExecutor::new(host.clone(), Revision::London);
+
+    assert_eq!(
+        Err(BlockError::InvalidTransaction { index: 0, kind: FailureKind::Rejected }),
+        block.apply_transaction(&transaction(20_999, 0), address_sender()).cloned()
+    );
+    assert_eq!(0, host.borrow().get_nonce(address_sender()));
+    assert_eq!(balance, host.borrow().get_balance(address_sender()));
+}
+
+#[test]
+fn test_block_failed_transaction() {
+    let code = Code::builder()
+        .append("602a600155")     // PUSH1 0x2a PUSH1 0x01 SSTORE
+        .append("60006000a0")     // PUSH1 0x00 PUSH1 0x00 LOG0
+        .append(OpCode::POP)      // stack underflow
+        .clone();
+    let host = host_with(get_default_context(), code);
+    let mut block = BlockExecutor::new(host.clone(), Revision::London);
+
+    let receipt = block.apply_transaction(&transaction(100_000, 1), address_sender()).unwrap().clone();
+    assert_eq!(StatusCode::Failure(FailureKind::StackUnderflow), receipt.status_code);
+    assert!(receipt.logs.is_empty());
+    assert_eq!(U256::zero(), host.borrow().get_storage(address_contract(), U256::from(1)));
+    assert_eq!(U256::zero(), host.borrow().get_balance(address_contract()));
+    assert_eq!(1, host.borrow().get_nonce(address_sender()));
+}
+
+#[test]
+fn test_block_invalid_transaction() {
+    let host = host_with(get_default_context(), log_code());
+    let mut block = BlockExecutor::new(host.clone(), Revision::London);
+
+    let tx = transaction(30_000, 100_000_000);
+    assert_eq!(
+        Err(BlockError::InvalidTransaction { index: 0, kind: FailureKind::InsufficientBalance }),
+        block.apply_transaction(&tx, address_sender()).cloned()
+    );
+    // unsigned transaction.
+    assert_eq!(
+        Err(BlockError::InvalidTransaction { index: 0, kind: FailureKind::Rejected }),
+        block.apply_signed_transaction(&transaction(30_000, 1)).cloned()
+    );
+
+    let output = block.finish();
+    assert!(output.receipts.is_empty());
+    assert_eq!(0, output.gas_used);
+    assert_eq!(U256::from(100_000_000), host.borrow().get_balance(address_sender()));
+}
+
+#[test]
+fn test_block_signed_transaction() {
+    let secret_key = H256::from(hex!("4646464646464646464646464646464646464646464646464646464646464646"));
+    let mut tx = transaction(30_000, 1);
+    tx.sign(&secret_key).unwrap();
+
+    let host = host_with(get_default_context(), log_code());
+    let mut block = BlockExecutor::new(host, Revision::London);
+
+    let receipt = block.apply_signed_transaction(&tx).unwrap();
+    assert_eq!(DYNAMIC_FEE_TX_TYPE, receipt.tx_type);
+    assert!(receipt.is_success());
+}
+
+#[test]
+fn test_block_access_is_cold_in_each_transaction() {
+    let code = Code::builder()
+        .append(OpCode::PUSH1)
+        .append("00")
+        .append(OpCode::SLOAD)
+        .append(OpCode::POP)
+        .clone();
+
+    let host = host_with(get_default_context(), code);
+    let mut block = BlockExecutor::new(host, Revision::London);
+
+    let first = block.apply_transaction(&transaction(30_000, 0), address_sender()).unwrap().gas_used;
+    let second = block.apply_transaction(&transaction_with_nonce(1, 30_000, 0), address_sender()).unwrap().gas_used;
+    assert_eq!(21000 + 3 + 2100 + 2, first);
+    assert_eq!(first, second);
+}
+
+#[test]
+fn test_block_sender_nonce() {
+    let host = host_with(get_default_context(), log_code());
+    let mut block = BlockExecutor::new(host.clone(), Revision::London);
+
+    block.apply_transaction(&transaction(30_000, 1), address_sender()).unwrap();
+    block.apply_transaction(&transaction_with_nonce(1, 30_000, 1), address_sender()).unwrap();
+    assert_eq!(2, host.borrow().get_nonce(address_sender()));
+
+    // invalid transaction does not bump the nonce.
+    assert!(block.apply_transaction(&transaction_with_nonce(2, 30_000, 100_000_000), address_sender()).is_err());
+    assert_eq!(2, host.borrow().get_nonce(address_sender()));
+
+    // replayed and future nonces.
+    for nonce in [0, 42] {
+        assert_eq!(
+            Err(BlockError::InvalidTransaction { index: 2, kind: FailureKind::InvalidNonce }),
+            block.apply_transaction(&transaction_with_nonce(nonce, 30_000, 1), address_sender()).cloned()
+        );
+    }
+    assert_eq!(2, host.borrow().get_nonce(address_sender()));
+}
+
+/// defined in https://eips.ethereum.org/EIPS/eip-2681
+#[test]
+fn test_block_sender_nonce_overflow() {
+    let host = host_with(get_default_context(), log_code());
+    host.borrow_mut().set_nonce(address_sender(), u64::MAX);
+    host.borrow_mut().commit();
+
+    let mut block = BlockExecutor::new(host.clone(), Revision::London);
+    assert_eq!(
+        Err(BlockError::InvalidTransaction { index: 0, kind: FailureKind::Rejected }),
+        block.apply_transaction(&transaction(30_000, 1), address_sender()).cloned()
+    );
+    assert_eq!(u64::MAX, host.borrow().get_nonce(address_sender()));
+    assert_eq!(U256::from(100_000_000), host.borrow().get_balance(address_sender()));
+}
+
+/// defined in https://eips.ethereum.org/EIPS/eip-161
+#[test]
+fn test_block_touched_empty_account_is_deleted() {
+    let empty = Address::from_low_u64_be(0xeeee);
+    for (revision, exists) in [(Revision::Tangerine, true), (Revision::Spurious, false), (Revision::London, false)] {
+        let host = host_with(get_default_context(), log_code());
+        host.borrow_mut().debug_deploy_contract2(empty, Code::empty(), U256::zero());
+
+        let mut block = BlockExecutor::new(host.clone(), revision);
+        block.apply_transaction(&legacy_transaction_to(empty, 30_000, 10), address_sender()).unwrap();
+        assert_eq!(exists, host.borrow().account_exists(empty));
+        // sender is not empty, since its nonce is bumped.
+        assert!(host.borrow().account_exists(address_sender()));
+    }
+}
+
+#[test]
+fn test_block_gas_price_before_london() {
+    let code = Code::builder()
+        .append("6000")         // PUSH1 0x00
+        .append(OpCode::DUP1)
+        .append(OpCode::REVERT)
+        .clone();
+    let host = host_with(get_default_context(), code);
+    let balance = host.borrow().get_balance(address_sender());
+
+    let mut block = BlockExecutor::new(host.clone(), Revision::Berlin);
+    let receipt = block.apply_transaction(&legacy_transaction_to(address_contract(), 30_000, 3), address_sender()).unwrap().clone();
+    assert_eq!(StatusCode::Failure(FailureKind::Revert), receipt.status_code);
+
+    // the revert keeps the nonce and the fee, which is paid to the coinbase as a whole.
+    let gas_used = 21_000 + 3 + 3;
+    assert_eq!(1, host.borrow().get_nonce(address_sender()));
+    assert_eq!(balance - U256::from(gas_used * 3), host.borrow().get_balance(address_sender()));
+    assert_eq!(U256::from(gas_used * 3), host.borrow().get_balance(address_coinbase()));
+}
+
+fn ether(n: u64) -> U256 {
+    U256::exp10(18) * U256::from(n)
+}
+
+#[test]
+fn test_withdrawals() {
+    let validator = Address::from_low_u64_be(0xbeef);
+    let idle = Address::from_low_u64_be(0x1d1e);
+    let withdrawals = [
+        Withdrawal { index: 0, validator_index: 1, address: validator, amount: 32_000_000_000 },
+        Withdrawal { index: 1, validator_index: 2, address: address_sender(), amount: 1 },
+        Withdrawal { index: 2, validator_index: 1, address: validator, amount: 5 },
+        Withdrawal { index: 3, validator_index: 3, address: idle, amount: 0 },
+    ];
+
+    let host = host_with(get_default_context(), log_code());
+    let mut block = BlockExecutor::new(host.clone(), Revision::Shanghai);
+    assert_eq!(Ok(()), block.apply_withdrawals(&withdrawals));
+
+    // no reward after the merge.
+    block.apply_block_reward(&[]);
+    let output = block.finish();
+    assert_eq!(0, output.gas_used);
+
+    let host = host.borrow();
+    assert_eq!(ether(32) + U256::from(5_000_000_000u64), host.get_balance(validator));
+    assert_eq!(U256::from(100_000_000 + 1_000_000_000), host.get_balance(address_sender()));
+    assert_eq!(U256::zero(), host.get_balance(address_coinbase()));
+    // zero credits do not create empty accounts.
+    assert!(!host.account_exists(idle));
+    assert!(!host.account_exists(address_coinbase()));
+}
+
+#[test]
+fn test_withdrawals_before_shanghai() {
+    let validator = Address::from_low_u64_be(0xbeef);
+    let withdrawals = [Withdrawal { index: 0, validator_index: 1, address: validator, amount: 1 }];
+
+    let host = host_with(get_default_context(), log_code());
+    let mut block = BlockExecutor::new(host.clone(), Revision::London);
+    assert_eq!(Err(BlockError::UnsupportedRevision), block.apply_withdrawals(&withdrawals));
+    assert_eq!(U256::zero(), host.borrow().get_balance(validator));
+}
+
+#[test]
+fn test_block_reward() {
+    let uncle = Address::from_low_u64_be(0xcafe);
+    let ommers = [Ommer { coinbase: uncle, block_number: 0x1111 - 1 }];
+
+    let host = host_with(get_default_context(), log_code());
+    let mut block = BlockExecutor::new(host.clone(), Revision::Byzantium);
+    block.apply_block_reward(&ommers);
+
+    // EIP-649: 3 ether in Byzantium.
+    assert_eq!(ether(3) + ether(3) / 32, host.borrow().get_balance(address_coinbase()));
+    assert_eq!(ether(3) * 7 / 8, host.borrow().get_balance(uncle));
+
+    let host = host_with(get_default_context(), log_code());
+    let mut block = BlockExecutor::new(host.clone(), Revision::Frontier);
+    block.apply_block_reward(&[]);
+    assert_eq!(ether(5), host.borrow().get_balance(address_coinbase()));
+
+    let host = host_with(get_default_context(), log_code());
+    let mut block = BlockExecutor::new(host.clone(), Revision::London);
+    block.set_block_reward(U256::from(1234));
+    block.apply_block_reward(&[]);
+    assert_eq!(U256::from(1234), host.borrow().get_balance(address_coinbase()));
+}
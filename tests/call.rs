@@ -42,6 +42,8 @@ fn get_default_context() -> TxContext {
         base_fee: U256::from(0x4444),
         chain_id: U256::from(0x01),
         difficulty: U256::from(0x5555),
+        excess_blob_gas: 0,
+        blob_hashes: vec![],
     }
 }
 fn get_code_for_call(gas: i64, address: &str, value: usize, args_offset: u8, args_size: u8, ret_offset: u8, ret_size: u8) -> Code {
@@ -346,7 +348,7 @@ fn test_transfer_to_existent() {
         .with_default_gas()
         .with_to(sender_address)
         .with_account(sender_address, sender_balance)
-        .with_contract_deployed2(receiver_address, Code::empty(), U256::zero());
+        .with_contract_deployed2(receiver_address, Code::from("00"), U256::zero());
     
     let result = emulator.run_code(code);
 
@@ -358,6 +360,41 @@ fn test_transfer_to_existent() {
         ;
 }
 
+#[test]
+fn test_transfer_to_precompile() {
+    let sender_address = address(0xff);
+    let sender_balance = U256::from(100);
+    let identity_address = address(0x04);
+    let value = U256::from(5);
+
+    let code = Code::builder()
+        .append("6000600060006000")     // ret_size, ret_offset, args_size, args_offset
+        .append(OpCode::PUSH32)
+        .append(value)
+        .append(OpCode::PUSH20)
+        .append(identity_address)
+        .append(OpCode::GAS)
+        .append(OpCode::CALL)
+        .append("60005260206000")       // PUSH1 0x00 MSTORE PUSH1 0x20 PUSH1 0x00
+        .append(OpCode::RETURN)
+        .clone();
+
+    let mut emulator = EvmEmulator::new_stateful_with(get_default_context());
+    emulator
+        .with_default_gas()
+        .with_to(sender_address)
+        .with_account(sender_address, sender_balance);
+
+    let result = emulator.run_code(code);
+
+    // the precompiled contract receives the value as any other account does.
+    result.expect_status(StatusCode::Success)
+        .expect_output("0000000000000000000000000000000000000000000000000000000000000001")
+        .expect_balance(sender_address, sender_balance - value)
+        .expect_balance(identity_address, value)
+        ;
+}
+
 #[test]
 fn test_transfer_to_non_existent() {
     let sender_address = address(0xff);
@@ -573,7 +610,7 @@ fn test_transfer_gas_by_revisions() {
                 .with_default_gas()
                 .with_to(sender_address)
                 .with_account(sender_address, sender_balance)
-                .with_contract_deployed2(receiver_address, Code::empty(), U256::zero());
+                .with_contract_deployed2(receiver_address, Code::from("00"), U256::zero());
             
             if !is_cold {
                 emulator.with_warm_account(receiver_address);
@@ -609,6 +646,57 @@ fn test_transfer_gas_by_revisions() {
     }
 }
 
+#[test]
+fn test_transfer_to_empty_account_by_revisions() {
+    let sender_address = address(0xff);
+    let sender_balance = U256::from_str("ffffffffffffffff").unwrap();
+    let receiver_address = address(0xdd);
+    let value = U256::one();
+
+    let code = Code::builder()
+        .append(OpCode::PUSH1)
+        .append(0x00)   // ret_size
+        .append(OpCode::PUSH1)
+        .append(0x00)   // ret_offset
+        .append(OpCode::PUSH1)
+        .append(0x00)   // args_size
+        .append(OpCode::PUSH1)
+        .append(0x00)   // args_offset
+        .append(OpCode::PUSH1)
+        .append(0x01)   // value
+        .append(OpCode::PUSH20)
+        .append(receiver_address)   // address
+        .append(OpCode::PUSH2)
+        .append("ffff")   // gas
+        .append(OpCode::CALL)
+        .clone();   // 3 * 7 + call[=static_cost+cold_cost+new_account_cost+9000-2300]
+
+    for revision in [Revision::Homestead, Revision::Tangerine, Revision::Spurious, Revision::Berlin] {
+        let mut emulator = EvmEmulator::new_stateful_with(get_default_context());
+
+        // receiver exists, but is empty.
+        let emulator = emulator
+            .with_default_gas()
+            .with_to(sender_address)
+            .with_account(sender_address, sender_balance)
+            .with_contract_deployed2(receiver_address, Code::empty(), U256::zero());
+
+        let result = emulator.run_code_as(code.clone(), revision);
+
+        let static_cost = match revision {
+            Revision::Homestead => 40,
+            Revision::Berlin => 2600,
+            _ => 700,
+        };
+        // https://eips.ethereum.org/EIPS/eip-161
+        let new_account_cost = if revision >= Revision::Spurious { 25000 } else { 0 };
+
+        result.expect_status(StatusCode::Success)
+            .expect_balance(receiver_address, value)
+            .expect_gas(21 + static_cost + new_account_cost + 9000 - 2300);
+    }
+}
+
 #[test]
 fn test_exceed_call_depth() {
     let code = Code::builder()
@@ -2,10 +2,11 @@ use std::rc::Rc;
 use std::cell::RefCell;
 
 use bytes::Bytes;
-use ethereum_types::{U256, Address};
+use ethereum_types::{U256, H256, Address};
 
 use evmstar::host::transient::TransientHost;
 use evmstar::executor::executor::Executor;
+use evmstar::emulator::EvmEmulator;
 #[allow(unused_imports)]
 use evmstar::model::{
     code::{
@@ -35,6 +36,8 @@ fn get_default_context() -> TxContext {
         base_fee: U256::from(0x4444),
         chain_id: U256::from(0x01),
         difficulty: U256::from(0x5555),
+        excess_blob_gas: 0,
+        blob_hashes: vec![],
     }
 }
 
@@ -42,7 +45,7 @@ fn get_default_context() -> TxContext {
 fn test_gas_price() {
     let host = TransientHost::new_with(get_default_context());
     let host = Rc::new(RefCell::new(host));
-    let mut executor = Executor::new_with_tracing(host.clone());
+    let mut executor = Executor::new(host.clone());
     let mut builder = Code::builder();
 
     let code = builder
@@ -68,7 +71,7 @@ fn test_gas_price() {
 fn test_coinbase() {
     let host = TransientHost::new_with(get_default_context());
     let host = Rc::new(RefCell::new(host));
-    let mut executor = Executor::new_with_tracing(host.clone());
+    let mut executor = Executor::new(host.clone());
     let mut builder = Code::builder();
 
     let code = builder
@@ -94,7 +97,7 @@ fn test_coinbase() {
 fn test_block_number() {
     let host = TransientHost::new_with(get_default_context());
     let host = Rc::new(RefCell::new(host));
-    let mut executor = Executor::new_with_tracing(host.clone());
+    let mut executor = Executor::new(host.clone());
     let mut builder = Code::builder();
 
     let code = builder
@@ -120,7 +123,7 @@ fn test_block_number() {
 fn test_block_timestamp() {
     let host = TransientHost::new_with(get_default_context());
     let host = Rc::new(RefCell::new(host));
-    let mut executor = Executor::new_with_tracing(host.clone());
+    let mut executor = Executor::new(host.clone());
     let mut builder = Code::builder();
 
     let code = builder
@@ -146,7 +149,7 @@ fn test_block_timestamp() {
 fn test_gas_limit() {
     let host = TransientHost::new_with(get_default_context());
     let host = Rc::new(RefCell::new(host));
-    let mut executor = Executor::new_with_tracing(host.clone());
+    let mut executor = Executor::new(host.clone());
     let mut builder = Code::builder();
 
     let code = builder
@@ -172,7 +175,7 @@ fn test_gas_limit() {
 fn test_base_fee() {
     let host = TransientHost::new_with(get_default_context());
     let host = Rc::new(RefCell::new(host));
-    let mut executor = Executor::new_with_tracing(host.clone());
+    let mut executor = Executor::new(host.clone());
     let mut builder = Code::builder();
 
     let code = builder
@@ -198,7 +201,7 @@ fn test_base_fee() {
 fn test_chain_id() {
     let host = TransientHost::new_with(get_default_context());
     let host = Rc::new(RefCell::new(host));
-    let mut executor = Executor::new_with_tracing(host.clone());
+    let mut executor = Executor::new(host.clone());
     let mut builder = Code::builder();
 
     let code = builder
@@ -224,7 +227,7 @@ fn test_chain_id() {
 fn test_difficulty() {
     let host = TransientHost::new_with(get_default_context());
     let host = Rc::new(RefCell::new(host));
-    let mut executor = Executor::new_with_tracing(host.clone());
+    let mut executor = Executor::new(host.clone());
     let mut builder = Code::builder();
 
     let code = builder
@@ -250,7 +253,7 @@ fn test_difficulty() {
 fn test_blockhash() {
     let host = TransientHost::new_with(get_default_context());
     let host = Rc::new(RefCell::new(host));
-    let mut executor = Executor::new_with_tracing(host.clone());
+    let mut executor = Executor::new(host.clone());
     let mut builder = Code::builder();
 
     let code = builder
@@ -272,4 +275,55 @@ fn test_blockhash() {
     assert_eq!(StatusCode::Success, output.status_code);
     assert_eq!(Bytes::from(data), output.data);
     assert_eq!(consumed_gas(38), output.gas_left);
-}
\ No newline at end of file
+}
+
+fn blockhash_code(height: &str) -> Code {
+    Code::builder()
+        .append(OpCode::PUSH2)
+        .append(height)
+        .append(OpCode::BLOCKHASH)
+        .append(OpCode::PUSH1)
+        .append("00")
+        .append(OpCode::MSTORE)
+        .append(OpCode::PUSH1)
+        .append("20")
+        .append(OpCode::PUSH1)
+        .append("00")
+        .append(OpCode::RETURN)
+        .clone()
+}
+
+#[test]
+fn test_blockhash_history() {
+    // current block is 0x1111.
+    let hash = "00000000000000000000000000000000000000000000000000000000000abcde";
+    let mut tester = EvmEmulator::new_stateful_with(get_default_context());
+    tester
+        .with_default_gas()
+        .with_block_hash(0x1110, H256::from_low_u64_be(0xabcde))
+        .with_block_hash(0x1011, H256::from_low_u64_be(0xabcde))
+        .with_block_hash(0x1010, H256::from_low_u64_be(0xabcde))
+        .with_block_hash(0x1111, H256::from_low_u64_be(0xabcde));
+
+    tester.run_code(blockhash_code("1110"))
+        .expect_status(StatusCode::Success)
+        .expect_output(hash)
+        .expect_gas(38);
+
+    // the oldest block in the window.
+    tester.run_code(blockhash_code("1011"))
+        .expect_output(hash);
+
+    // out of the window.
+    let zero = "0000000000000000000000000000000000000000000000000000000000000000";
+    tester.run_code(blockhash_code("1010"))
+        .expect_output(zero);
+
+    // the current block is not available.
+    tester.run_code(blockhash_code("1111"))
+        .expect_output(zero);
+
+    // unknown block within the window.
+    tester.run_code(blockhash_code("1100"))
+        .expect_output(zero);
+}
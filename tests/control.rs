@@ -26,7 +26,7 @@ fn consumed_gas(amount: i64) -> i64 {
 pub fn test_pc() {
     let host = TransientHost::new();
     let host = Rc::new(RefCell::new(host));
-    let mut executor = Executor::new_with_tracing(host.clone());
+    let mut executor = Executor::new(host.clone());
     let mut builder = Code::builder();
 
     let code = builder
@@ -60,7 +60,7 @@ pub fn test_pc() {
 pub fn test_jump() {
     let host = TransientHost::new();
     let host = Rc::new(RefCell::new(host));
-    let mut executor = Executor::new_with_tracing(host.clone());
+    let mut executor = Executor::new(host.clone());
     let mut builder = Code::builder();
 
     let code = builder
@@ -96,7 +96,7 @@ pub fn test_jump() {
 pub fn test_jump_bad() {
     let host = TransientHost::new();
     let host = Rc::new(RefCell::new(host));
-    let mut executor = Executor::new_with_tracing(host.clone());
+    let mut executor = Executor::new(host.clone());
     let mut builder = Code::builder();
 
     let code = builder
@@ -130,7 +130,7 @@ pub fn test_jump_bad() {
 pub fn test_jumpi() {
     let host = TransientHost::new();
     let host = Rc::new(RefCell::new(host));
-    let mut executor = Executor::new_with_tracing(host.clone());
+    let mut executor = Executor::new(host.clone());
     let mut builder = Code::builder();
 
     let code = builder
@@ -168,7 +168,7 @@ pub fn test_jumpi() {
 pub fn test_jumpi_condition_unmet() {
     let host = TransientHost::new();
     let host = Rc::new(RefCell::new(host));
-    let mut executor = Executor::new_with_tracing(host.clone());
+    let mut executor = Executor::new(host.clone());
     let mut builder = Code::builder();
 
     let code = builder
@@ -206,7 +206,7 @@ pub fn test_jumpi_condition_unmet() {
 pub fn test_jumpi_bad() {
     let host = TransientHost::new();
     let host = Rc::new(RefCell::new(host));
-    let mut executor = Executor::new_with_tracing(host.clone());
+    let mut executor = Executor::new(host.clone());
     let mut builder = Code::builder();
 
     let code = builder
@@ -0,0 +1,201 @@
+use ethereum_types::{U256, Address};
+
+use evmstar::emulator::EvmEmulator;
+use evmstar::interpreter::stack::Calldata;
+use evmstar::tracer::{coverage::Coverage, eip3155::Eip3155Tracer};
+use evmstar::utils::keccak256;
+#[allow(unused_imports)]
+use evmstar::model::{
+    code::{
+        Code, Append,
+    },
+    opcode::OpCode,
+    evmc::{
+        StatusCode, FailureKind,
+        TxContext,
+    },
+    revision::Revision,
+    srcmap::{Jump, SourceFile, SourceLocation, SourceMap, SourceMapError, SourceRange, Sources},
+};
+
+fn default_address() -> Address { Address::from_low_u64_be(0xffffeeee) }
+
+fn get_default_context() -> TxContext {
+    TxContext {
+        gas_price: U256::from(0x1234),
+        origin: Address::from_low_u64_be(0x1234),
+        coinbase: Address::from_low_u64_be(0xabcd),
+        block_number: 0x1111,
+        block_timestamp: 0x2222,
+        gas_limit: 100_000,
+        base_fee: U256::from(0x4444),
+        chain_id: U256::from(0x01),
+        difficulty: U256::from(0x5555),
+        excess_blob_gas: 0,
+        blob_hashes: vec![],
+    }
+}
+
+/// jump to the end if calldata is not empty.
+fn branch_code() -> Code {
+    Code::builder()
+        .append(OpCode::CALLDATASIZE)   // 0
+        .append("6005")                 // 1: PUSH1 0x05
+        .append(OpCode::JUMPI)          // 3
+        .append(OpCode::STOP)           // 4
+        .append(OpCode::JUMPDEST)       // 5
+        .append(OpCode::STOP)           // 6
+        .clone()
+}
+
+const SOURCE: &str = "contract A {\n  function f() {\n    if (x) {}\n    return;\n  }\n}\n";
+const SRCMAP: &str = "13:46:0:-;34:1;;44:7;56:1;::-1";
+
+fn run(coverage: &mut Coverage, calldata: Vec<u8>) {
+    let mut emu = EvmEmulator::new_stateful_with(get_default_context());
+    emu.with_to(default_address())
+        .with_code(branch_code())
+        .mutate_scope(|scope| scope.calldata = Calldata(calldata))
+        .with_default_gas()
+        .run_traced_as(Revision::Shanghai, coverage)
+        .expect_status(StatusCode::Success);
+}
+
+#[test]
+fn test_source_map() {
+    let source_map = SourceMap::new(SRCMAP, &branch_code()).unwrap();
+
+    assert_eq!(Some(&SourceRange { offset: 13, length: 46, file: Some(0), jump: Jump::Regular }), source_map.get(0));
+    assert_eq!(Some(&SourceRange { offset: 34, length: 1, file: Some(0), jump: Jump::Regular }), source_map.get(1));
+    // push data is not an instruction.
+    assert_eq!(None, source_map.get(2));
+    assert_eq!(Some(&SourceRange { offset: 34, length: 1, file: Some(0), jump: Jump::Regular }), source_map.get(3));
+    assert_eq!(Some(&SourceRange { offset: 56, length: 1, file: None, jump: Jump::Regular }), source_map.get(6));
+
+    assert_eq!(Err(SourceMapError::MissingField), SourceMap::new(";1:2", &branch_code()).map(|_| ()));
+    assert_eq!(Err(SourceMapError::InvalidEntry(1)), SourceMap::new("1:2;x", &branch_code()).map(|_| ()));
+
+    let file = SourceFile::new("A.sol", SOURCE);
+    assert_eq!((1, 1), file.line_column(0));
+    assert_eq!((3, 5), file.line_column(34));
+}
+
+#[test]
+fn test_coverage_accumulates_runs() {
+    let code_hash = keccak256(&branch_code().0);
+    let mut coverage = Coverage::new();
+
+    run(&mut coverage, vec![]);
+    let code = coverage.get(&code_hash).unwrap();
+    assert_eq!(vec![5, 6], code.uncovered_pcs());
+    assert_eq!(Some(&(0, 1)), code.branches.get(&3));
+
+    run(&mut coverage, vec![1]);
+    run(&mut coverage, vec![1]);
+    let code = coverage.get(&code_hash).unwrap();
+    assert!(code.uncovered_pcs().is_empty());
+    assert_eq!(Some(&3), code.hits.get(&0));
+    assert_eq!(Some(&1), code.hits.get(&4));
+    assert_eq!(Some(&(2, 1)), code.branches.get(&3));
+}
+
+#[test]
+fn test_coverage_lcov() {
+    let code_hash = keccak256(&branch_code().0);
+    let source_map = SourceMap::new(SRCMAP, &branch_code()).unwrap();
+    let sources = vec![SourceFile::new("A.sol", SOURCE)];
+
+    let mut coverage = Coverage::new();
+    run(&mut coverage, vec![]);
+
+    assert_eq!(
+        "SF:A.sol\n\
+        DA:2,1\nDA:3,1\nDA:4,1\nDA:5,0\n\
+        LF:4\nLH:3\n\
+        BRDA:3,3,0,0\nBRDA:3,3,1,1\n\
+        BRF:2\nBRH:1\n\
+        end_of_record\n",
+        coverage.lcov(&code_hash, &source_map, &sources)
+    );
+}
+
+/// revert with empty data.
+fn revert_code() -> Code {
+    Code::builder()
+        .append("6000")                 // 0: PUSH1 0x00
+        .append("6000")                 // 2: PUSH1 0x00
+        .append(OpCode::REVERT)         // 4
+        .clone()
+}
+
+const REVERT_SRCMAP: &str = "13:46:0:-;34:1;48:6";
+
+fn revert_sources() -> Sources {
+    let mut sources = Sources::new(vec![SourceFile::new("A.sol", SOURCE)]);
+    sources.add(&revert_code(), REVERT_SRCMAP).unwrap();
+    sources
+}
+
+#[test]
+fn test_revert_source_location() {
+    let location = SourceLocation { file: "A.sol".to_string(), line: 4, column: 5 };
+    assert_eq!("A.sol:4:5", location.to_string());
+
+    let mut emu = EvmEmulator::new_stateful_with(get_default_context());
+    let result = emu.with_to(default_address())
+        .with_code(revert_code())
+        .with_sources(revert_sources())
+        .with_default_gas()
+        .run();
+    result.expect_status(StatusCode::Failure(FailureKind::Revert));
+    assert_eq!(Some((keccak256(&revert_code().0), 4)), result.failed_at);
+    assert_eq!(Some(location), result.source_location);
+
+    // successful runs have no failure location.
+    let mut emu = EvmEmulator::new_stateful_with(get_default_context());
+    let result = emu.with_to(default_address())
+        .with_code(branch_code())
+        .with_sources(revert_sources())
+        .with_default_gas()
+        .run();
+    assert_eq!(None, result.failed_at);
+    assert_eq!(None, result.source_location);
+}
+
+#[test]
+fn test_trace_source_location() {
+    let mut tracer = Eip3155Tracer::new(Vec::new()).with_sources(revert_sources());
+    let mut emu = EvmEmulator::new_stateful_with(get_default_context());
+    emu.with_to(default_address())
+        .with_code(revert_code())
+        .with_default_gas()
+        .run_traced_as(Revision::Shanghai, &mut tracer);
+
+    let lines = String::from_utf8(tracer.into_inner().unwrap()).unwrap();
+    let lines: Vec<&str> = lines.lines().collect();
+    assert_eq!(3, lines.len());
+    assert!(lines[0].contains(r#""source":"A.sol:2:1""#));
+    assert!(lines[2].contains(r#""source":"A.sol:4:5""#));
+}
+
+#[test]
+fn test_coverage_lcov_sources() {
+    let mut coverage = Coverage::new();
+    run(&mut coverage, vec![]);
+
+    let mut emu = EvmEmulator::new_stateful_with(get_default_context());
+    emu.with_to(default_address())
+        .with_code(revert_code())
+        .with_default_gas()
+        .run_traced_as(Revision::Shanghai, &mut coverage);
+
+    // the branch code has no source map.
+    assert_eq!(
+        "SF:A.sol\n\
+        DA:2,1\nDA:3,1\nDA:4,1\n\
+        LF:3\nLH:3\n\
+        BRF:0\nBRH:0\n\
+        end_of_record\n",
+        coverage.lcov_sources(&revert_sources())
+    );
+}
@@ -0,0 +1,219 @@
+use ethereum_types::{U256, Address};
+
+use evmstar::emulator::EvmEmulator;
+use evmstar::interpreter::CallKind;
+use evmstar::tracer::debugger::{Breakpoint, Command, Debugger, PauseReason};
+#[allow(unused_imports)]
+use evmstar::model::{
+    code::{
+        Code, Append,
+    },
+    opcode::OpCode,
+    evmc::{
+        StatusCode, FailureKind,
+        TxContext,
+    },
+    revision::Revision,
+};
+
+fn default_address() -> Address { Address::from_low_u64_be(0xffffeeee) }
+
+fn get_default_context() -> TxContext {
+    TxContext {
+        gas_price: U256::from(0x1234),
+        origin: Address::from_low_u64_be(0x1234),
+        coinbase: Address::from_low_u64_be(0xabcd),
+        block_number: 0x1111,
+        block_timestamp: 0x2222,
+        gas_limit: 100_000,
+        base_fee: U256::from(0x4444),
+        chain_id: U256::from(0x01),
+        difficulty: U256::from(0x5555),
+        excess_blob_gas: 0,
+        blob_hashes: vec![],
+    }
+}
+
+#[test]
+fn test_debugger_step_and_breakpoints() {
+    let code = Code::builder()
+        .append("6001")                 // 0: PUSH1 0x01
+        .append("6002")                 // 2: PUSH1 0x02
+        .append(OpCode::ADD)            // 4
+        .append("6000")                 // 5: PUSH1 0x00
+        .append(OpCode::MSTORE)         // 7
+        .append(OpCode::STOP)           // 8
+        .clone();
+
+    let mut pauses = Vec::new();
+    let mut stacks = Vec::new();
+    let mut debugger = Debugger::new(|paused, breakpoints| {
+        pauses.push((paused.reason, paused.scope.pc));
+        stacks.push(paused.scope.stack.0.to_vec());
+        match paused.scope.pc {
+            0 => Command::Step,
+            2 => {
+                breakpoints.push(Breakpoint::Opcode(OpCode::ADD));
+                breakpoints.push(Breakpoint::Pc(8));
+                Command::Continue
+            },
+            _ => Command::Continue,
+        }
+    });
+
+    let mut emu = EvmEmulator::new_stateful_with(get_default_context());
+    emu.with_to(default_address())
+        .with_code(code)
+        .with_default_gas()
+        .run_traced_as(Revision::Shanghai, &mut debugger)
+        .expect_status(StatusCode::Success);
+
+    assert_eq!(
+        vec![
+            (PauseReason::Step, 0),
+            (PauseReason::Step, 2),
+            (PauseReason::Breakpoint(0), 4),
+            (PauseReason::Breakpoint(1), 8),
+        ],
+        pauses
+    );
+    assert_eq!(vec![U256::from(1), U256::from(2)], stacks[2]);
+}
+
+#[test]
+fn test_debugger_watchpoints() {
+    let code = Code::builder()
+        .append("602a6000")             // 0: PUSH1 0x2a PUSH1 0x00
+        .append(OpCode::SSTORE)         // 4
+        .append("602a6000")             // 5: PUSH1 0x2a PUSH1 0x00
+        .append(OpCode::SSTORE)         // 9, writes the same value
+        .append("60016020")             // 10: PUSH1 0x01 PUSH1 0x20
+        .append(OpCode::MSTORE)         // 14
+        .append("60016040")             // 15: PUSH1 0x01 PUSH1 0x40
+        .append(OpCode::MSTORE)         // 19, outside of the watched range
+        .append(OpCode::STOP)           // 20
+        .clone();
+
+    let mut pauses = Vec::new();
+    let mut debugger = Debugger::new(|paused, _| {
+        pauses.push((paused.reason, paused.scope.pc));
+        Command::Continue
+    }).with_breakpoints(vec![
+        Breakpoint::Storage(default_address(), U256::zero()),
+        Breakpoint::Memory(0x20, 0x20),
+    ]);
+
+    let mut emu = EvmEmulator::new_stateful_with(get_default_context());
+    emu.with_to(default_address())
+        .with_code(code)
+        .with_default_gas()
+        .run_traced_as(Revision::Shanghai, &mut debugger)
+        .expect_status(StatusCode::Success);
+
+    assert_eq!(
+        vec![
+            (PauseReason::Breakpoint(0), 5),
+            (PauseReason::Breakpoint(1), 15),
+        ],
+        pauses
+    );
+}
+
+#[test]
+fn test_debugger_frames() {
+    let callee = Address::from_low_u64_be(0xbbbb);
+    let callee_code = Code::builder()
+        .append("60016000")     // PUSH1 0x01 PUSH1 0x00
+        .append(OpCode::MSTORE8)
+        .append("60016000")     // PUSH1 0x01 PUSH1 0x00
+        .append(OpCode::REVERT)
+        .clone();
+
+    let code = Code::builder()
+        .append("6000600060006000600061bbbb61ffff") // ret, args, value, address, gas
+        .append(OpCode::CALL)   // 16
+        .clone();
+
+    let mut frames = Vec::new();
+    let mut memory = Vec::new();
+    let mut debugger = Debugger::new(|paused, _| {
+        frames = paused.frames.to_vec();
+        memory = paused.scope.memory.0.clone();
+        Command::Continue
+    }).with_breakpoints(vec![Breakpoint::Opcode(OpCode::REVERT)]);
+
+    let mut emu = EvmEmulator::new_stateful_with(get_default_context());
+    emu.with_to(default_address())
+        .mutate_scope(|scope| scope.code_address = default_address())
+        .with_contract_deployed2(callee, callee_code, U256::zero())
+        .with_code(code)
+        .with_default_gas()
+        .run_traced_as(Revision::Shanghai, &mut debugger)
+        .expect_status(StatusCode::Success);
+
+    assert_eq!(2, frames.len());
+    assert_eq!((CallKind::Call, default_address(), 16), (frames[0].kind, frames[0].code_address, frames[0].pc));
+    assert_eq!((CallKind::Call, callee, default_address(), 9), (frames[1].kind, frames[1].code_address, frames[1].caller, frames[1].pc));
+    assert_eq!(1, memory[0]);
+}
+
+#[test]
+fn test_debugger_watchpoint_beyond_memory() {
+    let code = Code::builder()
+        .append("60016000")             // 0: PUSH1 0x01 PUSH1 0x00
+        .append(OpCode::MSTORE)         // 4
+        .append(OpCode::STOP)           // 5
+        .clone();
+
+    // only the part of the range in memory is compared.
+    let mut pauses = Vec::new();
+    let mut debugger = Debugger::new(|paused, _| {
+        pauses.push((paused.reason, paused.scope.pc));
+        Command::Continue
+    }).with_breakpoints(vec![Breakpoint::Memory(0, usize::MAX)]);
+
+    let mut emu = EvmEmulator::new_stateful_with(get_default_context());
+    emu.with_to(default_address())
+        .with_code(code)
+        .with_default_gas()
+        .run_traced_as(Revision::Shanghai, &mut debugger)
+        .expect_status(StatusCode::Success);
+
+    assert_eq!(vec![(PauseReason::Breakpoint(0), 5)], pauses);
+}
+
+#[test]
+fn test_debugger_watchpoint_return_data() {
+    let callee = Address::from_low_u64_be(0xbbbb);
+    let callee_code = Code::builder()
+        .append("60016040")     // PUSH1 0x01 PUSH1 0x40
+        .append(OpCode::MSTORE)
+        .append("60206040")     // PUSH1 0x20 PUSH1 0x40
+        .append(OpCode::RETURN)
+        .clone();
+
+    // the return data is copied to the watched range after the CALL itself.
+    let code = Code::builder()
+        .append("60206000600060006000")     // 0: ret_size, ret_offset, args_size, args_offset, value
+        .append("61bbbb61ffff")             // 10: address, gas
+        .append(OpCode::CALL)               // 16
+        .append(OpCode::STOP)               // 17
+        .clone();
+
+    let mut pauses = Vec::new();
+    let mut debugger = Debugger::new(|paused, _| {
+        pauses.push((paused.reason, paused.scope.depth, paused.scope.pc));
+        Command::Continue
+    }).with_breakpoints(vec![Breakpoint::Memory(0, 0x20)]);
+
+    let mut emu = EvmEmulator::new_stateful_with(get_default_context());
+    emu.with_to(default_address())
+        .mutate_scope(|scope| scope.code_address = default_address())
+        .with_contract_deployed2(callee, callee_code, U256::zero())
+        .with_code(code)
+        .with_default_gas()
+        .run_traced_as(Revision::Shanghai, &mut debugger)
+        .expect_status(StatusCode::Success);
+
+    assert_eq!(vec![(PauseReason::Breakpoint(0), 0, 17)], pauses);
+}
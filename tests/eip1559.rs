@@ -0,0 +1,209 @@
+use ethereum_types::{U256, Address};
+use evmstar::model::{
+    code::{
+        Code, Append,
+    },
+    opcode::OpCode,
+    evmc::{
+        StatusCode, FailureKind,
+        TxContext,
+    },
+    revision::Revision,
+};
+use evmstar::emulator::EvmEmulator;
+
+fn address_sender() -> Address {
+    Address::from_low_u64_be(0x1234)
+}
+fn address_contract() -> Address {
+    Address::from_low_u64_be(0xaaaaaaaa)
+}
+fn address_coinbase() -> Address {
+    Address::from_low_u64_be(0xabcd)
+}
+
+fn get_default_context() -> TxContext {
+    TxContext {
+        gas_price: U256::from(0x1234),
+        origin: address_sender(),
+        coinbase: address_coinbase(),
+        block_number: 0x1111,
+        block_timestamp: 0x2222,
+        gas_limit: 0x3333,
+        base_fee: U256::from(10),
+        chain_id: U256::from(0x01),
+        difficulty: U256::from(0x5555),
+        excess_blob_gas: 0,
+        blob_hashes: vec![],
+    }
+}
+
+fn gas_price_code() -> Code {
+    Code::builder()
+        .append(OpCode::GASPRICE)
+        .append(OpCode::PUSH1)
+        .append("00")
+        .append(OpCode::MSTORE)
+        .append(OpCode::PUSH1)
+        .append("20")
+        .append(OpCode::PUSH1)
+        .append("00")
+        .append(OpCode::RETURN)
+        .clone()
+}
+
+#[test]
+fn test_eip1559_effective_gas_price() {
+    let mut tester = EvmEmulator::new_stateful_with(get_default_context());
+    let result = tester
+        .with_account(address_sender(), U256::from(10_000_000))
+        .mutate_scope(|scope| scope.caller = address_sender())
+        .with_to(address_contract())
+        .with_gas_limit(100_000)
+        .with_gas_left(100_000)
+        .with_dynamic_fee(U256::from(100), U256::from(2))
+        .run_code(gas_price_code());
+
+    // effective gas price = base fee + priority fee = 10 + 2
+    result.expect_status(StatusCode::Success)
+        .expect_output("000000000000000000000000000000000000000000000000000000000000000c")
+        .expect_gas(17)
+        .expect_balance(address_sender(), U256::from(10_000_000 - 17 * 12))
+        .expect_balance(address_coinbase(), U256::from(17 * 2))
+        .expect_burnt(U256::from(17 * 10));
+}
+
+#[test]
+fn test_eip1559_priority_fee_capped_by_max_fee() {
+    let mut tester = EvmEmulator::new_stateful_with(get_default_context());
+    let result = tester
+        .with_account(address_sender(), U256::from(10_000_000))
+        .mutate_scope(|scope| scope.caller = address_sender())
+        .with_to(address_contract())
+        .with_gas_limit(100_000)
+        .with_gas_left(100_000)
+        .with_dynamic_fee(U256::from(11), U256::from(5))
+        .run_code(gas_price_code());
+
+    // only 1 wei per gas is left above the base fee.
+    result.expect_status(StatusCode::Success)
+        .expect_output("000000000000000000000000000000000000000000000000000000000000000b")
+        .expect_balance(address_sender(), U256::from(10_000_000 - 17 * 11))
+        .expect_balance(address_coinbase(), U256::from(17))
+        .expect_burnt(U256::from(17 * 10));
+}
+
+#[test]
+fn test_eip1559_with_execution_cost() {
+    let mut tester = EvmEmulator::new_stateful_with(get_default_context());
+    let result = tester
+        .with_account(address_sender(), U256::from(10_000_000))
+        .mutate_scope(|scope| scope.caller = address_sender())
+        .with_to(address_contract())
+        .with_gas_limit(100_000)
+        .with_gas_left(100_000)
+        .with_dynamic_fee(U256::from(100), U256::from(2))
+        .enable_execution_cost()
+        .run_code_as(gas_price_code(), Revision::London);
+
+    let gas_used = 21000 + 17;
+    result.expect_status(StatusCode::Success)
+        .expect_gas(gas_used)
+        .expect_balance(address_sender(), U256::from(10_000_000 - gas_used * 12))
+        .expect_balance(address_coinbase(), U256::from(gas_used * 2))
+        .expect_burnt(U256::from(gas_used * 10));
+}
+
+#[test]
+fn test_eip1559_fee_is_charged_on_revert() {
+    let code = Code::builder()
+        .append(OpCode::PUSH1)
+        .append("00")
+        .append(OpCode::PUSH1)
+        .append("00")
+        .append(OpCode::REVERT)
+        .clone();
+
+    let mut tester = EvmEmulator::new_stateful_with(get_default_context());
+    let result = tester
+        .with_account(address_sender(), U256::from(10_000_000))
+        .mutate_scope(|scope| scope.caller = address_sender())
+        .with_to(address_contract())
+        .with_gas_limit(100_000)
+        .with_gas_left(100_000)
+        .with_dynamic_fee(U256::from(100), U256::from(2))
+        .run_code(code);
+
+    result.expect_status(StatusCode::Failure(FailureKind::Revert))
+        .expect_gas(6)
+        .expect_balance(address_sender(), U256::from(10_000_000 - 6 * 12))
+        .expect_balance(address_coinbase(), U256::from(6 * 2))
+        .expect_burnt(U256::from(6 * 10));
+}
+
+#[test]
+fn test_eip1559_max_fee_below_base_fee() {
+    let mut tester = EvmEmulator::new_stateful_with(get_default_context());
+    let result = tester
+        .with_account(address_sender(), U256::from(10_000_000))
+        .mutate_scope(|scope| scope.caller = address_sender())
+        .with_to(address_contract())
+        .with_gas_limit(100_000)
+        .with_gas_left(100_000)
+        .with_dynamic_fee(U256::from(9), U256::from(0))
+        .run_code(gas_price_code());
+
+    result.expect_status(StatusCode::Failure(FailureKind::Rejected))
+        .expect_balance(address_sender(), U256::from(10_000_000))
+        .expect_balance(address_coinbase(), U256::zero())
+        .expect_burnt(U256::zero());
+}
+
+#[test]
+fn test_eip1559_priority_fee_above_max_fee() {
+    let mut tester = EvmEmulator::new_stateful_with(get_default_context());
+    let result = tester
+        .with_account(address_sender(), U256::from(10_000_000))
+        .mutate_scope(|scope| scope.caller = address_sender())
+        .with_to(address_contract())
+        .with_gas_limit(100_000)
+        .with_gas_left(100_000)
+        .with_dynamic_fee(U256::from(20), U256::from(21))
+        .run_code(gas_price_code());
+
+    result.expect_status(StatusCode::Failure(FailureKind::Rejected))
+        .expect_balance(address_sender(), U256::from(10_000_000));
+}
+
+#[test]
+fn test_eip1559_insufficient_balance() {
+    let mut tester = EvmEmulator::new_stateful_with(get_default_context());
+    let result = tester
+        // balance must cover gas limit * max fee, not only the effective gas price.
+        .with_account(address_sender(), U256::from(100_000 * 100 - 1))
+        .mutate_scope(|scope| scope.caller = address_sender())
+        .with_to(address_contract())
+        .with_gas_limit(100_000)
+        .with_gas_left(100_000)
+        .with_dynamic_fee(U256::from(100), U256::from(2))
+        .run_code(gas_price_code());
+
+    result.expect_status(StatusCode::Failure(FailureKind::InsufficientBalance))
+        .expect_balance(address_sender(), U256::from(100_000 * 100 - 1));
+}
+
+#[test]
+fn test_eip1559_before_london() {
+    let mut tester = EvmEmulator::new_stateful_with(get_default_context());
+    let result = tester
+        .with_account(address_sender(), U256::from(10_000_000))
+        .mutate_scope(|scope| scope.caller = address_sender())
+        .with_to(address_contract())
+        .with_gas_limit(100_000)
+        .with_gas_left(100_000)
+        .with_dynamic_fee(U256::from(100), U256::from(2))
+        .run_code_as(gas_price_code(), Revision::Berlin);
+
+    result.expect_status(StatusCode::Failure(FailureKind::Rejected))
+        .expect_balance(address_sender(), U256::from(10_000_000));
+}
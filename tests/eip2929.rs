@@ -1,5 +1,7 @@
+use bytes::Bytes;
 use ethereum_types::{U256, Address};
 use evmstar::emulator::EvmEmulator;
+use evmstar::host::{Host, stateful::StatefulHost};
 
 use evmstar::model::{
     code::{
@@ -7,9 +9,10 @@ use evmstar::model::{
     },
     evmc::{
         StatusCode,
-        TxContext,
+        TxContext, AccessStatus,
     },
     revision::Revision,
+    transaction::{LegacyTransaction, Signature, Transaction},
 };
 
 fn default_address() -> Address { Address::from_low_u64_be(0xffffeeee) }
@@ -25,6 +28,8 @@ fn get_default_context() -> TxContext {
         base_fee: U256::from(0x4444),
         chain_id: U256::from(0x01),
         difficulty: U256::from(0x5555),
+        excess_blob_gas: 0,
+        blob_hashes: vec![],
     }
 }
 
@@ -219,4 +224,83 @@ fn test_eip2929_17(){
     let original = 0x01;
     sstore_eip2929(code, gas_used, gas_refund, true, original);
     sstore_eip2929(code, gas_used + 2100, gas_refund, false, original);
-}
\ No newline at end of file
+}
+fn balance_eip2929(address: Address, revision: Revision, gas_used: i64) {
+    let code = Code::builder()
+        .append("73")
+        .append(hex::encode(address.as_bytes()).as_str())
+        .append("31")
+        .clone();
+    let gas_limit = i64::max_value();
+
+    let mut tester = EvmEmulator::new_stateful_with(get_default_context());
+    tester.with_to(default_address())
+        .with_gas_limit(gas_limit)
+        .with_gas_left(gas_limit);
+
+    let result = tester.run_code_as(code, revision);
+    result.expect_status(StatusCode::Success)
+        .expect_gas(gas_used);
+}
+
+#[test]
+fn test_eip2929_precompiles_are_warm() {
+    balance_eip2929(Address::from_low_u64_be(0x01), Revision::Berlin, 3 + 100);
+    balance_eip2929(Address::from_low_u64_be(0x09), Revision::Berlin, 3 + 100);
+    // point evaluation precompile from Cancun.
+    balance_eip2929(Address::from_low_u64_be(0x0a), Revision::London, 3 + 2600);
+    balance_eip2929(Address::from_low_u64_be(0x0a), Revision::Cancun, 3 + 100);
+    balance_eip2929(Address::from_low_u64_be(0x0b), Revision::Cancun, 3 + 2600);
+}
+
+/// defined in https://eips.ethereum.org/EIPS/eip-3651
+#[test]
+fn test_eip3651_coinbase_is_warm() {
+    let coinbase = get_default_context().coinbase;
+    balance_eip2929(coinbase, Revision::London, 3 + 2600);
+    balance_eip2929(coinbase, Revision::Shanghai, 3 + 100);
+}
+
+#[test]
+fn test_eip2929_accessed_addresses_are_not_bounded() {
+    let mut host = StatefulHost::new();
+    for i in 0..300 {
+        assert_eq!(AccessStatus::Cold, host.access_account(Address::from_low_u64_be(0x1000 + i)));
+    }
+    for i in 0..300 {
+        assert_eq!(AccessStatus::Warm, host.access_account(Address::from_low_u64_be(0x1000 + i)));
+    }
+
+    // reading state does not warm the address.
+    host.get_balance(default_address());
+    assert_eq!(AccessStatus::Cold, host.access_account(default_address()));
+
+    host.commit();
+    assert_eq!(AccessStatus::Cold, host.access_account(Address::from_low_u64_be(0x1000)));
+}
+
+#[test]
+fn test_eip2929_accessed_sets_are_per_transaction() {
+    // PUSH2 0xbeef BALANCE POP PUSH1 0x00 SLOAD POP
+    let code = Code::builder().append("61beef315060005450").clone();
+    let sender = Address::from_low_u64_be(0x1234);
+    let transaction = |nonce| Transaction::Legacy(LegacyTransaction {
+        nonce,
+        gas_price: U256::zero(),
+        gas_limit: 100_000,
+        to: Some(default_address()),
+        value: U256::zero(),
+        data: Bytes::default(),
+        signature: Signature::default(),
+    });
+
+    let mut tester = EvmEmulator::new_stateful_with(get_default_context());
+    tester.with_contract_deployed2(default_address(), code, U256::zero());
+
+    // the address and the slot are cold again in the second transaction.
+    for nonce in 0..2 {
+        tester.run_transaction_as(&transaction(nonce), sender, Revision::Berlin)
+            .expect_status(StatusCode::Success)
+            .expect_gas(3 + 2600 + 2 + 3 + 2100 + 2);
+    }
+}
@@ -39,6 +39,8 @@ fn get_default_context() -> TxContext {
         base_fee: U256::from(0x4444),
         chain_id: U256::from(0x01),
         difficulty: U256::from(0x5555),
+        excess_blob_gas: 0,
+        blob_hashes: vec![],
     }
 }
 
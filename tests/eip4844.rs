@@ -0,0 +1,283 @@
+use std::rc::Rc;
+use std::cell::RefCell;
+
+use bytes::Bytes;
+use ethereum_types::{U256, H256, Address};
+use evmstar::model::{
+    code::{
+        Code, Append,
+    },
+    opcode::OpCode,
+    evmc::{
+        StatusCode, FailureKind,
+        TxContext,
+    },
+    fee::{BLOB_BASE_FEE_UPDATE_FRACTION, GAS_PER_BLOB},
+    revision::Revision,
+    transaction::*,
+};
+use evmstar::emulator::EvmEmulator;
+use evmstar::executor::executor::Executor;
+use evmstar::host::{Host, stateful::StatefulHost};
+
+fn address_sender() -> Address {
+    Address::from_low_u64_be(0x1234)
+}
+fn address_contract() -> Address {
+    Address::from_low_u64_be(0xaaaaaaaa)
+}
+fn address_coinbase() -> Address {
+    Address::from_low_u64_be(0xabcd)
+}
+
+fn blob_hash(n: u8) -> H256 {
+    let mut hash = H256::repeat_byte(n);
+    hash.0[0] = 0x01;
+    hash
+}
+
+fn get_default_context() -> TxContext {
+    TxContext {
+        gas_price: U256::zero(),
+        origin: address_sender(),
+        coinbase: address_coinbase(),
+        block_number: 0x1111,
+        block_timestamp: 0x2222,
+        gas_limit: 0x3333,
+        base_fee: U256::from(10),
+        chain_id: U256::from(0x01),
+        difficulty: U256::from(0x5555),
+        // blob base fee is 2.
+        excess_blob_gas: BLOB_BASE_FEE_UPDATE_FRACTION,
+        blob_hashes: vec![blob_hash(0xaa), blob_hash(0xbb)],
+    }
+}
+
+fn blob_hash_code(index: &str) -> Code {
+    Code::builder()
+        .append(OpCode::PUSH1)
+        .append(index)
+        .append(OpCode::BLOBHASH)
+        .append(OpCode::PUSH1)
+        .append("00")
+        .append(OpCode::MSTORE)
+        .append(OpCode::PUSH1)
+        .append("20")
+        .append(OpCode::PUSH1)
+        .append("00")
+        .append(OpCode::RETURN)
+        .clone()
+}
+
+fn blob_transaction() -> Transaction {
+    Transaction::Blob(BlobTransaction {
+        chain_id: U256::one(),
+        nonce: 0,
+        max_priority_fee_per_gas: U256::from(2),
+        max_fee_per_gas: U256::from(100),
+        gas_limit: 100_000,
+        to: address_contract(),
+        value: U256::from(1000),
+        data: Bytes::default(),
+        access_list: vec![],
+        max_fee_per_blob_gas: U256::from(3),
+        blob_versioned_hashes: vec![blob_hash(0x11), blob_hash(0x22)],
+        signature: Signature::default(),
+    })
+}
+
+#[test]
+fn test_blobhash() {
+    let mut tester = EvmEmulator::new_stateful_with(get_default_context());
+    let result = tester
+        .with_to(address_contract())
+        .with_default_gas()
+        .run_code_as(blob_hash_code("01"), Revision::Cancun);
+
+    result.expect_status(StatusCode::Success)
+        .expect_output("01bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb")
+        .expect_gas(21);
+}
+
+#[test]
+fn test_blobhash_out_of_range() {
+    let mut tester = EvmEmulator::new_stateful_with(get_default_context());
+    let result = tester
+        .with_to(address_contract())
+        .with_default_gas()
+        .run_code_as(blob_hash_code("02"), Revision::Cancun);
+
+    result.expect_status(StatusCode::Success)
+        .expect_output("0000000000000000000000000000000000000000000000000000000000000000")
+        .expect_gas(21);
+}
+
+#[test]
+fn test_blobhash_before_cancun() {
+    let mut tester = EvmEmulator::new_stateful_with(get_default_context());
+    let result = tester
+        .with_to(address_contract())
+        .with_default_gas()
+        .run_code_as(blob_hash_code("00"), Revision::Shanghai);
+
+    result.expect_status(StatusCode::Failure(FailureKind::InvalidInstruction));
+}
+
+#[test]
+fn test_blobbasefee() {
+    let code = Code::builder()
+        .append(OpCode::BLOBBASEFEE)
+        .append(OpCode::PUSH1)
+        .append("00")
+        .append(OpCode::MSTORE)
+        .append(OpCode::PUSH1)
+        .append("20")
+        .append(OpCode::PUSH1)
+        .append("00")
+        .append(OpCode::RETURN)
+        .clone();
+
+    let mut context = get_default_context();
+    context.excess_blob_gas = 10 * BLOB_BASE_FEE_UPDATE_FRACTION;
+
+    let mut tester = EvmEmulator::new_stateful_with(context);
+    let result = tester
+        .with_to(address_contract())
+        .with_default_gas()
+        .run_code_as(code, Revision::Cancun);
+
+    result.expect_status(StatusCode::Success)
+        .expect_output("000000000000000000000000000000000000000000000000000000000000560a")
+        .expect_gas(17);
+}
+
+#[test]
+fn test_blob_transaction() {
+    let mut tester = EvmEmulator::new_stateful_with(get_default_context());
+    let result = tester
+        .with_account(address_sender(), U256::from(100_000_000))
+        .with_contract_deployed2(address_contract(), blob_hash_code("01"), U256::zero())
+        .run_transaction_as(&blob_transaction(), address_sender(), Revision::Cancun);
+
+    // blob gas is bought at the blob base fee, not at max_fee_per_blob_gas.
+    let blob_cost = 2 * GAS_PER_BLOB * 2;
+    result.expect_status(StatusCode::Success)
+        .expect_output("0122222222222222222222222222222222222222222222222222222222222222")
+        .expect_gas(21)
+        .expect_balance(address_sender(), U256::from(100_000_000 - 1000 - 21 * 12 - blob_cost))
+        .expect_balance(address_contract(), U256::from(1000))
+        .expect_balance(address_coinbase(), U256::from(21 * 2))
+        .expect_burnt(U256::from(21 * 10 + blob_cost));
+}
+
+#[test]
+fn test_blob_transaction_reverted() {
+    let code = Code::builder()
+        .append(OpCode::PUSH1)
+        .append("00")
+        .append(OpCode::DUP1)
+        .append(OpCode::REVERT)
+        .clone();
+
+    let mut tester = EvmEmulator::new_stateful_with(get_default_context());
+    let result = tester
+        .with_account(address_sender(), U256::from(100_000_000))
+        .with_contract_deployed2(address_contract(), code, U256::zero())
+        .run_transaction_as(&blob_transaction(), address_sender(), Revision::Cancun);
+
+    // blob gas is charged even if the execution reverts.
+    let blob_cost = 2 * GAS_PER_BLOB * 2;
+    result.expect_status(StatusCode::Failure(FailureKind::Revert))
+        .expect_balance(address_sender(), U256::from(100_000_000 - 6 * 12 - blob_cost))
+        .expect_balance(address_contract(), U256::zero())
+        .expect_burnt(U256::from(6 * 10 + blob_cost));
+}
+
+#[test]
+fn test_blob_transaction_max_fee_per_blob_gas_too_low() {
+    let mut tx = blob_transaction();
+    if let Transaction::Blob(blob) = &mut tx {
+        blob.max_fee_per_blob_gas = U256::one();
+    }
+
+    let mut tester = EvmEmulator::new_stateful_with(get_default_context());
+    let result = tester
+        .with_account(address_sender(), U256::from(100_000_000))
+        .run_transaction_as(&tx, address_sender(), Revision::Cancun);
+
+    result.expect_status(StatusCode::Failure(FailureKind::Rejected))
+        .expect_balance(address_sender(), U256::from(100_000_000))
+        .expect_burnt(U256::zero());
+}
+
+#[test]
+fn test_blob_transaction_insufficient_balance() {
+    // enough for the gas and the value but not for blob gas at max_fee_per_blob_gas.
+    let balance = 100_000 * 100 + 1000 + 2 * GAS_PER_BLOB * 3 - 1;
+
+    let mut tester = EvmEmulator::new_stateful_with(get_default_context());
+    let result = tester
+        .with_account(address_sender(), U256::from(balance))
+        .run_transaction_as(&blob_transaction(), address_sender(), Revision::Cancun);
+
+    result.expect_status(StatusCode::Failure(FailureKind::InsufficientBalance))
+        .expect_balance(address_sender(), U256::from(balance));
+}
+
+#[test]
+fn test_blob_transaction_invalid() {
+    let mut no_blobs = blob_transaction();
+    if let Transaction::Blob(blob) = &mut no_blobs {
+        blob.blob_versioned_hashes = vec![];
+    }
+    let mut invalid_version = blob_transaction();
+    if let Transaction::Blob(blob) = &mut invalid_version {
+        blob.blob_versioned_hashes = vec![H256::repeat_byte(0x02)];
+    }
+
+    for tx in [no_blobs, invalid_version] {
+        let mut tester = EvmEmulator::new_stateful_with(get_default_context());
+        let result = tester
+            .with_account(address_sender(), U256::from(100_000_000))
+            .run_transaction_as(&tx, address_sender(), Revision::Cancun);
+
+        result.expect_status(StatusCode::Failure(FailureKind::Rejected));
+    }
+
+    let mut tester = EvmEmulator::new_stateful_with(get_default_context());
+    let result = tester
+        .with_account(address_sender(), U256::from(100_000_000))
+        .run_transaction_as(&blob_transaction(), address_sender(), Revision::Shanghai);
+
+    result.expect_status(StatusCode::Failure(FailureKind::Rejected));
+}
+
+#[test]
+fn test_blob_fee_is_not_carried_over() {
+    let mut host = StatefulHost::new_with(get_default_context());
+    host.debug_deploy_contract2(address_sender(), Code::empty(), U256::from(100_000_000));
+    host.debug_deploy_contract2(address_contract(), blob_hash_code("01"), U256::zero());
+    let host = Rc::new(RefCell::new(host));
+
+    let mut executor = Executor::new_with(host.clone(), Revision::Cancun);
+    let output = executor.execute_transaction(&blob_transaction(), address_sender());
+    assert_eq!(StatusCode::Success, output.status_code);
+    let balance = host.borrow().get_balance(address_sender());
+
+    // the next transaction on the same executor has no blobs to pay for.
+    let tx = Transaction::DynamicFee(DynamicFeeTransaction {
+        chain_id: U256::one(),
+        nonce: 1,
+        max_priority_fee_per_gas: U256::from(2),
+        max_fee_per_gas: U256::from(100),
+        gas_limit: 100_000,
+        to: Some(address_contract()),
+        value: U256::from(1000),
+        data: Bytes::default(),
+        access_list: vec![],
+        signature: Signature::default(),
+    });
+    let output = executor.execute_transaction(&tx, address_sender());
+    assert_eq!(StatusCode::Success, output.status_code);
+    assert_eq!(balance - 1000 - 21 * 12, host.borrow().get_balance(address_sender()));
+}
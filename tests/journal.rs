@@ -0,0 +1,190 @@
+use bytes::Bytes;
+use ethereum_types::{U256, Address};
+use evmstar::emulator::EvmEmulator;
+use evmstar::executor::callstack::{CallScope, ExecutionContext};
+use evmstar::model::{
+    code::{Code, Append},
+    evmc::{AccessStatus, StatusCode, StorageStatusKind, TxContext},
+    opcode::OpCode,
+    revision::Revision,
+};
+use evmstar::host::{Host, stateful::StatefulHost};
+use evmstar::tracer::Tracer;
+
+fn address_existing() -> Address {
+    Address::from_low_u64_be(0xaaaaaaaa)
+}
+fn address_new() -> Address {
+    Address::from_low_u64_be(0xbbbbbbbb)
+}
+
+fn host() -> StatefulHost {
+    let mut host = StatefulHost::new();
+    host.debug_deploy_contract2(address_existing(), Code::from("6000"), U256::from(100));
+    host.debug_set_storage(address_existing(), U256::one(), U256::from(0x11));
+    host
+}
+
+#[test]
+fn test_rollback_existing_account() {
+    let mut host = host();
+    let snapshot = host.take_snapshot();
+
+    host.set_storage(address_existing(), U256::one(), U256::from(0x22));
+    host.set_storage(address_existing(), U256::from(2), U256::from(0x33));
+    host.add_balance(address_existing(), U256::from(10));
+    host.subtract_balance(address_existing(), U256::from(30));
+    host.set_nonce(address_existing(), 7);
+    host.set_code(address_existing(), Bytes::from_static(&[0x60, 0x01]));
+    host.emit_log(address_existing(), &[0x01], &[U256::one()]);
+
+    assert_eq!(U256::from(80), host.get_balance(address_existing()));
+    assert_eq!(7, host.get_nonce(address_existing()));
+    assert_eq!(1, host.get_logs().len());
+
+    host.rollback(&snapshot);
+    assert_eq!(U256::from(0x11), host.get_storage(address_existing(), U256::one()));
+    assert_eq!(U256::zero(), host.get_storage(address_existing(), U256::from(2)));
+    assert_eq!(U256::from(100), host.get_balance(address_existing()));
+    assert_eq!(0, host.get_nonce(address_existing()));
+    assert_eq!(Bytes::from_static(&[0x60, 0x00]), host.get_code(address_existing(), 0, 2));
+    assert!(host.get_logs().is_empty());
+}
+
+#[test]
+fn test_rollback_burn() {
+    let mut host = host();
+    host.burn(U256::from(5));
+    let snapshot = host.take_snapshot();
+
+    host.subtract_balance(address_existing(), U256::from(10));
+    host.burn(U256::from(10));
+    assert_eq!(U256::from(15), host.get_burnt_amount());
+
+    host.rollback(&snapshot);
+    assert_eq!(U256::from(5), host.get_burnt_amount());
+    assert_eq!(U256::from(100), host.get_balance(address_existing()));
+}
+
+#[test]
+fn test_rollback_account_creation() {
+    let mut host = host();
+    let root = host.state_root();
+    let snapshot = host.take_snapshot();
+
+    host.add_balance(address_new(), U256::from(10));
+    host.set_code(address_new(), Bytes::from_static(&[0x00]));
+    host.set_nonce(address_new(), 1);
+    host.set_storage(address_new(), U256::one(), U256::one());
+    assert!(host.account_exists(address_new()));
+
+    host.rollback(&snapshot);
+    assert!(!host.account_exists(address_new()));
+    assert_eq!(root, host.state_root());
+}
+
+#[test]
+fn test_rollback_nested() {
+    let mut host = host();
+    let outer = host.take_snapshot();
+    host.set_nonce(address_existing(), 1);
+    host.emit_log(address_existing(), &[], &[]);
+
+    let inner = host.take_snapshot();
+    host.set_nonce(address_existing(), 2);
+    host.emit_log(address_existing(), &[], &[]);
+
+    host.rollback(&inner);
+    assert_eq!(1, host.get_nonce(address_existing()));
+    assert_eq!(1, host.get_logs().len());
+
+    host.rollback(&outer);
+    assert_eq!(0, host.get_nonce(address_existing()));
+    assert!(host.get_logs().is_empty());
+}
+
+#[test]
+fn test_rollback_warm_sets() {
+    let mut host = host();
+    assert_eq!(AccessStatus::Cold, host.access_account(address_new()));
+    let snapshot = host.take_snapshot();
+
+    assert_eq!(AccessStatus::Cold, host.access_account(address_existing()));
+    assert_eq!(AccessStatus::Warm, host.access_account(address_existing()));
+    assert_eq!(AccessStatus::Cold, host.access_storage(address_existing(), U256::one()));
+    assert_eq!(AccessStatus::Warm, host.access_storage(address_existing(), U256::one()));
+
+    host.rollback(&snapshot);
+    assert_eq!(AccessStatus::Cold, host.access_account(address_existing()));
+    assert_eq!(AccessStatus::Cold, host.access_storage(address_existing(), U256::one()));
+    // warmed before the snapshot.
+    assert_eq!(AccessStatus::Warm, host.access_account(address_new()));
+}
+
+#[test]
+fn test_commit_discards_journal() {
+    let mut host = host();
+    let snapshot = host.take_snapshot();
+    host.set_nonce(address_existing(), 1);
+    host.commit();
+
+    host.rollback(&snapshot);
+    assert_eq!(1, host.get_nonce(address_existing()));
+}
+
+/// gas of each SSTORE executed.
+#[derive(Default)]
+struct SstoreGas(Vec<i64>);
+
+impl Tracer for SstoreGas {
+    fn step_end(&mut self, opcode: OpCode, gas_cost: i64, _: &CallScope, _: &ExecutionContext) {
+        if opcode == OpCode::SSTORE {
+            self.0.push(gas_cost);
+        }
+    }
+}
+
+#[test]
+fn test_rollback_storage_dirty_flag() {
+    // called with calldata, write the slot and revert.
+    // otherwise call itself with calldata, then write the slot.
+    let code = Code::builder()
+        .append(OpCode::CALLDATASIZE)           // 0
+        .append("601d")                         // 1: PUSH1 0x1d
+        .append(OpCode::JUMPI)                  // 3
+        .append("60006000600160006000")         // 4: ret, args, value
+        .append("63aaaaaaaa61ffff")             // 14: address, gas
+        .append(OpCode::CALL)                   // 22
+        .append("6001600055")                   // 23: SSTORE(0, 1)
+        .append(OpCode::STOP)                   // 28
+        .append(OpCode::JUMPDEST)               // 29
+        .append("6001600055")                   // 30: SSTORE(0, 1)
+        .append("60006000")                     // 35: PUSH1 0x00 PUSH1 0x00
+        .append(OpCode::REVERT)                 // 39
+        .clone();
+
+    let mut tracer = SstoreGas::default();
+    let mut emu = EvmEmulator::new_stateful_with(TxContext::default());
+    emu.with_to(address_existing())
+        .mutate_scope(|scope| scope.code_address = address_existing())
+        .with_contract_deployed2(address_existing(), code.clone(), U256::zero())
+        .with_code(code)
+        .with_default_gas()
+        .run_traced_as(Revision::Shanghai, &mut tracer)
+        .expect_status(StatusCode::Success)
+        .expect_storage(address_existing(), U256::zero(), U256::one());
+
+    // the slot is clean and cold again after the revert: 2100 + 20000 both times.
+    assert_eq!(vec![22100, 22100], tracer.0);
+}
+
+#[test]
+fn test_rollback_storage_status() {
+    let mut host = host();
+    let snapshot = host.take_snapshot();
+    assert_eq!(StorageStatusKind::Added, host.set_storage(address_existing(), U256::from(2), U256::one()).kind);
+    host.rollback(&snapshot);
+
+    // the write is the first one again after the rollback.
+    assert_eq!(StorageStatusKind::Added, host.set_storage(address_existing(), U256::from(2), U256::one()).kind);
+}
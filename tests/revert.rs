@@ -8,7 +8,9 @@ use evmstar::model::{
     evmc::{
         StatusCode, FailureKind,
         TxContext,
-    }
+    },
+    abi::{Abi, AbiType, AbiValue},
+    revert::{PanicCode, RevertReason},
 };
 
 fn default_address() -> Address { Address::from_low_u64_be(0xffffeeee) }
@@ -24,6 +26,8 @@ fn get_default_context() -> TxContext {
         base_fee: U256::from(0x4444),
         chain_id: U256::from(0x01),
         difficulty: U256::from(0x5555),
+        excess_blob_gas: 0,
+        blob_hashes: vec![],
     }
 }
 
@@ -590,4 +594,136 @@ fn test_reverted_create() {
 fn test_reverted_create2() {
     // TODO
     assert!(false);
-}
\ No newline at end of file
+}
+/// store `data` in memory and revert with it.
+fn revert_with(data: &[u8]) -> Code {
+    let mut code = Code::builder();
+    for (index, chunk) in data.chunks(32).enumerate() {
+        let mut word = [0u8; 32];
+        word[..chunk.len()].copy_from_slice(chunk);
+        code.append(OpCode::PUSH32)
+            .append(&word[..])
+            .append(OpCode::PUSH2)
+            .append(&((index * 32) as u16).to_be_bytes()[..])
+            .append(OpCode::MSTORE);
+    }
+    code.append(OpCode::PUSH2)
+        .append(&(data.len() as u16).to_be_bytes()[..])
+        .append("6000")     // PUSH1 0x00
+        .append(OpCode::REVERT)
+        .clone()
+}
+
+fn word(value: u64) -> Vec<u8> {
+    let mut word = [0u8; 32];
+    U256::from(value).to_big_endian(&mut word);
+    word.to_vec()
+}
+
+/// length and content of `bytes` or `string`.
+fn padded(data: &[u8]) -> Vec<u8> {
+    let mut encoded = word(data.len() as u64);
+    encoded.extend_from_slice(data);
+    encoded.resize(32 + data.len().div_ceil(32) * 32, 0);
+    encoded
+}
+
+fn run_revert(data: &[u8]) -> evmstar::emulator::EvmResult {
+    let mut tester = EvmEmulator::new_stateful_with(get_default_context());
+    tester.with_to(default_address())
+        .with_default_gas()
+        .run_code(revert_with(data))
+}
+
+#[test]
+fn test_revert_reason_error() {
+    let data = [hex::decode("08c379a0").unwrap(), word(0x20), padded(b"Not enough Ether provided.")].concat();
+
+    let result = run_revert(&data);
+    result.expect_status(StatusCode::Failure(FailureKind::Revert))
+        .expect_revert_reason("Not enough Ether provided.");
+    assert_eq!("Not enough Ether provided.", result.output.revert_reason().unwrap().to_string());
+
+    // truncated data is not decoded.
+    assert_eq!(None, run_revert(&data[..data.len() - 32]).output.revert_reason());
+}
+
+#[test]
+fn test_revert_reason_panic() {
+    let data = [hex::decode("4e487b71").unwrap(), word(0x11)].concat();
+    let result = run_revert(&data);
+    result.expect_panic(PanicCode::ArithmeticOverflow);
+    assert_eq!("arithmetic underflow or overflow", result.output.revert_reason().unwrap().to_string());
+
+    let data = [hex::decode("4e487b71").unwrap(), word(0x99)].concat();
+    let reason = run_revert(&data).output.revert_reason().unwrap();
+    assert_eq!(RevertReason::Panic(U256::from(0x99)), reason);
+    assert_eq!(None, reason.panic_code());
+    assert_eq!("unknown panic code: 0x99", reason.to_string());
+}
+
+#[test]
+fn test_revert_reason_custom_error() {
+    let abi = Abi::from_json(r#"[
+        {"type": "function", "name": "withdraw", "inputs": [], "outputs": []},
+        {"type": "error", "name": "InsufficientBalance", "inputs": [
+            {"name": "available", "type": "uint256"},
+            {"name": "required", "type": "uint256"}
+        ]},
+        {"type": "error", "name": "Denied", "inputs": [
+            {"name": "who", "type": "address"},
+            {"name": "roles", "type": "string[]"},
+            {"name": "flag", "type": "tuple", "components": [
+                {"name": "level", "type": "int8"},
+                {"name": "strict", "type": "bool"}
+            ]}
+        ]}
+    ]"#).unwrap();
+    assert_eq!(2, abi.errors.len());
+    assert_eq!("InsufficientBalance(uint256,uint256)", abi.errors[0].signature());
+    assert_eq!([0xcf, 0x47, 0x91, 0x81], abi.errors[0].selector());
+    assert_eq!("Denied(address,string[],(int8,bool))", abi.errors[1].signature());
+
+    let data = [hex::decode("cf479181").unwrap(), word(100), word(250)].concat();
+    let result = run_revert(&data);
+    assert_eq!(None, result.output.revert_reason());
+    let reason = result.output.revert_reason_with(&abi).unwrap();
+    assert_eq!(
+        RevertReason::Custom {
+            name: "InsufficientBalance".to_string(),
+            args: vec![AbiValue::Uint(U256::from(100)), AbiValue::Uint(U256::from(250))],
+        },
+        reason
+    );
+    assert_eq!("InsufficientBalance(100, 250)", reason.to_string());
+
+    let mut data = abi.errors[1].selector().to_vec();
+    data.extend(word(0xabcd));                     // who
+    data.extend(word(0x80));                       // offset of roles
+    data.extend([0xff; 32]);                       // level -1
+    data.extend(word(1));                          // strict
+    data.extend(word(2));                          // length of roles
+    data.extend(word(0x40));                       // offset of "a"
+    data.extend(word(0x80));                       // offset of "bc"
+    data.extend(padded(b"a"));
+    data.extend(padded(b"bc"));
+    let reason = run_revert(&data).output.revert_reason_with(&abi).unwrap();
+    assert_eq!(
+        format!("Denied({:?}, [\"a\", \"bc\"], (-1, true))", Address::from_low_u64_be(0xabcd)),
+        reason.to_string()
+    );
+}
+
+#[test]
+fn test_abi_type() {
+    assert_eq!(AbiType::Uint(256), AbiType::parse("uint").unwrap());
+    assert_eq!(
+        AbiType::FixedArray(Box::new(AbiType::Tuple(vec![AbiType::Address, AbiType::Array(Box::new(AbiType::Uint(8)))])), 2),
+        AbiType::parse("(address,uint8[])[2]").unwrap()
+    );
+    assert_eq!("(address,uint8[])[2]", AbiType::parse("(address,uint8[])[2]").unwrap().to_string());
+    assert_eq!("bytes32[][3]", AbiType::parse("bytes32[][3]").unwrap().to_string());
+    assert!(AbiType::parse("uint7").is_err());
+    assert!(AbiType::parse("bytes33").is_err());
+    assert!(AbiType::parse("mapping").is_err());
+}
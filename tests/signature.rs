@@ -0,0 +1,285 @@
+use bytes::Bytes;
+use ethereum_types::{U256, H256, Address};
+use evmstar::model::{
+    code::{
+        Code, Append,
+    },
+    opcode::OpCode,
+    evmc::{
+        StatusCode, FailureKind,
+        TxContext,
+    },
+    revision::Revision,
+    transaction::*,
+};
+use evmstar::emulator::EvmEmulator;
+use evmstar::utils::{keccak256, secp256k1::{sign, secret_key_to_address, SECP256K1N}};
+use hex::decode;
+use hex_literal::hex;
+
+fn secret_key() -> H256 {
+    H256::from(hex!("4646464646464646464646464646464646464646464646464646464646464646"))
+}
+fn address_sender() -> Address {
+    Address::from(hex!("9d8a62f656a8d1615c1294fd71e9cfb3e4855a4f"))
+}
+fn address_contract() -> Address {
+    Address::from_low_u64_be(0xaaaaaaaa)
+}
+
+fn get_default_context() -> TxContext {
+    TxContext {
+        gas_price: U256::zero(),
+        origin: Address::zero(),
+        coinbase: Address::from_low_u64_be(0xabcd),
+        block_number: 0x1111,
+        block_timestamp: 0x2222,
+        gas_limit: 0x3333,
+        base_fee: U256::from(10),
+        chain_id: U256::from(0x01),
+        difficulty: U256::from(0x5555),
+        excess_blob_gas: 0,
+        blob_hashes: vec![],
+    }
+}
+
+fn eip155_transaction() -> Transaction {
+    Transaction::Legacy(LegacyTransaction {
+        nonce: 9,
+        gas_price: U256::from(20_000_000_000u64),
+        gas_limit: 21000,
+        to: Some(Address::from(hex!("3535353535353535353535353535353535353535"))),
+        value: U256::from(1_000_000_000_000_000_000u64),
+        data: Bytes::default(),
+        // chain id 1 is encoded in v before signing.
+        signature: Signature { v: 37, ..Default::default() },
+    })
+}
+
+fn dynamic_fee_transaction() -> Transaction {
+    Transaction::DynamicFee(DynamicFeeTransaction {
+        chain_id: U256::one(),
+        nonce: 0,
+        max_priority_fee_per_gas: U256::from(2),
+        max_fee_per_gas: U256::from(100),
+        gas_limit: 100_000,
+        to: Some(address_contract()),
+        value: U256::from(1000),
+        data: Bytes::default(),
+        access_list: vec![],
+        signature: Signature::default(),
+    })
+}
+
+#[test]
+fn test_recover_eip155_sender() {
+    // example in https://eips.ethereum.org/EIPS/eip-155
+    let raw = "f86c098504a817c800825208943535353535353535353535353535353535353535880de0b6b3a76400008025a028ef61340bd939bc2195fe537567866003e1a15d3c71ff63e1590620aa636276a067cbe9d8997f761aecb703304b3800ccf555c9f3dc64214b297fb1966a3b6d83";
+    let tx = Transaction::from_hex(raw).unwrap();
+    assert_eq!(Ok(address_sender()), tx.recover_sender(U256::one()));
+    assert_eq!(Err(TransactionError::ChainIdMismatch), tx.recover_sender(U256::from(5)));
+
+    // signing is deterministic as specified in RFC 6979.
+    let mut signed = eip155_transaction();
+    signed.sign(&secret_key()).unwrap();
+    assert_eq!(decode(raw).unwrap(), signed.encode());
+}
+
+#[test]
+fn test_recover_pre_eip155_sender() {
+    let mut tx = eip155_transaction();
+    if let Transaction::Legacy(legacy) = &mut tx {
+        legacy.signature.v = 27;
+    }
+    tx.sign(&secret_key()).unwrap();
+
+    let v = tx.signature().v;
+    assert!(v == 27 || v == 28);
+    assert_eq!(None, tx.chain_id());
+    // replayable on any chain.
+    assert_eq!(Ok(address_sender()), tx.recover_sender(U256::one()));
+    assert_eq!(Ok(address_sender()), tx.recover_sender(U256::from(5)));
+}
+
+#[test]
+fn test_recover_typed_transaction_sender() {
+    let mut tx = dynamic_fee_transaction();
+    tx.sign(&secret_key()).unwrap();
+    assert!(tx.signature().v <= 1);
+
+    let tx = Transaction::decode(&tx.encode()).unwrap();
+    assert_eq!(Ok(address_sender()), tx.recover_sender(U256::one()));
+    assert_eq!(Err(TransactionError::ChainIdMismatch), tx.recover_sender(U256::from(5)));
+}
+
+#[test]
+fn test_recover_invalid_signature() {
+    let mut tx = dynamic_fee_transaction();
+    tx.sign(&secret_key()).unwrap();
+    let signature = *tx.signature();
+
+    // high s is rejected as specified in EIP-2 even though it recovers the same key.
+    if let Transaction::DynamicFee(inner) = &mut tx {
+        inner.signature = Signature {
+            v: signature.v ^ 1,
+            r: signature.r,
+            s: SECP256K1N - signature.s,
+        };
+    }
+    assert_eq!(Err(TransactionError::InvalidSignature), tx.recover_sender(U256::one()));
+
+    if let Transaction::DynamicFee(inner) = &mut tx {
+        inner.signature = Signature { v: 2, ..signature };
+    }
+    assert_eq!(Err(TransactionError::InvalidSignature), tx.recover_sender(U256::one()));
+
+    // tampered transaction recovers someone else.
+    if let Transaction::DynamicFee(inner) = &mut tx {
+        inner.signature = signature;
+        inner.value = U256::from(1001);
+    }
+    assert_ne!(Ok(address_sender()), tx.recover_sender(U256::one()));
+}
+
+#[test]
+fn test_recover_authority() {
+    let mut authorization = Authorization {
+        chain_id: U256::one(),
+        address: address_contract(),
+        nonce: 1,
+        signature: Signature::default(),
+    };
+    let (y_parity, r, s) = sign(&authorization.signing_hash(), &secret_key()).unwrap();
+    authorization.signature = Signature { v: y_parity as u64, r, s };
+
+    assert_eq!(Some(address_sender()), authorization.recover_authority());
+}
+
+#[test]
+fn test_execute_signed_transaction() {
+    let mut tx = dynamic_fee_transaction();
+    tx.sign(&secret_key()).unwrap();
+
+    let mut tester = EvmEmulator::new_stateful_with(get_default_context());
+    let result = tester
+        .with_account(address_sender(), U256::from(100_000_000))
+        .run_signed_transaction(&tx);
+
+    result.expect_status(StatusCode::Success)
+        .expect_balance(address_sender(), U256::from(100_000_000 - 1000))
+        .expect_balance(address_contract(), U256::from(1000));
+}
+
+#[test]
+fn test_execute_signed_transaction_on_other_chain() {
+    let mut tx = dynamic_fee_transaction();
+    tx.sign(&secret_key()).unwrap();
+
+    let mut context = get_default_context();
+    context.chain_id = U256::from(5);
+
+    let mut tester = EvmEmulator::new_stateful_with(context);
+    let result = tester
+        .with_account(address_sender(), U256::from(100_000_000))
+        .run_signed_transaction(&tx);
+
+    result.expect_status(StatusCode::Failure(FailureKind::Rejected))
+        .expect_balance(address_sender(), U256::from(100_000_000))
+        .expect_balance(address_contract(), U256::zero());
+}
+
+#[test]
+fn test_execute_eip155_transaction_before_spurious() {
+    let mut tx = eip155_transaction();
+    tx.sign(&secret_key()).unwrap();
+
+    let mut tester = EvmEmulator::new_stateful_with(get_default_context());
+    let result = tester
+        .with_account(address_sender(), U256::from(10).pow(U256::from(19)))
+        .run_signed_transaction_as(&tx, Revision::Tangerine);
+
+    result.expect_status(StatusCode::Failure(FailureKind::Rejected));
+}
+
+fn ecrecover_code(hash: H256, v: U256, r: U256, s: U256) -> Code {
+    Code::builder()
+        .append(OpCode::PUSH32)
+        .append(U256::from_big_endian(hash.as_bytes()))
+        .append(OpCode::PUSH1)
+        .append("00")
+        .append(OpCode::MSTORE)
+        .append(OpCode::PUSH32)
+        .append(v)
+        .append(OpCode::PUSH1)
+        .append("20")
+        .append(OpCode::MSTORE)
+        .append(OpCode::PUSH32)
+        .append(r)
+        .append(OpCode::PUSH1)
+        .append("40")
+        .append(OpCode::MSTORE)
+        .append(OpCode::PUSH32)
+        .append(s)
+        .append(OpCode::PUSH1)
+        .append("60")
+        .append(OpCode::MSTORE)
+        .append(OpCode::PUSH1)   // ret size
+        .append("20")
+        .append(OpCode::PUSH1)   // ret offset
+        .append("80")
+        .append(OpCode::PUSH1)   // args size
+        .append("80")
+        .append(OpCode::PUSH1)   // args offset
+        .append("00")
+        .append(OpCode::PUSH1)   // value
+        .append("00")
+        .append(OpCode::PUSH1)   // address
+        .append("01")
+        .append(OpCode::PUSH2)   // gas
+        .append("ffff")
+        .append(OpCode::CALL)
+        .append(OpCode::POP)
+        .append(OpCode::PUSH1)
+        .append("20")
+        .append(OpCode::PUSH1)
+        .append("80")
+        .append(OpCode::RETURN)
+        .clone()
+}
+
+#[test]
+fn test_ecrecover_precompile() {
+    let hash = keccak256(b"evmstar");
+    let (y_parity, r, s) = sign(&hash, &secret_key()).unwrap();
+    assert_eq!(Some(address_sender()), secret_key_to_address(&secret_key()));
+
+    let code = ecrecover_code(hash, U256::from(27 + y_parity), r, s);
+    let mut tester = EvmEmulator::new_stateful_with(get_default_context());
+    let result = tester
+        .with_to(address_contract())
+        .with_default_gas()
+        .run_code(code);
+
+    result.expect_status(StatusCode::Success)
+        .expect_output("0000000000000000000000009d8a62f656a8d1615c1294fd71e9cfb3e4855a4f")
+        .expect_gas(48 + 21 + 103 + 3000 + 2 + 6);
+}
+
+#[test]
+fn test_ecrecover_precompile_invalid_signature() {
+    let hash = keccak256(b"evmstar");
+    let (y_parity, r, s) = sign(&hash, &secret_key()).unwrap();
+
+    // v must be 27 or 28.
+    let code = ecrecover_code(hash, U256::from(y_parity), r, s);
+    let mut tester = EvmEmulator::new_stateful_with(get_default_context());
+    let result = tester
+        .with_to(address_contract())
+        .with_default_gas()
+        .run_code(code);
+
+    // nothing is written to the return area.
+    result.expect_status(StatusCode::Success)
+        .expect_output("0000000000000000000000000000000000000000000000000000000000000000")
+        .expect_gas(48 + 21 + 103 + 3000 + 2 + 6);
+}
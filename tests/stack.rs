@@ -14,6 +14,7 @@ use evmstar::model::{
     evmc::{
         StatusCode, FailureKind,
     },
+    revision::Revision,
 };
 
 use hex::{decode};
@@ -470,4 +471,43 @@ RETURN
     assert_eq!(StatusCode::Success, output.status_code);
     assert_eq!(Bytes::from(memory), output.data);
     assert_eq!(consumed_gas(443), output.gas_left);
-}
\ No newline at end of file
+}
+
+#[test]
+pub fn test_push0() {
+    let host = TransientHost::new();
+    let host = Rc::new(RefCell::new(host));
+    let mut executor = Executor::new(host.clone());
+    let mut builder = Code::builder();
+
+    let code = builder
+        .append(OpCode::PUSH1)
+        .append(0xff)
+        .append(OpCode::PUSH0)
+        .append(OpCode::MSTORE8)
+        .append(OpCode::PUSH1)
+        .append(0x01)
+        .append(OpCode::PUSH0)
+        .append(OpCode::RETURN);
+
+    let output = executor.execute_raw(code);
+
+    assert_eq!(StatusCode::Success, output.status_code);
+    assert_eq!(Bytes::from(vec![0xff]), output.data);
+    assert_eq!(consumed_gas(3 + 2 + 6 + 3 + 2), output.gas_left);
+}
+
+#[test]
+pub fn test_push0_before_shanghai() {
+    let host = TransientHost::new();
+    let host = Rc::new(RefCell::new(host));
+    let mut executor = Executor::new_with(host.clone(), Revision::London);
+    let mut builder = Code::builder();
+
+    let code = builder
+        .append(OpCode::PUSH0);
+
+    let output = executor.execute_raw(code);
+
+    assert_eq!(StatusCode::Failure(FailureKind::InvalidInstruction), output.status_code);
+}
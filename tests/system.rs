@@ -0,0 +1,148 @@
+use std::rc::Rc;
+use std::cell::RefCell;
+
+use ethereum_types::{U256, H256, Address};
+use evmstar::model::{
+    code::Code,
+    evmc::{
+        StatusCode, FailureKind,
+        TxContext, Output,
+    },
+    revision::Revision,
+    system::*,
+};
+use evmstar::executor::{
+    block::{BlockExecutor, BlockError},
+    callstack::CallScope,
+    executor::Executor,
+};
+use evmstar::host::{Host, stateful::StatefulHost};
+
+const TIMESTAMP: i64 = 0x2222;
+const BLOCK_NUMBER: i64 = 0x1111;
+
+fn get_default_context() -> TxContext {
+    TxContext {
+        gas_price: U256::zero(),
+        origin: Address::zero(),
+        coinbase: Address::from_low_u64_be(0xabcd),
+        block_number: BLOCK_NUMBER,
+        block_timestamp: TIMESTAMP,
+        gas_limit: 30_000_000,
+        base_fee: U256::from(10),
+        chain_id: U256::from(0x01),
+        difficulty: U256::from(0x5555),
+        excess_blob_gas: 0,
+        blob_hashes: vec![],
+    }
+}
+
+fn host_with_system_contracts() -> Rc<RefCell<StatefulHost>> {
+    let mut host = StatefulHost::new_with(get_default_context());
+    host.debug_deploy_contract2(BEACON_ROOTS_ADDRESS, Code::from(BEACON_ROOTS_CODE), U256::zero());
+    host.debug_deploy_contract2(HISTORY_STORAGE_ADDRESS, Code::from(HISTORY_STORAGE_CODE), U256::zero());
+    Rc::new(RefCell::new(host))
+}
+
+/// call `address` with a 32 bytes argument as an ordinary account does.
+fn call(host: Rc<RefCell<StatefulHost>>, address: Address, argument: U256, revision: Revision) -> Output {
+    let mut calldata = [0u8; 32];
+    argument.to_big_endian(&mut calldata);
+
+    let code_size = host.borrow().get_code_size(address).as_usize();
+    let code = host.borrow().get_code(address, 0, code_size);
+    let scope = CallScope {
+        caller: Address::from_low_u64_be(0x1234),
+        to: address,
+        code_address: address,
+        code: code.into(),
+        calldata: calldata.to_vec().into(),
+        gas_limit: 100_000,
+        gas_left: 100_000,
+        ..CallScope::default()
+    };
+    Executor::new_with(host, revision).execute_raw_with(scope)
+}
+
+#[test]
+fn test_beacon_root() {
+    let root = H256::repeat_byte(0xbe);
+    let host = host_with_system_contracts();
+
+    let mut block = BlockExecutor::new(host.clone(), Revision::Cancun);
+    assert_eq!(Ok(()), block.apply_beacon_root(root));
+
+    // timestamp and root are stored in the ring buffers.
+    let index = TIMESTAMP as u64 % HISTORY_BUFFER_LENGTH;
+    let stored_timestamp = host.borrow().debug_get_storage(BEACON_ROOTS_ADDRESS, U256::from(index));
+    let stored_root = host.borrow().debug_get_storage(BEACON_ROOTS_ADDRESS, U256::from(index + HISTORY_BUFFER_LENGTH));
+    assert_eq!(U256::from(TIMESTAMP), stored_timestamp);
+    assert_eq!(U256::from_big_endian(root.as_bytes()), stored_root);
+
+    // system call is free.
+    let output = block.finish();
+    assert_eq!(0, output.gas_used);
+    assert!(output.receipts.is_empty());
+
+    let output = call(host.clone(), BEACON_ROOTS_ADDRESS, U256::from(TIMESTAMP), Revision::Cancun);
+    assert_eq!(StatusCode::Success, output.status_code);
+    assert_eq!(root.as_bytes(), &output.data[..]);
+
+    // unknown timestamp.
+    let output = call(host, BEACON_ROOTS_ADDRESS, U256::from(TIMESTAMP + 1), Revision::Cancun);
+    assert_eq!(StatusCode::Failure(FailureKind::Revert), output.status_code);
+}
+
+#[test]
+fn test_system_address_is_not_created() {
+    let host = host_with_system_contracts();
+    let mut block = BlockExecutor::new(host.clone(), Revision::Prague);
+    assert_eq!(Ok(()), block.apply_beacon_root(H256::repeat_byte(0xbe)));
+    assert_eq!(Ok(()), block.apply_parent_hash(H256::repeat_byte(0x9a)));
+    assert!(!host.borrow().account_exists(SYSTEM_ADDRESS));
+}
+
+#[test]
+fn test_beacon_root_before_cancun() {
+    let host = host_with_system_contracts();
+    let mut block = BlockExecutor::new(host, Revision::Shanghai);
+    assert_eq!(Err(BlockError::UnsupportedRevision), block.apply_beacon_root(H256::repeat_byte(0xbe)));
+}
+
+#[test]
+fn test_beacon_root_without_contract() {
+    let host = Rc::new(RefCell::new(StatefulHost::new_with(get_default_context())));
+    let mut block = BlockExecutor::new(host.clone(), Revision::Cancun);
+    assert_eq!(Ok(()), block.apply_beacon_root(H256::repeat_byte(0xbe)));
+
+    let index = TIMESTAMP as u64 % HISTORY_BUFFER_LENGTH;
+    assert_eq!(U256::zero(), host.borrow().debug_get_storage(BEACON_ROOTS_ADDRESS, U256::from(index)));
+}
+
+#[test]
+fn test_parent_hash() {
+    let parent_hash = H256::repeat_byte(0x9a);
+    let host = host_with_system_contracts();
+
+    let mut block = BlockExecutor::new(host.clone(), Revision::Prague);
+    assert_eq!(Ok(()), block.apply_parent_hash(parent_hash));
+
+    let index = (BLOCK_NUMBER as u64 - 1) % HISTORY_SERVE_WINDOW;
+    let stored = host.borrow().debug_get_storage(HISTORY_STORAGE_ADDRESS, U256::from(index));
+    assert_eq!(U256::from_big_endian(parent_hash.as_bytes()), stored);
+
+    let output = call(host.clone(), HISTORY_STORAGE_ADDRESS, U256::from(BLOCK_NUMBER - 1), Revision::Prague);
+    assert_eq!(StatusCode::Success, output.status_code);
+    assert_eq!(parent_hash.as_bytes(), &output.data[..]);
+
+    // current block is not served.
+    let output = call(host, HISTORY_STORAGE_ADDRESS, U256::from(BLOCK_NUMBER), Revision::Prague);
+    assert_eq!(StatusCode::Failure(FailureKind::Revert), output.status_code);
+}
+
+#[test]
+fn test_parent_hash_before_prague() {
+    let host = host_with_system_contracts();
+    let mut block = BlockExecutor::new(host, Revision::Cancun);
+    assert_eq!(Err(BlockError::UnsupportedRevision), block.apply_parent_hash(H256::repeat_byte(0x9a)));
+}
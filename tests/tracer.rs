@@ -0,0 +1,590 @@
+use std::rc::Rc;
+use std::cell::RefCell;
+
+use bytes::Bytes;
+use ethereum_types::{U256, H256, Address};
+
+use evmstar::emulator::EvmEmulator;
+use evmstar::executor::{
+    callstack::{CallScope, ExecutionContext},
+    executor::Executor,
+};
+use evmstar::host::{Host, stateful::StatefulHost};
+use evmstar::interpreter::CallKind;
+use evmstar::tracer::{
+    Tracer,
+    call::CallTracer,
+    eip3155::Eip3155Tracer,
+    prestate::{AccountState, PrestateTracer},
+    profiler::{GasProfiler, GasStat},
+    recorder::Recorder,
+};
+#[allow(unused_imports)]
+use evmstar::model::{
+    code::{
+        Code, Append,
+    },
+    opcode::OpCode,
+    evmc::{
+        StatusCode, FailureKind,
+        TxContext,
+    },
+    revision::Revision,
+    transaction::{LegacyTransaction, Signature, Transaction},
+};
+
+fn default_address() -> Address { Address::from_low_u64_be(0xffffeeee) }
+
+fn get_default_context() -> TxContext {
+    TxContext {
+        gas_price: U256::from(0x1234),
+        origin: Address::from_low_u64_be(0x1234),
+        coinbase: Address::from_low_u64_be(0xabcd),
+        block_number: 0x1111,
+        block_timestamp: 0x2222,
+        gas_limit: 100_000,
+        base_fee: U256::from(0x4444),
+        chain_id: U256::from(0x01),
+        difficulty: U256::from(0x5555),
+        excess_blob_gas: 0,
+        blob_hashes: vec![],
+    }
+}
+
+#[derive(Default)]
+struct RecordingTracer {
+    steps: Vec<(usize, OpCode)>,
+    gas_costs: Vec<i64>,
+    calls: Vec<String>,
+    storage: Vec<String>,
+    logs: usize,
+    failures: Vec<FailureKind>,
+    /// stack and memory before each step.
+    states: Vec<(Vec<U256>, Vec<u8>)>,
+}
+
+impl Tracer for RecordingTracer {
+    fn step(&mut self, opcode: OpCode, scope: &CallScope, _: &ExecutionContext) {
+        self.steps.push((scope.pc, opcode));
+        self.states.push((scope.stack.0.to_vec(), scope.memory.0.clone()));
+    }
+    fn step_end(&mut self, _: OpCode, gas_cost: i64, _: &CallScope, _: &ExecutionContext) {
+        self.gas_costs.push(gas_cost);
+    }
+    fn call_enter(&mut self, kind: CallKind, scope: &CallScope) {
+        self.calls.push(format!("enter {:?} {:?} {}", kind, scope.to, scope.depth));
+    }
+    fn call_exit(&mut self, scope: &CallScope, status: &StatusCode, output: &Bytes) {
+        self.calls.push(format!("exit {:?} {:?} {}", scope.to, status, output.len()));
+    }
+    fn storage_read(&mut self, _: Address, key: U256, value: U256) {
+        self.storage.push(format!("read {} {}", key, value));
+    }
+    fn storage_write(&mut self, _: Address, key: U256, value: U256) {
+        self.storage.push(format!("write {} {}", key, value));
+    }
+    fn log(&mut self, _: Address, _: &[u8], _: &[U256]) {
+        self.logs += 1;
+    }
+    fn failure(&mut self, kind: &FailureKind, _: &CallScope) {
+        self.failures.push(kind.clone());
+    }
+}
+
+#[test]
+fn test_trace_steps() {
+    let mut emu = EvmEmulator::new_stateful_with(get_default_context());
+    let code = Code::builder()
+        .append(OpCode::PUSH1)
+        .append("01")
+        .append(OpCode::PUSH1)
+        .append("02")
+        .append(OpCode::ADD)
+        .append(OpCode::POP)
+        .append(OpCode::STOP)
+        .clone();
+
+    let mut tracer = RecordingTracer::default();
+    emu.with_to(default_address())
+        .with_code(code)
+        .with_default_gas()
+        .run_traced_as(Revision::Shanghai, &mut tracer)
+        .expect_status(StatusCode::Success)
+        .expect_gas(3 + 3 + 3 + 2);
+
+    assert_eq!(vec![(0, OpCode::PUSH1), (2, OpCode::PUSH1), (4, OpCode::ADD), (5, OpCode::POP), (6, OpCode::STOP)], tracer.steps);
+    assert_eq!(vec![3, 3, 3, 2, 0], tracer.gas_costs);
+    assert_eq!(
+        vec![
+            format!("enter Call {:?} 0", default_address()),
+            format!("exit {:?} Success 0", default_address()),
+        ],
+        tracer.calls
+    );
+    assert!(tracer.failures.is_empty());
+}
+
+#[test]
+fn test_trace_storage_and_log() {
+    let mut emu = EvmEmulator::new_stateful_with(get_default_context());
+    let code = Code::builder()
+        .append("602a6001")     // PUSH1 0x2a PUSH1 0x01
+        .append(OpCode::SSTORE)
+        .append("6001")
+        .append(OpCode::SLOAD)
+        .append("60006000")
+        .append(OpCode::LOG0)
+        .clone();
+
+    let mut tracer = RecordingTracer::default();
+    emu.with_to(default_address())
+        .with_code(code)
+        .with_default_gas()
+        .run_traced_as(Revision::Shanghai, &mut tracer)
+        .expect_status(StatusCode::Success);
+
+    assert_eq!(vec!["write 1 42".to_string(), "read 1 42".to_string()], tracer.storage);
+    assert_eq!(1, tracer.logs);
+}
+
+#[test]
+fn test_trace_failure() {
+    let mut emu = EvmEmulator::new_stateful_with(get_default_context());
+    let code = Code::builder()
+        .append(OpCode::POP)
+        .clone();
+
+    let mut tracer = RecordingTracer::default();
+    emu.with_to(default_address())
+        .with_code(code)
+        .with_default_gas()
+        .run_traced_as(Revision::Shanghai, &mut tracer)
+        .expect_status(StatusCode::Failure(FailureKind::StackUnderflow));
+
+    assert_eq!(vec![(0, OpCode::POP)], tracer.steps);
+    assert!(tracer.gas_costs.is_empty());
+    assert_eq!(vec![FailureKind::StackUnderflow], tracer.failures);
+    assert_eq!(format!("exit {:?} Failure(StackUnderflow) 0", default_address()), tracer.calls[1]);
+}
+
+#[test]
+fn test_trace_nested_call() {
+    let callee = Address::from_low_u64_be(0xbbbb);
+    let callee_code = Code::builder()
+        .append("60016000")     // PUSH1 0x01 PUSH1 0x00
+        .append(OpCode::MSTORE8)
+        .append("60016000")     // PUSH1 0x01 PUSH1 0x00
+        .append(OpCode::REVERT)
+        .clone();
+
+    let code = Code::builder()
+        .append("6000600060006000600061bbbb61ffff") // ret, args, value, address, gas
+        .append(OpCode::CALL)
+        .clone();
+
+    let mut emu = EvmEmulator::new_stateful_with(get_default_context());
+    let mut tracer = RecordingTracer::default();
+    emu.with_to(default_address())
+        .with_contract_deployed2(callee, callee_code, U256::zero())
+        .with_code(code)
+        .with_default_gas()
+        .run_traced_as(Revision::Shanghai, &mut tracer)
+        .expect_status(StatusCode::Success);
+
+    assert_eq!(
+        vec![
+            format!("enter Call {:?} 0", default_address()),
+            format!("enter Call {:?} 1", callee),
+            format!("exit {:?} Failure(Revert) 1", callee),
+            format!("exit {:?} Success 0", default_address()),
+        ],
+        tracer.calls
+    );
+}
+
+#[test]
+fn test_eip3155_lines() {
+    let host = Rc::new(RefCell::new(StatefulHost::new_with(get_default_context())));
+    let code = Code::builder()
+        .append("6001600201")     // PUSH1 0x01 PUSH1 0x02 ADD
+        .append("6000")
+        .append(OpCode::MSTORE)
+        .append("60206000")
+        .append(OpCode::RETURN)
+        .clone();
+    let scope = CallScope {
+        code,
+        to: default_address(),
+        gas_limit: 100_000,
+        gas_left: 100_000,
+        ..CallScope::default()
+    };
+
+    let mut tracer = Eip3155Tracer::new(Vec::new());
+    let output = Executor::new_with(host.clone(), Revision::Shanghai)
+        .with_tracer(&mut tracer)
+        .execute_raw_with(scope);
+    tracer.write_summary(host.borrow().state_root(), &output);
+
+    let lines = String::from_utf8(tracer.into_inner().unwrap()).unwrap();
+    let lines: Vec<&str> = lines.lines().collect();
+    assert_eq!(9, lines.len());
+    assert_eq!(
+        r#"{"pc":0,"op":96,"gas":"0x186a0","gasCost":"0x3","memSize":0,"stack":[],"returnData":"0x","depth":1,"refund":0,"opName":"PUSH1"}"#,
+        lines[0]
+    );
+    assert_eq!(
+        r#"{"pc":4,"op":1,"gas":"0x1869a","gasCost":"0x3","memSize":0,"stack":["0x1","0x2"],"returnData":"0x","depth":1,"refund":0,"opName":"ADD"}"#,
+        lines[2]
+    );
+    assert_eq!(
+        r#"{"pc":7,"op":82,"gas":"0x18694","gasCost":"0x6","memSize":0,"stack":["0x3","0x0"],"returnData":"0x","depth":1,"refund":0,"opName":"MSTORE"}"#,
+        lines[4]
+    );
+    assert_eq!(
+        format!(
+            r#"{{"stateRoot":"{:?}","output":"0x{}","gasUsed":"0x18","pass":true,"fork":"Shanghai"}}"#,
+            host.borrow().state_root(),
+            "0".repeat(63) + "3"
+        ),
+        lines[8]
+    );
+}
+
+#[test]
+fn test_eip3155_error() {
+    let host = Rc::new(RefCell::new(StatefulHost::new_with(get_default_context())));
+    let scope = CallScope {
+        code: Code::builder().append("6001").append(OpCode::ADD).clone(),
+        to: default_address(),
+        ..CallScope::default()
+    };
+
+    let mut tracer = Eip3155Tracer::new(Vec::new()).with_memory();
+    Executor::new_with(host, Revision::Shanghai)
+        .with_tracer(&mut tracer)
+        .execute_raw_with(scope);
+
+    let lines = String::from_utf8(tracer.into_inner().unwrap()).unwrap();
+    let last = lines.lines().last().unwrap();
+    assert!(last.contains(r#""memory":"0x","memSize":0"#));
+    assert!(last.ends_with(r#""opName":"ADD","error":"StackUnderflow"}"#));
+}
+
+#[test]
+fn test_call_tracer() {
+    let callee = Address::from_low_u64_be(0xbbbb);
+    // revert with Error("nope")
+    let callee_code = Code::builder()
+        .append(OpCode::PUSH32)
+        .append("08c379a000000000000000000000000000000000000000000000000000000000")
+        .append("600052")           // PUSH1 0x00 MSTORE
+        .append("6020600452")       // PUSH1 0x20 PUSH1 0x04 MSTORE
+        .append("6004602452")       // PUSH1 0x04 PUSH1 0x24 MSTORE
+        .append(OpCode::PUSH32)
+        .append("6e6f706500000000000000000000000000000000000000000000000000000000")
+        .append("604452")           // PUSH1 0x44 MSTORE
+        .append("60646000")         // PUSH1 0x64 PUSH1 0x00
+        .append(OpCode::REVERT)
+        .clone();
+
+    let code = Code::builder()
+        .append("60006000600260006005")   // ret_size, ret_offset, args_size, args_offset, value
+        .append("61bbbb61ffff")           // address, gas
+        .append(OpCode::CALL)
+        .append(OpCode::STOP)
+        .clone();
+
+    let mut emu = EvmEmulator::new_stateful_with(get_default_context());
+    let mut tracer = CallTracer::new();
+    emu.with_to(default_address())
+        .mutate_scope(|scope| scope.code_address = default_address())
+        .with_account(default_address(), U256::from(100))
+        .with_contract_deployed2(callee, callee_code, U256::zero())
+        .with_code(code)
+        .with_gas_limit(100_000)
+        .with_gas_left(100_000)
+        .run_traced_as(Revision::Shanghai, &mut tracer)
+        .expect_status(StatusCode::Success);
+
+    let root = tracer.result().unwrap();
+    assert_eq!(CallKind::Call, root.kind);
+    assert_eq!(default_address(), root.to);
+    assert_eq!(100_000, root.gas);
+    assert_eq!(None, root.error);
+    assert_eq!(1, root.calls.len());
+
+    let child = &root.calls[0];
+    assert_eq!(default_address(), child.from);
+    assert_eq!(callee, child.to);
+    assert_eq!(Some(U256::from(5)), child.value);
+    assert_eq!(0xffff + 2300, child.gas);  // with call stipend
+    assert_eq!(Bytes::from(vec![0, 0]), child.input);
+    assert_eq!(100, child.output.len());
+    assert_eq!(Some("execution reverted".to_string()), child.error);
+    assert_eq!(Some("nope".to_string()), child.revert_reason);
+    assert!(child.calls.is_empty());
+    assert_eq!(root.gas_used, 3 * 7 + 3 + 2600 + 9000 - 2300 + child.gas_used); // push*7 + memory + CALL
+
+    let json = serde_json::to_value(root).unwrap();
+    assert_eq!("CALL", json["type"]);
+    assert_eq!(format!("{:?}", default_address()), json["to"]);
+    assert_eq!("0x186a0", json["gas"]);
+    assert!(json.get("error").is_none());
+    assert_eq!("0x5", json["calls"][0]["value"]);
+    assert_eq!("0x0000", json["calls"][0]["input"]);
+    assert_eq!("nope", json["calls"][0]["revertReason"]);
+}
+
+#[test]
+fn test_call_tracer_precompile() {
+    let ecrecover = Address::from_low_u64_be(0x01);
+    let code = Code::builder()
+        .append("60006000608060006000")   // ret_size, ret_offset, args_size, args_offset, value
+        .append("600161ffff")             // address, gas
+        .append(OpCode::CALL)
+        .append(OpCode::POP)
+        .append("60006000608060006000")
+        .append("60016064")               // not enough gas for ECRECOVER
+        .append(OpCode::CALL)
+        .append(OpCode::STOP)
+        .clone();
+
+    let mut emu = EvmEmulator::new_stateful_with(get_default_context());
+    let mut tracer = CallTracer::new();
+    emu.with_to(default_address())
+        .mutate_scope(|scope| scope.code_address = default_address())
+        .with_code(code)
+        .with_gas_limit(100_000)
+        .with_gas_left(100_000)
+        .run_traced_as(Revision::Shanghai, &mut tracer)
+        .expect_status(StatusCode::Success);
+
+    let root = tracer.result().unwrap();
+    assert_eq!(2, root.calls.len());
+
+    // v of the zero signature is invalid, so nothing is recovered.
+    let child = &root.calls[0];
+    assert_eq!(CallKind::Call, child.kind);
+    assert_eq!(default_address(), child.from);
+    assert_eq!(ecrecover, child.to);
+    assert_eq!(0xffff, child.gas);
+    assert_eq!(3000, child.gas_used);
+    assert_eq!(Bytes::from(vec![0; 0x80]), child.input);
+    assert!(child.output.is_empty());
+    assert_eq!(None, child.error);
+
+    let child = &root.calls[1];
+    assert_eq!(ecrecover, child.to);
+    assert_eq!(0x64, child.gas_used);
+    assert_eq!(Some("out of gas".to_string()), child.error);
+}
+
+#[test]
+fn test_call_tracer_failure() {
+    let mut emu = EvmEmulator::new_stateful_with(get_default_context());
+    let mut tracer = CallTracer::new();
+    emu.with_to(default_address())
+        .with_code(Code::builder().append(OpCode::JUMP).clone())
+        .with_gas_limit(1000)
+        .with_gas_left(1000)
+        .run_traced_as(Revision::Shanghai, &mut tracer)
+        .expect_status(StatusCode::Failure(FailureKind::StackUnderflow));
+
+    let root = tracer.result().unwrap();
+    assert_eq!(Some("stack underflow".to_string()), root.error);
+    assert_eq!(1000, root.gas_used);
+    assert_eq!(None, root.revert_reason);
+}
+
+#[test]
+fn test_prestate_tracer() {
+    let sender = Address::from_low_u64_be(0xaaaa);
+    let contract = Address::from_low_u64_be(0xcccc);
+    let code = Code::builder()
+        .append("602a600155")     // PUSH1 0x2a PUSH1 0x01 SSTORE
+        .append("600254")         // PUSH1 0x02 SLOAD
+        .append(OpCode::POP)
+        .clone();
+
+    let mut inner = StatefulHost::new_with(get_default_context());
+    inner.debug_deploy_contract2(sender, Code::empty(), U256::from(1000));
+    inner.debug_deploy_contract2(contract, code.clone(), U256::zero());
+    inner.debug_set_storage(contract, U256::from(2), U256::from(5));
+    let host = Rc::new(RefCell::new(PrestateTracer::new(inner)));
+
+    let tx = Transaction::Legacy(LegacyTransaction {
+        nonce: 0,
+        gas_price: U256::zero(),
+        gas_limit: 100_000,
+        to: Some(contract),
+        value: U256::from(7),
+        data: Bytes::default(),
+        signature: Signature::default(),
+    });
+    let output = Executor::new_with(host.clone(), Revision::Berlin).execute_transaction(&tx, sender);
+    assert_eq!(StatusCode::Success, output.status_code);
+
+    let prestate = host.borrow().prestate();
+    assert_eq!(vec![sender, contract], prestate.keys().copied().collect::<Vec<_>>());
+    assert_eq!(
+        AccountState { balance: Some(U256::from(1000)), ..AccountState::default() },
+        prestate[&sender]
+    );
+    let contract_state = &prestate[&contract];
+    assert_eq!(Some(U256::zero()), contract_state.balance);
+    assert_eq!(None, contract_state.nonce);
+    assert_eq!(Some(Bytes::from(code.0.clone())), contract_state.code);
+    assert_eq!(
+        vec![
+            (H256::from_low_u64_be(1), H256::zero()),
+            (H256::from_low_u64_be(2), H256::from_low_u64_be(5)),
+        ],
+        contract_state.storage.clone().into_iter().collect::<Vec<_>>()
+    );
+
+    let diff = host.borrow().diff();
+    assert_eq!(
+        AccountState { balance: Some(U256::from(993)), nonce: Some(1), ..AccountState::default() },
+        diff.post[&sender]
+    );
+    assert_eq!(
+        AccountState {
+            balance: Some(U256::from(7)),
+            storage: [(H256::from_low_u64_be(1), H256::from_low_u64_be(0x2a))].into_iter().collect(),
+            ..AccountState::default()
+        },
+        diff.post[&contract]
+    );
+    // unchanged slot is left out of the diff.
+    assert_eq!(
+        vec![(H256::from_low_u64_be(1), H256::zero())],
+        diff.pre[&contract].storage.clone().into_iter().collect::<Vec<_>>()
+    );
+
+    let json = serde_json::to_value(&diff).unwrap();
+    assert_eq!("0x3e8", json["pre"][format!("{:?}", sender)]["balance"]);
+    assert!(json["pre"][format!("{:?}", sender)].get("nonce").is_none());
+    assert_eq!(1, json["post"][format!("{:?}", sender)]["nonce"]);
+    assert_eq!(
+        format!("{:?}", H256::from_low_u64_be(0x2a)),
+        json["post"][format!("{:?}", contract)]["storage"][format!("{:?}", H256::from_low_u64_be(1))]
+    );
+}
+
+#[test]
+fn test_gas_profiler() {
+    let callee = Address::from_low_u64_be(0xbbbb);
+    let callee_code = Code::builder()
+        .append("6001600155")     // PUSH1 0x01 PUSH1 0x01 SSTORE
+        .clone();
+
+    // call the callee twice with calldata 0x12345678
+    let call = Code::builder()
+        .append("6000600060046000600061bbbb61ffff")   // ret, args, value, address, gas
+        .append(OpCode::CALL)
+        .append(OpCode::POP)
+        .clone();
+    let code = Code::builder()
+        .append("6312345678")     // PUSH4 0x12345678
+        .append("60e01b")         // PUSH1 0xe0 SHL
+        .append("600052")         // PUSH1 0x00 MSTORE
+        .append_code(&mut call.clone())
+        .append_code(&mut call.clone())
+        .clone();
+
+    let mut emu = EvmEmulator::new_stateful_with(get_default_context());
+    let mut profiler = GasProfiler::new();
+    let result = emu.with_to(default_address())
+        .mutate_scope(|scope| scope.code_address = default_address())
+        .with_contract_deployed2(callee, callee_code, U256::zero())
+        .with_code(code)
+        .with_gas_limit(100_000)
+        .with_gas_left(100_000)
+        .run_traced_as(Revision::Shanghai, &mut profiler);
+    assert_eq!(StatusCode::Success, result.output.status_code);
+    let gas_used = 100_000 - result.output.gas_left;
+
+    let total: i64 = profiler.opcodes.values().map(|stat| stat.gas).sum();
+    assert_eq!(gas_used, total);
+
+    // cold then warm account access, excluding the gas of the callee.
+    assert_eq!(GasStat { count: 2, gas: 2600 + 100 }, profiler.opcodes[&OpCode::CALL.to_u8()]);
+    // cold then warm storage access of a slot set the first time.
+    assert_eq!(GasStat { count: 2, gas: 22100 + 100 }, profiler.opcodes[&OpCode::SSTORE.to_u8()]);
+    assert_eq!(GasStat { count: 2, gas: 22100 + 100 + 4 * 3 }, profiler.contracts[&callee]);
+    assert_eq!(GasStat { count: 2, gas: 22100 + 100 + 4 * 3 }, profiler.selectors[&[0x12, 0x34, 0x56, 0x78]]);
+    assert_eq!(1, profiler.contracts[&default_address()].count);
+    assert_eq!(GasStat { count: 1, gas: 2600 }, profiler.pcs[&(default_address(), 27)]);
+
+    let folded = profiler.folded_stacks();
+    assert_eq!(
+        format!(
+            "{:?} {}\n{:?};{:?}.0x12345678 {}\n",
+            default_address(), gas_used - 22100 - 100 - 4 * 3,
+            default_address(), callee, 22100 + 100 + 4 * 3,
+        ),
+        folded
+    );
+
+    let table = profiler.table();
+    assert!(table.lines().nth(1).unwrap().starts_with("SSTORE"));
+}
+
+#[test]
+fn test_recorder() {
+    let callee = Address::from_low_u64_be(0xbbbb);
+    let callee_code = Code::builder()
+        .append("60016000")     // PUSH1 0x01 PUSH1 0x00
+        .append(OpCode::MSTORE8)
+        .append("60016000")     // PUSH1 0x01 PUSH1 0x00
+        .append(OpCode::REVERT)
+        .clone();
+
+    let code = Code::builder()
+        .append("6001600060006000600061bbbb61ffff") // ret, args, value, address, gas
+        .append(OpCode::CALL)
+        .append("6001600255")   // PUSH1 0x01 PUSH1 0x02 SSTORE
+        .clone();
+
+    let mut emu = EvmEmulator::new_stateful_with(get_default_context());
+    let mut recorder = Recorder::new();
+    let mut tracer = RecordingTracer::default();
+    emu.with_to(default_address())
+        .with_contract_deployed2(callee, callee_code, U256::zero())
+        .with_code(code)
+        .with_default_gas()
+        .run_traced_as(Revision::Shanghai, &mut (&mut recorder, &mut tracer))
+        .expect_status(StatusCode::Success);
+
+    let recording = recorder.into_recording();
+    assert_eq!(tracer.states.len(), recording.len());
+    assert_eq!(2, recording.frames.len());
+    assert_eq!(vec![(default_address(), U256::from(2), U256::from(1))], recording.steps.last().unwrap().storage);
+
+    // the call pushes its result and copies the revert data.
+    let call = recording.steps.iter().find(|step| step.opcode == OpCode::CALL).unwrap();
+    assert_eq!(vec![U256::zero()], call.stack.pushed);
+    assert_eq!(Some(vec![1]), call.memory.as_ref().map(|delta| delta.new.clone()));
+
+    let state = |index: usize| {
+        let (stack, memory) = recording.state_at(index);
+        (stack.0.to_vec(), memory.0)
+    };
+    for index in 0..recording.len() {
+        assert_eq!(tracer.states[index], state(index), "step {}", index);
+    }
+
+    let mut cursor = recording.cursor();
+    loop {
+        assert_eq!(tracer.states[cursor.index()], (cursor.stack().0.to_vec(), cursor.memory().0.clone()));
+        if !cursor.step_back() {
+            break;
+        }
+    }
+    assert_eq!(0, cursor.index());
+    while cursor.step_forward() {
+        assert_eq!(tracer.states[cursor.index()], (cursor.stack().0.to_vec(), cursor.memory().0.clone()));
+    }
+    assert_eq!(recording.len() - 1, cursor.index());
+}
@@ -0,0 +1,299 @@
+use bytes::Bytes;
+use ethereum_types::{U256, H256, Address};
+use evmstar::model::{
+    code::{
+        Code, Append,
+    },
+    opcode::OpCode,
+    evmc::{
+        StatusCode, FailureKind,
+        TxContext,
+    },
+    transaction::*,
+};
+use evmstar::utils::rlp::RlpError;
+use evmstar::emulator::EvmEmulator;
+use hex::decode;
+use hex_literal::hex;
+
+fn address_sender() -> Address {
+    Address::from_low_u64_be(0x1234)
+}
+fn address_contract() -> Address {
+    Address::from_low_u64_be(0xaaaaaaaa)
+}
+fn address_coinbase() -> Address {
+    Address::from_low_u64_be(0xabcd)
+}
+
+fn get_default_context() -> TxContext {
+    TxContext {
+        gas_price: U256::zero(),
+        origin: Address::zero(),
+        coinbase: address_coinbase(),
+        block_number: 0x1111,
+        block_timestamp: 0x2222,
+        gas_limit: 0x3333,
+        base_fee: U256::from(10),
+        chain_id: U256::from(0x01),
+        difficulty: U256::from(0x5555),
+        excess_blob_gas: 0,
+        blob_hashes: vec![],
+    }
+}
+
+fn signature() -> Signature {
+    Signature {
+        v: 1,
+        r: U256::from(0x1111),
+        s: U256::from(0x2222),
+    }
+}
+
+fn access_list() -> Vec<AccessListItem> {
+    vec![
+        AccessListItem {
+            address: Address::from_low_u64_be(0xaa),
+            storage_keys: vec![H256::from_low_u64_be(1), H256::from_low_u64_be(2)],
+        },
+        AccessListItem {
+            address: Address::from_low_u64_be(0xbb),
+            storage_keys: vec![],
+        },
+    ]
+}
+
+#[test]
+fn test_legacy_eip155() {
+    // example in https://eips.ethereum.org/EIPS/eip-155
+    let raw = "0xf86c098504a817c800825208943535353535353535353535353535353535353535880de0b6b3a76400008025a028ef61340bd939bc2195fe537567866003e1a15d3c71ff63e1590620aa636276a067cbe9d8997f761aecb703304b3800ccf555c9f3dc64214b297fb1966a3b6d83";
+    let tx = Transaction::from_hex(raw).unwrap();
+
+    assert_eq!(LEGACY_TX_TYPE, tx.tx_type());
+    assert_eq!(9, tx.nonce());
+    assert_eq!(Some(U256::from(20_000_000_000u64)), tx.gas_price());
+    assert_eq!(21000, tx.gas_limit());
+    assert_eq!(Some(Address::from(hex!("3535353535353535353535353535353535353535"))), tx.to());
+    assert_eq!(U256::from(1_000_000_000_000_000_000u64), tx.value());
+    assert!(tx.data().is_empty());
+    assert_eq!(Some(U256::one()), tx.chain_id());
+    assert_eq!(37, tx.signature().v);
+    assert_eq!(
+        H256::from(hex!("daf5a779ae972f972197303d7b574746c7ef83eadac0f2791ad23db92e4c8e53")),
+        tx.signing_hash()
+    );
+    assert_eq!(decode(&raw[2..]).unwrap(), tx.encode());
+}
+
+#[test]
+fn test_legacy_without_chain_id() {
+    let tx = Transaction::Legacy(LegacyTransaction {
+        nonce: 9,
+        gas_price: U256::from(20_000_000_000u64),
+        gas_limit: 21000,
+        to: Some(Address::from(hex!("3535353535353535353535353535353535353535"))),
+        value: U256::from(1_000_000_000_000_000_000u64),
+        data: Bytes::default(),
+        signature: Signature { v: 27, r: U256::one(), s: U256::one() },
+    });
+    assert_eq!(None, tx.chain_id());
+
+    // signing data of pre EIP-155 transaction has only 6 fields.
+    let signing_data = decode("e9098504a817c800825208943535353535353535353535353535353535353535880de0b6b3a764000080").unwrap();
+    assert_eq!(evmstar::utils::keccak256(&signing_data), tx.signing_hash());
+    assert_eq!(tx, Transaction::decode(&tx.encode()).unwrap());
+}
+
+#[test]
+fn test_dynamic_fee_decode() {
+    let raw = "02e2018001028252089400000000000000000000000000000000000000018080c0800101";
+    let tx = Transaction::from_hex(raw).unwrap();
+
+    let expected = Transaction::DynamicFee(DynamicFeeTransaction {
+        chain_id: U256::one(),
+        nonce: 0,
+        max_priority_fee_per_gas: U256::from(1),
+        max_fee_per_gas: U256::from(2),
+        gas_limit: 21000,
+        to: Some(Address::from_low_u64_be(1)),
+        value: U256::zero(),
+        data: Bytes::default(),
+        access_list: vec![],
+        signature: Signature { v: 0, r: U256::one(), s: U256::one() },
+    });
+    assert_eq!(expected, tx);
+    assert_eq!(decode(raw).unwrap(), tx.encode());
+
+    let signing_data = decode("02df018001028252089400000000000000000000000000000000000000018080c0").unwrap();
+    assert_eq!(evmstar::utils::keccak256(&signing_data), tx.signing_hash());
+    assert_eq!(evmstar::utils::keccak256(&decode(raw).unwrap()), tx.hash());
+}
+
+#[test]
+fn test_typed_transaction_roundtrip() {
+    let txs = vec![
+        Transaction::AccessList(AccessListTransaction {
+            chain_id: U256::from(5),
+            nonce: 1,
+            gas_price: U256::from(1_000_000_000u64),
+            gas_limit: 100_000,
+            to: None,
+            value: U256::from(10),
+            data: Bytes::from(vec![0x60, 0x00]),
+            access_list: access_list(),
+            signature: signature(),
+        }),
+        Transaction::DynamicFee(DynamicFeeTransaction {
+            chain_id: U256::from(5),
+            nonce: 2,
+            max_priority_fee_per_gas: U256::from(2),
+            max_fee_per_gas: U256::from(100),
+            gas_limit: 100_000,
+            to: Some(address_contract()),
+            value: U256::zero(),
+            data: Bytes::from(vec![0xab; 100]),
+            access_list: access_list(),
+            signature: signature(),
+        }),
+        Transaction::Blob(BlobTransaction {
+            chain_id: U256::from(5),
+            nonce: 3,
+            max_priority_fee_per_gas: U256::from(2),
+            max_fee_per_gas: U256::from(100),
+            gas_limit: 100_000,
+            to: address_contract(),
+            value: U256::zero(),
+            data: Bytes::default(),
+            access_list: vec![],
+            max_fee_per_blob_gas: U256::from(7),
+            blob_versioned_hashes: vec![H256::repeat_byte(0x01), H256::repeat_byte(0x02)],
+            signature: signature(),
+        }),
+        Transaction::SetCode(SetCodeTransaction {
+            chain_id: U256::from(5),
+            nonce: 4,
+            max_priority_fee_per_gas: U256::from(2),
+            max_fee_per_gas: U256::from(100),
+            gas_limit: 100_000,
+            to: address_contract(),
+            value: U256::zero(),
+            data: Bytes::default(),
+            access_list: vec![],
+            authorization_list: vec![Authorization {
+                chain_id: U256::zero(),
+                address: Address::from_low_u64_be(0xcc),
+                nonce: 7,
+                signature: signature(),
+            }],
+            signature: signature(),
+        }),
+    ];
+
+    for tx in txs {
+        let raw = tx.encode();
+        assert_eq!(tx.tx_type(), raw[0]);
+        assert_eq!(tx, Transaction::decode(&raw).unwrap());
+        assert_ne!(tx.hash(), tx.signing_hash());
+    }
+}
+
+#[test]
+fn test_access_list_conversion() {
+    let tx = Transaction::DynamicFee(DynamicFeeTransaction {
+        access_list: access_list(),
+        ..Default::default()
+    });
+    let access_list = tx.access_list();
+    assert_eq!(2, access_list.get_account_count());
+    assert_eq!(2, access_list.get_storage_count());
+    assert_eq!(
+        vec![U256::from(1), U256::from(2)],
+        access_list.map.get(&Address::from_low_u64_be(0xaa)).unwrap().1
+    );
+}
+
+#[test]
+fn test_decode_invalid_transaction() {
+    assert_eq!(Err(TransactionError::InvalidHex), Transaction::from_hex("0xzz"));
+    assert_eq!(Err(TransactionError::UnsupportedType(0x7f)), Transaction::from_hex("7fc0"));
+    assert_eq!(Err(TransactionError::Rlp(RlpError::InvalidItemCount)), Transaction::from_hex("02c3010203"));
+    assert_eq!(Err(TransactionError::Rlp(RlpError::UnexpectedEnd)), Transaction::from_hex(""));
+}
+
+#[test]
+fn test_execute_dynamic_fee_transaction() {
+    let code = Code::builder()
+        .append(OpCode::GASPRICE)
+        .append(OpCode::PUSH1)
+        .append("00")
+        .append(OpCode::MSTORE)
+        .append(OpCode::PUSH1)
+        .append("20")
+        .append(OpCode::PUSH1)
+        .append("00")
+        .append(OpCode::RETURN)
+        .clone();
+
+    let tx = Transaction::DynamicFee(DynamicFeeTransaction {
+        chain_id: U256::one(),
+        nonce: 0,
+        max_priority_fee_per_gas: U256::from(2),
+        max_fee_per_gas: U256::from(100),
+        gas_limit: 100_000,
+        to: Some(address_contract()),
+        value: U256::from(1000),
+        data: Bytes::default(),
+        access_list: vec![],
+        signature: signature(),
+    });
+
+    let mut tester = EvmEmulator::new_stateful_with(get_default_context());
+    let result = tester
+        .with_account(address_sender(), U256::from(100_000_000))
+        .with_contract_deployed2(address_contract(), code, U256::zero())
+        .run_transaction(&tx, address_sender());
+
+    result.expect_status(StatusCode::Success)
+        .expect_output("000000000000000000000000000000000000000000000000000000000000000c")
+        .expect_gas(17)
+        .expect_balance(address_sender(), U256::from(100_000_000 - 1000 - 17 * 12))
+        .expect_balance(address_contract(), U256::from(1000))
+        .expect_balance(address_coinbase(), U256::from(17 * 2))
+        .expect_burnt(U256::from(17 * 10));
+}
+
+#[test]
+fn test_execute_failed_transaction() {
+    // SSTORE and LOG0, then fail with a stack underflow.
+    let code = Code::builder()
+        .append("6001600055")   // PUSH1 0x01 PUSH1 0x00 SSTORE
+        .append("60006000a0")   // PUSH1 0x00 PUSH1 0x00 LOG0
+        .append(OpCode::POP)
+        .clone();
+
+    let tx = Transaction::DynamicFee(DynamicFeeTransaction {
+        chain_id: U256::one(),
+        nonce: 0,
+        max_priority_fee_per_gas: U256::from(2),
+        max_fee_per_gas: U256::from(100),
+        gas_limit: 100_000,
+        to: Some(address_contract()),
+        value: U256::from(1000),
+        data: Bytes::default(),
+        access_list: vec![],
+        signature: signature(),
+    });
+
+    let mut tester = EvmEmulator::new_stateful_with(get_default_context());
+    let result = tester
+        .with_account(address_sender(), U256::from(100_000_000))
+        .with_contract_deployed2(address_contract(), code, U256::zero())
+        .run_transaction(&tx, address_sender());
+
+    // the whole gas is paid, and nothing else remains of the execution.
+    result.expect_status(StatusCode::Failure(FailureKind::StackUnderflow))
+        .expect_gas(100_000)
+        .expect_storage(address_contract(), U256::zero(), U256::zero())
+        .expect_balance(address_sender(), U256::from(100_000_000 - 100_000 * 12))
+        .expect_balance(address_contract(), U256::zero());
+}
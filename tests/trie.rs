@@ -0,0 +1,149 @@
+use ethereum_types::{U256, Address};
+use bytes::Bytes;
+use evmstar::model::{
+    code::Code,
+    proof::AccountProof,
+    transaction::{transactions_root, Transaction},
+};
+use evmstar::host::{Host, stateful::StatefulHost};
+use evmstar::utils::{
+    keccak256,
+    trie::{EMPTY_ROOT, ProofError, ordered_trie_root},
+};
+
+fn address_contract() -> Address {
+    Address::from_low_u64_be(0xaaaaaaaa)
+}
+
+#[test]
+fn test_empty_state_root() {
+    let host = StatefulHost::new();
+    assert_eq!(EMPTY_ROOT, host.state_root());
+    assert_eq!(EMPTY_ROOT, host.storage_root(address_contract()));
+    assert_eq!(EMPTY_ROOT, transactions_root(&[]));
+}
+
+#[test]
+fn test_storage_root() {
+    let mut host = StatefulHost::new();
+    host.debug_deploy_contract2(address_contract(), Code::empty(), U256::from(1));
+    let root = host.state_root();
+    assert_ne!(EMPTY_ROOT, root);
+    assert_eq!(EMPTY_ROOT, host.storage_root(address_contract()));
+
+    // slot holding zero is not in the trie.
+    host.debug_set_storage(address_contract(), U256::from(1), U256::zero());
+    assert_eq!(EMPTY_ROOT, host.storage_root(address_contract()));
+    assert_eq!(root, host.state_root());
+
+    host.debug_set_storage(address_contract(), U256::from(1), U256::from(0x42));
+    let storage_root = host.storage_root(address_contract());
+    assert_ne!(EMPTY_ROOT, storage_root);
+    assert_ne!(root, host.state_root());
+
+    // clearing the slot restores both roots.
+    host.debug_set_storage(address_contract(), U256::from(1), U256::zero());
+    assert_eq!(EMPTY_ROOT, host.storage_root(address_contract()));
+    assert_eq!(root, host.state_root());
+}
+
+#[test]
+fn test_state_root_depends_on_account() {
+    let mut host = StatefulHost::new();
+    host.debug_deploy_contract2(address_contract(), Code::empty(), U256::from(1));
+    let root = host.state_root();
+
+    let mut other = StatefulHost::new();
+    other.debug_deploy_contract2(address_contract(), Code::empty(), U256::from(2));
+    assert_ne!(root, other.state_root());
+
+    let mut other = StatefulHost::new();
+    other.debug_deploy_contract2(address_contract(), Code::from("00"), U256::from(1));
+    assert_ne!(root, other.state_root());
+}
+
+#[test]
+fn test_transactions_root() {
+    // https://eips.ethereum.org/EIPS/eip-155
+    let tx = Transaction::from_hex("f86c098504a817c800825208943535353535353535353535353535353535353535880de0b6b3a76400008025a028ef61340bd939bc2195fe537567866003e1a15d3c71ff63e1590620aa636276a067cbe9d8997f761aecb703304b3800ccf555c9f3dc64214b297fb1966a3b6d83").unwrap();
+    let root = transactions_root(std::slice::from_ref(&tx));
+    assert_eq!(ordered_trie_root(vec![tx.encode()]), root);
+    assert_ne!(root, transactions_root(&[tx.clone(), tx]));
+}
+
+fn host_with_storage() -> StatefulHost {
+    let mut host = StatefulHost::new();
+    for i in 0..16u64 {
+        let address = Address::from_low_u64_be(0x1000 + i);
+        host.debug_deploy_contract2(address, Code::from("6000"), U256::from(i + 1));
+    }
+    host.debug_deploy_contract2(address_contract(), Code::from("6001"), U256::from(100));
+    for i in 1..32u64 {
+        host.debug_set_storage(address_contract(), U256::from(i), U256::from(i * 0x100));
+    }
+    host
+}
+
+#[test]
+fn test_account_proof() {
+    let host = host_with_storage();
+    let state_root = host.state_root();
+
+    let keys = [U256::from(1), U256::from(31), U256::from(32), U256::MAX];
+    let proof = host.get_proof(address_contract(), &keys);
+    assert_eq!(U256::from(100), proof.balance);
+    assert_eq!(keccak256(&[0x60, 0x01]), proof.code_hash);
+    assert_eq!(host.storage_root(address_contract()), proof.storage_hash);
+    assert_eq!(U256::from(0x100), proof.storage_proof[0].value);
+    assert_eq!(U256::from(0x1f00), proof.storage_proof[1].value);
+    assert_eq!(U256::zero(), proof.storage_proof[2].value);
+    assert_eq!(U256::zero(), proof.storage_proof[3].value);
+    assert_eq!(Ok(()), proof.verify(state_root));
+
+    // proofs do not hold against another state.
+    let mut other = host_with_storage();
+    other.debug_set_storage(address_contract(), U256::from(1), U256::from(0x101));
+    assert!(proof.verify(other.state_root()).is_err());
+    assert_eq!(Ok(()), other.get_proof(address_contract(), &keys).verify(other.state_root()));
+}
+
+#[test]
+fn test_account_proof_of_absence() {
+    let host = host_with_storage();
+    let proof = host.get_proof(Address::from_low_u64_be(0xdead), &[U256::one()]);
+    assert!(proof.is_empty());
+    assert_eq!(EMPTY_ROOT, proof.storage_hash);
+    assert_eq!(Ok(()), proof.verify(host.state_root()));
+
+    let empty = StatefulHost::new();
+    let proof = empty.get_proof(address_contract(), &[]);
+    assert_eq!(Ok(()), proof.verify(EMPTY_ROOT));
+}
+
+#[test]
+fn test_account_proof_tampered() {
+    let host = host_with_storage();
+    let state_root = host.state_root();
+    let proof = host.get_proof(address_contract(), &[U256::from(1)]);
+
+    let tampered = AccountProof { balance: U256::from(101), ..proof.clone() };
+    assert_eq!(Err(ProofError::ValueMismatch), tampered.verify(state_root));
+
+    let mut tampered = proof.clone();
+    tampered.storage_proof[0].value = U256::from(0x101);
+    assert_eq!(Err(ProofError::ValueMismatch), tampered.verify(state_root));
+
+    // claiming an existing account is absent.
+    let tampered = AccountProof {
+        nonce: 0,
+        balance: U256::zero(),
+        storage_hash: EMPTY_ROOT,
+        code_hash: keccak256(&[]),
+        ..proof.clone()
+    };
+    assert_eq!(Err(ProofError::ValueMismatch), tampered.verify(state_root));
+
+    let mut tampered = proof;
+    tampered.account_proof[0] = Bytes::from_static(&[0xc0]);
+    assert_eq!(Err(ProofError::HashMismatch), tampered.verify(state_root));
+}
@@ -0,0 +1,181 @@
+use std::rc::Rc;
+use std::cell::RefCell;
+
+use bytes::Bytes;
+use ethereum_types::{U256, Address};
+use evmstar::model::{
+    code::{
+        Code, Append,
+    },
+    opcode::OpCode,
+    evmc::{
+        StatusCode, FailureKind,
+        TxContext, Output,
+    },
+    proof::StateWitness,
+    revision::Revision,
+    transaction::*,
+};
+use evmstar::executor::executor::Executor;
+use evmstar::host::{
+    Host,
+    stateful::StatefulHost,
+    witness::{WitnessHost, WitnessError},
+};
+
+fn address_sender() -> Address {
+    Address::from_low_u64_be(0x1234)
+}
+fn address_contract() -> Address {
+    Address::from_low_u64_be(0xaaaaaaaa)
+}
+fn address_coinbase() -> Address {
+    Address::from_low_u64_be(0xabcd)
+}
+
+fn get_default_context() -> TxContext {
+    TxContext {
+        gas_price: U256::zero(),
+        origin: Address::zero(),
+        coinbase: address_coinbase(),
+        block_number: 0x1111,
+        block_timestamp: 0x2222,
+        gas_limit: 100_000,
+        base_fee: U256::from(10),
+        chain_id: U256::from(0x01),
+        difficulty: U256::from(0x5555),
+        excess_blob_gas: 0,
+        blob_hashes: vec![],
+    }
+}
+
+/// increments the storage slot given as calldata.
+fn increment_code() -> Code {
+    Code::builder()
+        .append(OpCode::PUSH1)
+        .append("00")
+        .append(OpCode::CALLDATALOAD)
+        .append(OpCode::DUP1)
+        .append(OpCode::SLOAD)
+        .append(OpCode::PUSH1)
+        .append("01")
+        .append(OpCode::ADD)
+        .append(OpCode::SWAP1)
+        .append(OpCode::SSTORE)
+        .clone()
+}
+
+fn transaction(to: Address, slot: u64) -> Transaction {
+    let mut data = [0u8; 32];
+    U256::from(slot).to_big_endian(&mut data);
+    Transaction::DynamicFee(DynamicFeeTransaction {
+        chain_id: U256::one(),
+        nonce: 0,
+        max_priority_fee_per_gas: U256::from(2),
+        max_fee_per_gas: U256::from(100),
+        gas_limit: 100_000,
+        to: Some(to),
+        value: U256::from(1),
+        data: Bytes::from(data.to_vec()),
+        access_list: vec![],
+        signature: Signature::default(),
+    })
+}
+
+fn full_host() -> StatefulHost {
+    let mut host = StatefulHost::new_with(get_default_context());
+    host.debug_deploy_contract2(address_sender(), Code::empty(), U256::from(100_000_000));
+    host.debug_deploy_contract2(address_contract(), increment_code(), U256::zero());
+    for i in 0..16u64 {
+        host.debug_deploy_contract2(Address::from_low_u64_be(0x1000 + i), increment_code(), U256::from(i));
+        host.debug_set_storage(address_contract(), U256::from(i), U256::from(0x100 + i));
+    }
+    host
+}
+
+fn execute(host: Rc<RefCell<dyn Host>>, tx: &Transaction) -> Output {
+    let mut executor = Executor::new_with_execution_cost(host, Revision::London);
+    executor.execute_transaction(tx, address_sender())
+}
+
+fn witness_of(tx: &Transaction) -> StateWitness {
+    let mut host = full_host();
+    host.record_witness();
+    let host = Rc::new(RefCell::new(host));
+    execute(host.clone(), tx);
+    let witness = host.borrow_mut().take_witness().unwrap();
+    witness
+}
+
+#[test]
+fn test_stateless_execution() {
+    let tx = transaction(address_contract(), 3);
+
+    let full = Rc::new(RefCell::new(full_host()));
+    let state_root = full.borrow().state_root();
+    let expected = execute(full.clone(), &tx);
+    assert_eq!(StatusCode::Success, expected.status_code);
+
+    let witness = witness_of(&tx);
+    assert_eq!(state_root, witness.state_root);
+    assert_eq!(vec![Bytes::from(increment_code().0)], witness.codes);
+
+    let stateless = Rc::new(RefCell::new(WitnessHost::new(get_default_context(), &witness)));
+    let output = execute(stateless.clone(), &tx);
+    assert_eq!(expected, output);
+    assert_eq!(Ok(()), stateless.borrow().check());
+
+    let (full, stateless) = (full.borrow(), stateless.borrow());
+    assert_eq!(U256::from(0x104), stateless.debug_get_storage(address_contract(), U256::from(3)));
+    for address in [address_sender(), address_contract(), address_coinbase()] {
+        assert_eq!(full.get_balance(address), stateless.get_balance(address));
+    }
+}
+
+#[test]
+fn test_stateless_execution_of_absent_slot() {
+    // slot which holds zero is proven absent.
+    let tx = transaction(address_contract(), 0x20);
+    let witness = witness_of(&tx);
+
+    let stateless = Rc::new(RefCell::new(WitnessHost::new(get_default_context(), &witness)));
+    let output = execute(stateless.clone(), &tx);
+    assert_eq!(StatusCode::Success, output.status_code);
+    assert_eq!(Ok(()), stateless.borrow().check());
+    assert_eq!(U256::one(), stateless.borrow().debug_get_storage(address_contract(), U256::from(0x20)));
+}
+
+#[test]
+fn test_stateless_execution_of_unwitnessed_storage() {
+    let witness = witness_of(&transaction(address_contract(), 3));
+
+    let stateless = Rc::new(RefCell::new(WitnessHost::new(get_default_context(), &witness)));
+    let output = execute(stateless.clone(), &transaction(address_contract(), 5));
+    assert!(matches!(output.status_code, StatusCode::Failure(FailureKind::InternalError(_))));
+    assert_eq!(
+        Err(WitnessError::MissingStorage { address: address_contract(), key: U256::from(5) }),
+        stateless.borrow().check()
+    );
+}
+
+#[test]
+fn test_stateless_execution_of_unwitnessed_account() {
+    let witness = witness_of(&transaction(address_contract(), 3));
+
+    let other = Address::from_low_u64_be(0x1007);
+    let stateless = Rc::new(RefCell::new(WitnessHost::new(get_default_context(), &witness)));
+    let output = execute(stateless.clone(), &transaction(other, 3));
+    assert!(matches!(output.status_code, StatusCode::Failure(FailureKind::InternalError(_))));
+    assert_eq!(Err(WitnessError::MissingAccount(other)), stateless.borrow().check());
+}
+
+#[test]
+fn test_stateless_execution_without_code() {
+    let mut witness = witness_of(&transaction(address_contract(), 3));
+    witness.codes.clear();
+
+    let stateless = Rc::new(RefCell::new(WitnessHost::new(get_default_context(), &witness)));
+    let output = execute(stateless.clone(), &transaction(address_contract(), 3));
+    assert!(matches!(output.status_code, StatusCode::Failure(FailureKind::InternalError(_))));
+    assert_eq!(Err(WitnessError::MissingCode(address_contract())), stateless.borrow().check());
+}